@@ -0,0 +1,8 @@
+ALTER TABLE wishes ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+UPDATE wishes SET position = ordered.position
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY wishlist_id ORDER BY id) - 1 AS position
+    FROM wishes
+) AS ordered
+WHERE wishes.id = ordered.id;
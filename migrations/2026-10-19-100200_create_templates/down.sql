@@ -0,0 +1,3 @@
+DROP TABLE template_products;
+
+DROP TABLE templates;
@@ -0,0 +1,13 @@
+CREATE TABLE templates (
+    id SERIAL PRIMARY KEY,
+    title VARCHAR NOT NULL,
+    description VARCHAR
+);
+
+CREATE TABLE template_products (
+    id SERIAL PRIMARY KEY,
+    template_id INTEGER NOT NULL,
+    FOREIGN KEY(template_id) REFERENCES templates(id) ON DELETE CASCADE,
+    product_id INTEGER NOT NULL REFERENCES products(id),
+    position INTEGER NOT NULL DEFAULT 0
+);
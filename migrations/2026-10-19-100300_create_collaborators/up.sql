@@ -0,0 +1,9 @@
+CREATE TABLE collaborators (
+    id SERIAL PRIMARY KEY,
+    wishlist_id INTEGER NOT NULL,
+    FOREIGN KEY(wishlist_id) REFERENCES wishlists(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR NOT NULL DEFAULT 'EDITOR',
+    UNIQUE (wishlist_id, user_id)
+);
@@ -0,0 +1,8 @@
+CREATE TABLE share_links (
+    id SERIAL PRIMARY KEY,
+    wishlist_id INTEGER NOT NULL,
+    FOREIGN KEY(wishlist_id) REFERENCES wishlists(id) ON DELETE CASCADE,
+    token VARCHAR NOT NULL UNIQUE,
+    expires_at TIMESTAMP,
+    revoked BOOLEAN NOT NULL DEFAULT FALSE
+);
@@ -0,0 +1,5 @@
+ALTER TABLE wishlists ADD COLUMN published BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE wishlists SET published = TRUE WHERE visibility = 'PUBLIC';
+
+ALTER TABLE wishlists DROP COLUMN visibility;
@@ -0,0 +1,5 @@
+ALTER TABLE wishlists ADD COLUMN visibility VARCHAR NOT NULL DEFAULT 'PRIVATE';
+
+UPDATE wishlists SET visibility = 'PUBLIC' WHERE published;
+
+ALTER TABLE wishlists DROP COLUMN published;
@@ -0,0 +1,5 @@
+DROP INDEX products_review_status_idx;
+
+ALTER TABLE products
+  DROP COLUMN submitted_by,
+  DROP COLUMN review_status;
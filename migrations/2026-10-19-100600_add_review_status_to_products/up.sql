@@ -0,0 +1,6 @@
+-- Products already in the catalog were added by admins
+ALTER TABLE products
+  ADD COLUMN review_status VARCHAR NOT NULL DEFAULT 'APPROVED',
+  ADD COLUMN submitted_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX products_review_status_idx ON products (review_status);
@@ -0,0 +1,12 @@
+DROP INDEX wishlists_search_en_idx;
+DROP INDEX wishlists_search_es_idx;
+DROP INDEX products_search_en_idx;
+DROP INDEX products_search_es_idx;
+
+ALTER TABLE wishlists
+  DROP COLUMN search_en,
+  DROP COLUMN search_es;
+
+ALTER TABLE products
+  DROP COLUMN search_en,
+  DROP COLUMN search_es;
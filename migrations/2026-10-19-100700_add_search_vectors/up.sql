@@ -0,0 +1,28 @@
+-- Generated columns are computed for the existing rows when added, and kept
+-- up to date by postgres on every insert or update.
+-- They are left out of `src/schema.rs`: diesel has no tsvector type and they
+-- are only read by the raw search query.
+ALTER TABLE products
+  ADD COLUMN search_es tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('spanish', coalesce(name, '')), 'A') ||
+    setweight(to_tsvector('spanish', coalesce(description, '')), 'B')
+  ) STORED,
+  ADD COLUMN search_en tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(description, '')), 'B')
+  ) STORED;
+
+ALTER TABLE wishlists
+  ADD COLUMN search_es tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('spanish', coalesce(title, '')), 'A') ||
+    setweight(to_tsvector('spanish', coalesce(description, '')), 'B')
+  ) STORED,
+  ADD COLUMN search_en tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(description, '')), 'B')
+  ) STORED;
+
+CREATE INDEX products_search_es_idx ON products USING GIN (search_es);
+CREATE INDEX products_search_en_idx ON products USING GIN (search_en);
+CREATE INDEX wishlists_search_es_idx ON wishlists USING GIN (search_es);
+CREATE INDEX wishlists_search_en_idx ON wishlists USING GIN (search_en);
@@ -0,0 +1,7 @@
+DROP INDEX products_category_id_idx;
+
+ALTER TABLE products DROP COLUMN category_id;
+
+DROP TABLE product_tags;
+DROP TABLE tags;
+DROP TABLE categories;
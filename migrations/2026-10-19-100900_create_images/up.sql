@@ -0,0 +1,14 @@
+-- An image belongs either to a product or, as its cover, to a wishlist
+CREATE TABLE images (
+  id SERIAL PRIMARY KEY,
+  product_id INTEGER REFERENCES products(id) ON DELETE CASCADE,
+  wishlist_id INTEGER REFERENCES wishlists(id) ON DELETE CASCADE,
+  content_type VARCHAR NOT NULL,
+  path VARCHAR NOT NULL,
+  thumbnail_path VARCHAR NOT NULL,
+  CHECK ((product_id IS NULL) <> (wishlist_id IS NULL))
+);
+
+CREATE INDEX images_product_id_idx ON images (product_id);
+
+CREATE UNIQUE INDEX images_wishlist_id_idx ON images (wishlist_id);
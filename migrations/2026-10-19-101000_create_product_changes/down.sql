@@ -0,0 +1,3 @@
+ALTER TABLE wishes DROP COLUMN original_price;
+
+DROP TABLE product_changes;
@@ -0,0 +1,21 @@
+-- Every change of a product price or availability, with the values it replaced
+CREATE TABLE product_changes (
+  id SERIAL PRIMARY KEY,
+  product_id INTEGER NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+  previous_price REAL NOT NULL,
+  price REAL NOT NULL,
+  previous_available BOOLEAN NOT NULL,
+  available BOOLEAN NOT NULL,
+  changed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX product_changes_product_id_idx ON product_changes (product_id);
+
+-- The price of the product when it was wished
+ALTER TABLE wishes ADD COLUMN original_price REAL;
+
+UPDATE wishes SET original_price = products.price
+FROM products
+WHERE products.id = wishes.product_id;
+
+ALTER TABLE wishes ALTER COLUMN original_price SET NOT NULL;
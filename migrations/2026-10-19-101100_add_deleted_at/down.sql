@@ -0,0 +1,3 @@
+ALTER TABLE wishes DROP COLUMN deleted_at;
+ALTER TABLE wishlists DROP COLUMN deleted_at;
+ALTER TABLE products DROP COLUMN deleted_at;
@@ -0,0 +1,8 @@
+-- Deleted rows are kept until purged, so they can be restored and keep their sponsors
+ALTER TABLE products ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE wishlists ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE wishes ADD COLUMN deleted_at TIMESTAMP;
+
+CREATE INDEX products_deleted_at_idx ON products (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX wishlists_deleted_at_idx ON wishlists (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX wishes_deleted_at_idx ON wishes (deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,13 @@
+DROP TRIGGER set_updated_at ON followers;
+DROP TRIGGER set_updated_at ON sponsors;
+DROP TRIGGER set_updated_at ON wishes;
+DROP TRIGGER set_updated_at ON wishlists;
+DROP TRIGGER set_updated_at ON products;
+DROP TRIGGER set_updated_at ON users;
+
+ALTER TABLE followers DROP COLUMN created_at, DROP COLUMN updated_at;
+ALTER TABLE sponsors DROP COLUMN created_at, DROP COLUMN updated_at;
+ALTER TABLE wishes DROP COLUMN created_at, DROP COLUMN updated_at;
+ALTER TABLE wishlists DROP COLUMN created_at, DROP COLUMN updated_at;
+ALTER TABLE products DROP COLUMN created_at, DROP COLUMN updated_at;
+ALTER TABLE users DROP COLUMN created_at, DROP COLUMN updated_at;
@@ -0,0 +1,15 @@
+-- Who did what to which resource and from where, kept even when the actor or the resource are gone
+CREATE TABLE audit_logs (
+    id SERIAL PRIMARY KEY,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR NOT NULL,
+    resource_type VARCHAR NOT NULL,
+    resource_id INTEGER,
+    changes JSONB,
+    ip VARCHAR,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX audit_logs_actor_id_idx ON audit_logs (actor_id);
+CREATE INDEX audit_logs_resource_idx ON audit_logs (resource_type, resource_id);
+CREATE INDEX audit_logs_created_at_idx ON audit_logs (created_at);
@@ -0,0 +1,5 @@
+-- Fails while two users share an email regardless of case,
+-- list them first with `cargo run -- duplicates emails`
+UPDATE users SET email = lower(trim(email)) WHERE email <> lower(trim(email));
+
+CREATE UNIQUE INDEX users_email_lower_key ON users (lower(email));
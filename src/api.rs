@@ -5,16 +5,29 @@ pub mod responses;
 pub mod admin;
 pub mod utils;
 pub mod validations;
+pub mod policy;
 
 pub fn get_router() -> salvo::Router {
-    use resources::{products, wishlists, wishes, sponsors};
+    use resources::{products, wishlists, wishes, sponsors, templates, collaborators, shares, search, categories, tags, images, inbox};
 
     salvo::Router::with_path("api")
         .path("v1")
+        .hoop(utils::assign_request_id)
         .push(admin::get_router())
         .push(products::get_router())
         .push(wishlists::get_router())
         .push(wishes::get_router_for_wishlists())
         .push(wishes::get_root_router())
+        .push(collaborators::get_router_for_wishlists())
+        .push(shares::get_router_for_wishlists())
+        .push(shares::get_public_router())
         .push(sponsors::get_router())
+        .push(templates::get_router())
+        .push(search::get_router())
+        .push(categories::get_router())
+        .push(tags::get_router())
+        .push(images::get_router_for_products())
+        .push(images::get_router_for_wishlists())
+        .push(images::get_uploads_router())
+        .push(inbox::get_router())
 }
\ No newline at end of file
@@ -1,17 +1,27 @@
 pub mod controllers;
 
 use salvo::prelude::*;
-use self::controllers::{check_admin_role, list_users, populate_products, populate_users, populate_wishlists};
+use self::controllers::{list_users, populate_products, populate_users, populate_wishlists};
 use super::auth::controllers::handle_auth;
+use super::policy::require_admin;
+use super::resources::{audit, categories, outbox, products, tags, templates, wishes, wishlists};
 
 pub fn get_router() -> Router {
     Router::with_path("admin")
         .hoop(handle_auth)
-        .hoop(check_admin_role)
+        .hoop(require_admin)
         .push(Router::with_path("users").get(list_users))
         .push(Router::with_path("populate")
             .push(Router::with_path("users").post(populate_users))
             .push(Router::with_path("products").post(populate_products))
             .push(Router::with_path("wishlists").post(populate_wishlists))
         )
+        .push(products::get_admin_router())
+        .push(tags::get_admin_router())
+        .push(categories::get_admin_router())
+        .push(templates::get_admin_router())
+        .push(wishlists::get_admin_router())
+        .push(wishes::get_admin_router())
+        .push(audit::get_admin_router())
+        .push(outbox::get_admin_router())
 }
@@ -1,14 +1,20 @@
 use salvo::prelude::*;
 use std::error::Error;
 use serde::Deserialize;
+use serde_json::json;
 use chrono::NaiveDateTime;
 use crate::api::errors::{ApiResult, ApiError};
-use crate::api::resources::wishlists::models::NewWishlist;
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::resources::wishlists::models::{NewWishlist, Visibility};
 use crate::api::utils::{get_db, hash_password, parse_csv};
-use crate::api::{errors as api_errors, responses as api_responses, utils};
-use crate::api::resources::users::models::NewUser;
-use crate::api::resources::products::models::NewProduct;
+use crate::api::{errors as api_errors, responses as api_responses};
+use crate::api::resources::users::models::{normalize_email, NewUser};
+use crate::api::resources::products::models::{NewProduct, ReviewStatus};
+use crate::api::resources::categories::models::NewCategory;
+use crate::services::database::contracts::CategoryRepo;
 use crate::api::utils::formatters::optional_date;
+use crate::api::utils::sorting::SyncFilter;
+use crate::services::notifications::messages::Locale;
 
 static USERS_CSV_FILE: &str = "data/users.csv";
 static PRODUCTS_CSV_FILE: &str = "data/products.csv";
@@ -20,13 +26,16 @@ struct UserBatch {
     email: String,
     password: String,
     active: bool,
+    #[serde(default)]
+    locale: Option<String>,
 }
 
 impl From<UserBatch> for NewUser {
     fn from(value: UserBatch) -> Self {
-        let UserBatch { name, email, password, active } = value;
+        let UserBatch { name, email, password, active, locale } = value;
+        let locale = Locale::from(locale.unwrap_or_default().as_str()).to_string();
 
-        Self { name, email, password: hash_password(&password), active }        
+        Self { name, email: normalize_email(&email), password: hash_password(&password), active, locale }        
     }
 }
 
@@ -36,13 +45,25 @@ pub struct ProductBatch {
     pub price: f32,
     pub url: Option<String>,
     pub description: Option<String>,
+    /// Name of the category, created when missing
+    #[serde(default)]
+    pub category: Option<String>,
 }
 
 impl From<ProductBatch> for NewProduct {
     fn from(val: ProductBatch) -> Self {
-        let ProductBatch { name, description, url, price } = val;
-
-        NewProduct { name, price, url, description, available: true }
+        let ProductBatch { name, description, url, price, .. } = val;
+
+        NewProduct {
+            name,
+            price,
+            url,
+            description,
+            available: true,
+            review_status: ReviewStatus::Approved,
+            submitted_by: None,
+            category_id: None,
+        }
     }
 }
 #[derive(Debug, Deserialize)]
@@ -59,22 +80,17 @@ impl From<WishlistBatch> for NewWishlist {
     fn from(val: WishlistBatch) -> Self {
         let WishlistBatch { title, description, date, user_id, published } = val;
 
-        NewWishlist { title, description, date, user_id, published }
-    }
-}
-
-#[handler]
-pub fn check_admin_role(depot: &Depot, res: &mut Response) {
-    if !utils::admin(depot) {
-        return api_errors::render_unauthorized(res);
+        NewWishlist { title, description, date, user_id, visibility: Visibility::from(published) }
     }
 }
 
 #[handler]
-pub fn list_users(_req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+pub fn list_users(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
     let repo = get_db(depot)?.user_repo();
 
-    let users = repo.list()?;
+    let filter = SyncFilter::from_query(req)?;
+
+    let users = repo.list(&filter)?;
 
     api_responses::render_collection(res, users);
 
@@ -82,7 +98,7 @@ pub fn list_users(_req: &Request, depot: &Depot, res: &mut Response) -> ApiResul
 }
 
 #[handler]
-pub fn populate_users(depot: &Depot, res: &mut Response) ->ApiResult<()> {
+pub fn populate_users(req: &Request, depot: &Depot, res: &mut Response) ->ApiResult<()> {
     let repo = get_db(depot)?.user_repo();
 
     match parse_csv::<UserBatch, NewUser>(USERS_CSV_FILE) {
@@ -91,7 +107,11 @@ pub fn populate_users(depot: &Depot, res: &mut Response) ->ApiResult<()> {
         Ok(users) => match repo.insert_many(users) {
             Err(error) => api_errors::render_db_insert_error(res, error, "users"),
 
-            Ok(total) => api_responses::render_db_execution(res, total)
+            Ok(total) => {
+                audit::record(req, depot, NewAuditLog::new("populate", "user", None).details(json!({ "total": total })));
+
+                api_responses::render_db_execution(res, total)
+            }
         }
     };
 
@@ -99,22 +119,27 @@ pub fn populate_users(depot: &Depot, res: &mut Response) ->ApiResult<()> {
 }
 
 #[handler]
-pub fn populate_products(_req: &mut Request, depot: &Depot, res: &mut Response) {
-    let repo = get_db(depot).unwrap().product_repo();
+pub fn populate_products(req: &mut Request, depot: &Depot, res: &mut Response) {
+    let db = get_db(depot).unwrap();
+    let repo = db.product_repo();
 
-    match parse_products_csv() {
+    match parse_products_csv(db.category_repo().as_ref()) {
         Err(error) => api_errors::render_parse_field_error(res, error, "products.csv"),
 
         Ok(products) => match repo.insert_many(products) {
             Err(error) => api_errors::render_db_insert_error(res, error, "products"),
 
-            Ok(total) => api_responses::render_db_execution(res, total)
+            Ok(total) => {
+                audit::record(req, depot, NewAuditLog::new("populate", "product", None).details(json!({ "total": total })));
+
+                api_responses::render_db_execution(res, total)
+            }
         }
     }
 }
 
 #[handler]
-pub fn populate_wishlists(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+pub fn populate_wishlists(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
     let repo = get_db(depot).unwrap().wishlist_repo();
 
     let wishlists = parse_wishlist_csv()
@@ -122,6 +147,8 @@ pub fn populate_wishlists(depot: &Depot, res: &mut Response) -> ApiResult<()> {
 
     let total = repo.insert_many(wishlists)?;
 
+    audit::record(req, depot, NewAuditLog::new("populate", "wishlist", None).details(json!({ "total": total })));
+
     api_responses::render_db_execution(res, total);
 
     Ok(())
@@ -131,8 +158,21 @@ pub fn parse_users_csv() -> Result<Vec<NewUser>, Box<dyn Error>> {
     parse_csv::<UserBatch, NewUser>(USERS_CSV_FILE)
 }
 
-pub fn parse_products_csv() -> Result<Vec<NewProduct>, Box<dyn Error>> {
-    parse_csv::<ProductBatch, NewProduct>(PRODUCTS_CSV_FILE)
+pub fn parse_products_csv(categories: &dyn CategoryRepo) -> Result<Vec<NewProduct>, Box<dyn Error>> {
+    let batches = parse_csv::<ProductBatch, ProductBatch>(PRODUCTS_CSV_FILE)?;
+
+    let mut products = vec![];
+
+    for mut batch in batches {
+        let category_id = match batch.category.take().filter(|name| !name.trim().is_empty()) {
+            None => None,
+            Some(name) => Some(categories.find_or_insert(NewCategory::new(&name, None))?.id),
+        };
+
+        products.push(NewProduct { category_id, ..batch.into() });
+    }
+
+    Ok(products)
 }
 
 pub fn parse_wishlist_csv() -> Result<Vec<NewWishlist>, Box<dyn Error>> {
@@ -1,27 +1,40 @@
 use salvo::prelude::*;
-use salvo::http::form::FormData;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde_json::json;
 use time::{OffsetDateTime, Duration};
 use crate::api::errors::{ApiError, ApiResult};
+use crate::api::errors as api_errors;
 use crate::api::responses as api_responses;
-use crate::api::resources::users::models::NewUser;
-use crate::api::utils::{get_db, get_notifier};
-use crate::api::validations::{FormValidator, Validator};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::resources::users::models::{normalize_email, NewUser, User};
+use crate::api::utils::get_db;
+use crate::api::validations::{BodyValidator, Constraint, FieldValidator, Validator};
+use crate::services::notifications::messages::{Locale, Message};
 use super::models::{ActivateUserAction, ActivateUserClaims};
 use super::{create_bearer_token, decode_token, encode_token};
 use crate::api::responses;
 
+/// Outlives every retry of the outbox, the email may arrive hours late
+const ACTIVATION_TOKEN_HOURS: i64 = 24;
+
 #[handler]
 pub async fn authenticate(req: &mut Request, depot: &mut Depot, res: &mut Response) -> ApiResult<()> {
     let repo = get_db(depot)?.auth_repo();
 
-    let form_data = req.form_data().await?;
+    let body = BodyValidator::from_request(req).await?;
+
+    let (email_candidate, password_candidate) = cast_login_form_data(&body)?;
 
-    let (email_candidate, password_candidate) = cast_login_form_data(form_data)?;
+    let Some(user) = repo.validate(&email_candidate, &password_candidate) else {
+        audit::record(req, depot, NewAuditLog::new("login_failed", "user", None).details(json!({ "email": email_candidate })));
 
-    let user = repo.validate(&email_candidate, &password_candidate).ok_or(ApiError::InvalidCredentials)?;
+        return Err(ApiError::InvalidCredentials);
+    };
 
     let token = create_bearer_token(&user)?;
 
+    audit::record(req, depot, NewAuditLog::new("login", "user", Some(user.id)).actor(user.id));
+
     responses::render_authentication(res, user.into(), token);
 
     Ok(())
@@ -32,10 +45,10 @@ pub fn handle_auth(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
     match depot.jwt_auth_state() {
         JwtAuthState::Authorized => {},
         JwtAuthState::Unauthorized => {
-            res.status_code(StatusCode::UNAUTHORIZED);
+            api_errors::render_unauthenticated(res);
         },
         JwtAuthState::Forbidden => {
-            res.status_code(StatusCode::FORBIDDEN);
+            api_errors::render_invalid_token(res);
         },
     }
 }
@@ -44,24 +57,31 @@ pub fn handle_auth(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
 pub async fn signup(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
     let repo = get_db(depot)?.user_repo();
 
-    let form_data = req.form_data().await?;
+    let accepted_locale = req.header::<String>("accept-language")
+        .and_then(|header| Locale::from_accept_language(&header));
 
-    let new_user = cast_registry_data(form_data)?;
+    let body = BodyValidator::from_request(req).await?;
 
-    let user = repo.insert(new_user)?;
+    let new_user = cast_registry_data(&body, accepted_locale)?;
 
-    let notifier = get_notifier(depot)?;
+    let activation = |user: &User| {
+        let claims = ActivateUserClaims {
+            id: user.id,
+            email: user.email.clone(),
+            action: ActivateUserAction::Activate,
+            exp: (OffsetDateTime::now_utc() + Duration::hours(ACTIVATION_TOKEN_HOURS)).unix_timestamp(),
+        };
 
-    let claims = ActivateUserClaims {
-        id: user.id,
-        email: user.email.clone(),
-        action: ActivateUserAction::Activate,
-        exp: (OffsetDateTime::now_utc() + Duration::minutes(1)).unix_timestamp(),
+        Message::AccountActivation { token: encode_token(claims).unwrap() }
     };
 
-    let token = encode_token(claims).unwrap();
+    // Queued along with the user, an account is never left without its activation
+    let user = repo.insert_notified(new_user, &activation).map_err(|error| match error {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => ApiError::EmailTaken,
+        other => ApiError::Diesel(other),
+    })?;
 
-    notifier.send(&user, format!("token={token}"));
+    audit::record(req, depot, NewAuditLog::new("signup", "user", Some(user.id)).actor(user.id).changes(None, Some(&user)));
 
     api_responses::render_resource_created(res, user);
 
@@ -87,6 +107,10 @@ pub fn activate(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<(
                 ActivateUserAction::Activate => {
                     let total = repo.activate(data.claims.id, &data.claims.email).unwrap();
 
+                    if total > 0 {
+                        audit::record(req, depot, NewAuditLog::new("activate", "user", Some(data.claims.id)).actor(data.claims.id));
+                    }
+
                     api_responses::render_db_execution(res, total);
                     
                     Ok(())
@@ -96,21 +120,27 @@ pub fn activate(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<(
     }
 }
 
-fn cast_login_form_data(form_data: &FormData) -> ApiResult<(String, String)> {
-    let validator = FormValidator(form_data);
+fn cast_login_form_data(validator: &dyn Validator) -> ApiResult<(String, String)> {
+    let mut validator = FieldValidator::new(validator);
+
+    let email = validator.string("email", &[]);
+    let password = validator.string("password", &[]);
 
-    let email = validator.string("email")?;
-    let password = validator.string("password")?;
+    validator.finish()?;
 
-    Ok((email, password))
+    Ok((normalize_email(&email), password))
 }
 
-fn cast_registry_data(form_data: &FormData) -> ApiResult<NewUser> {
-    let validator = FormValidator(form_data);
+/// The `locale` field wins over the `Accept-Language` header
+fn cast_registry_data(validator: &dyn Validator, accepted_locale: Option<Locale>) -> ApiResult<NewUser> {
+    let mut validator = FieldValidator::new(validator);
+
+    let name = validator.string("name", &[Constraint::MinLength(1), Constraint::MaxLength(100)]);
+    let email = validator.string("email", &[Constraint::Email, Constraint::MaxLength(255)]);
+    let password = validator.password("password", &[Constraint::MinLength(8), Constraint::MaxLength(128)]);
+    let locale = validator.parsed::<Locale>("locale", false).or(accepted_locale).unwrap_or_default();
 
-    let name = validator.string("name")?;
-    let email = validator.string("email")?;
-    let password = validator.password("password")?;
+    validator.finish()?;
 
-    Ok(NewUser { name, email, password, active: false })
+    Ok(NewUser { name, email: normalize_email(&email), password, active: false, locale: locale.to_string() })
 }
@@ -9,6 +9,7 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use time::{OffsetDateTime, Duration};
 use crate::models::Role;
+use crate::api::utils::assign_request_id;
 use self::controllers::{authenticate, activate, signup};
 use self::models::User;
 
@@ -64,6 +65,7 @@ pub fn create_bearer_token(user: &User) -> Result<String, jsonwebtoken::errors::
 
 pub fn get_router() -> Router {
     Router::with_path("api/v1/auth")
+        .hoop(assign_request_id)
         .post(authenticate)
         .push(Router::with_path("signup").post(signup))
         .push(Router::with_path("activate").post(activate))
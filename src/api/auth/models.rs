@@ -1,5 +1,7 @@
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
+use crate::api::utils::formatters::date;
 
 #[derive(Debug, Clone, Serialize)]
 #[derive(Queryable, Selectable, AsChangeset)]
@@ -12,6 +14,11 @@ pub struct User {
     pub role: String,
     pub password: Vec<u8>,
     pub active: bool,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
+    pub locale: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
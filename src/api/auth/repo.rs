@@ -1,4 +1,5 @@
 use diesel::{prelude::*, QueryDsl};
+use diesel::sql_types::Text;
 use crate::db::establish_connection;
 use crate::db::contracts::AuthRepo;
 use crate::schema::users::{table as users_table, dsl::*};
@@ -7,11 +8,13 @@ use crate::api::utils::compare_passwords;
 
 pub struct Repo;
 
+sql_function!(fn lower(x: Text) -> Text);
+
 impl AuthRepo for Repo {
     fn validate(&self, email_candidate: &str, password_candidate: &str) -> Option<User> {
         let conn = &mut establish_connection();
 
-        let user_result = QueryDsl::filter(users_table, email.eq(email_candidate))
+        let user_result = QueryDsl::filter(users_table, lower(email).eq(email_candidate.to_lowercase()))
             .select(User::as_select())
             .first(conn);
 
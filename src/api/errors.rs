@@ -1,7 +1,12 @@
 use std::{fmt::{Display, Debug}, str::ParseBoolError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use diesel::result::DatabaseErrorKind;
 use salvo::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use thiserror::Error;
+use super::utils::REQUEST_ID_HEADER;
+use super::validations::FieldError;
 use std::num::{ParseIntError, ParseFloatError};
 
 #[derive(Error, Debug)]
@@ -32,6 +37,18 @@ pub enum ApiError {
     InvalidCredentials,
     #[error("bad-request-error: {0}")]
     BadRequestError(String),
+    #[error("conflict: {0}")]
+    Conflict(String),
+    #[error("storage: {0}")]
+    Storage(#[from] std::io::Error),
+    #[error("payload-too-large: {0}")]
+    PayloadTooLarge(String),
+    #[error("validation: {0:?}")]
+    Validation(Vec<FieldError>),
+    #[error("email-taken")]
+    EmailTaken,
+    #[error("task: {0}")]
+    Task(#[from] tokio::task::JoinError),
 }
 
 #[async_trait]
@@ -39,62 +56,68 @@ impl Writer for ApiError {
     async fn write(mut self, _req: &mut Request, _depot: &mut Depot, res: &mut Response) {
         match self {
             ApiError::Injection(details) => {
-                res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-                res.render(json(format!("Error: {details}")));
+                render_internal_error(res, details);
             },
             ApiError::ParseInt(error, field) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("Error parsing `{field}` from the form: {error:?}")));
+                render_invalid_field(res, &field, error);
             },
             ApiError::ParseFloat(error, field) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("Error parsing `{field}` from the form: {error:?}")));
+                render_invalid_field(res, &field, error);
             },
             ApiError::ParseBool(error, field) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("Error parsing `{field}` from the form: {error:?}")));
+                render_invalid_field(res, &field, error);
             },
             ApiError::FieldNotFound(field) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("Field `{field}` not found")));
+                render_error(res, StatusCode::BAD_REQUEST, ErrorCode::MissingField, format!("Field `{field}` not found"), Some(json!({ "field": field })));
             },
             ApiError::ParseFormData(error) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("Error parsing the form data: {error}")));
+                render_error(res, StatusCode::BAD_REQUEST, ErrorCode::InvalidBody, format!("Error parsing the request body: {error}"), None);
             },
-            ApiError::Diesel(error) => match error {
-                diesel::result::Error::NotFound => {
-                    res.status_code(StatusCode::NOT_FOUND);
-                    res.render(json(format!("DB: {error}")));
-                },
-                other => {
-                    res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-                    res.render(json(format!("DB: {other}")));
-                }
+            ApiError::Diesel(error) => {
+                render_db_error(res, error);
             },
             ApiError::ChronoParse(error) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("Error parsing date: {error:?}")));
+                render_error(res, StatusCode::BAD_REQUEST, ErrorCode::InvalidDate, format!("Error parsing date: {error}"), None);
             },
             ApiError::Jwt(error) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("Error with jwt: {error:?}")));
+                render_error(res, StatusCode::BAD_REQUEST, ErrorCode::InvalidToken, format!("Invalid token: {error}"), None);
             },
             ApiError::Deserializer(error) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("{error:?}")));
+                render_error(res, StatusCode::BAD_REQUEST, ErrorCode::InvalidBody, error, None);
             },
             ApiError::NotAllowed(error) => {
-                res.status_code(StatusCode::FORBIDDEN);
-                res.render(json(error));
+                render_error(res, StatusCode::FORBIDDEN, ErrorCode::Forbidden, error, None);
             },
             ApiError::InvalidCredentials => {
-                res.status_code(StatusCode::NOT_ACCEPTABLE);
-                res.render(json("Authentication failed".to_string()));
+                render_error(res, StatusCode::UNAUTHORIZED, ErrorCode::InvalidCredentials, "Authentication failed", None);
             }
             ApiError::BadRequestError(reason) => {
-                res.status_code(StatusCode::BAD_REQUEST);
-                res.render(json(format!("{reason}")));
+                render_error(res, StatusCode::BAD_REQUEST, ErrorCode::BadRequest, reason, None);
+            }
+            ApiError::Conflict(reason) => {
+                render_error(res, StatusCode::CONFLICT, ErrorCode::Conflict, reason, None);
+            }
+            ApiError::Storage(error) => match error.kind() {
+                std::io::ErrorKind::NotFound => {
+                    render_error(res, StatusCode::NOT_FOUND, ErrorCode::NotFound, "File not found", None);
+                },
+                _other => {
+                    render_internal_error(res, error);
+                }
+            },
+            ApiError::PayloadTooLarge(reason) => {
+                render_error(res, StatusCode::PAYLOAD_TOO_LARGE, ErrorCode::PayloadTooLarge, reason, None);
+            }
+            ApiError::Validation(fields) => {
+                let details = serde_json::to_value(fields).ok();
+
+                render_error(res, StatusCode::UNPROCESSABLE_ENTITY, ErrorCode::ValidationFailed, "Validation failed", details);
+            }
+            ApiError::EmailTaken => {
+                render_error(res, StatusCode::CONFLICT, ErrorCode::EmailTaken, "The email is already registered", Some(json!({ "field": "email" })));
+            }
+            ApiError::Task(error) => {
+                render_internal_error(res, error);
             }
         }
     }
@@ -128,85 +151,245 @@ pub enum Error<'a> {
     ParseFloatErr(&'a str),
 }
 
-#[derive(Serialize)]
-struct ErrorMessage {
-    message: String,
+/// Stable identifier of an error, clients should branch on it rather than on the message
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    MissingField,
+    InvalidField,
+    InvalidBody,
+    InvalidDate,
+    InvalidToken,
+    Unauthenticated,
+    InvalidCredentials,
+    Forbidden,
+    NotFound,
+    Conflict,
+    EmailTaken,
+    InvalidReference,
+    PayloadTooLarge,
+    ValidationFailed,
+    InternalError,
+}
+
+/// The body of every error response:
+/// `{"error": {"code": "not_found", "message": "...", "details": ..., "request_id": "..."}}`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ErrorBody {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    pub request_id: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ErrorResponse {
-    error: ErrorMessage,
+    pub error: ErrorBody,
+}
+
+static SHOW_INTERNAL_DETAILS: AtomicBool = AtomicBool::new(false);
+
+/// Internal errors (database, storage...) only expose their cause when the `DEBUG` config flag is set
+pub fn show_internal_details(show: bool) {
+    SHOW_INTERNAL_DETAILS.store(show, Ordering::Relaxed);
+}
+
+pub fn render_error(res: &mut Response, status: StatusCode, code: ErrorCode, message: impl Display, details: Option<Value>) {
+    let request_id = res.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    res.status_code(status);
+    res.render(Json(ErrorResponse { error: ErrorBody { code, message: message.to_string(), details, request_id } }));
+}
+
+fn showing_internal_details() -> bool {
+    SHOW_INTERNAL_DETAILS.load(Ordering::Relaxed)
+}
+
+/// A 500 that hides its cause unless debugging
+pub fn render_internal_error(res: &mut Response, cause: impl Display) {
+    render_internal_error_with(res, cause, showing_internal_details());
 }
 
-pub fn make_json_response(message: String) -> Json<ErrorResponse> {
-    Json(ErrorResponse { error: ErrorMessage { message } })
+fn render_internal_error_with(res: &mut Response, cause: impl Display, show_details: bool) {
+    tracing::error!("{cause}");
+
+    render_error(res, StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError, "Internal server error", internal_details(cause, show_details));
+}
+
+fn internal_details(cause: impl Display, show_details: bool) -> Option<Value> {
+    match show_details {
+        true => Some(json!({ "internal": cause.to_string() })),
+        false => None,
+    }
 }
 
-use make_json_response as json;
+fn render_invalid_field(res: &mut Response, field: &str, error: impl Display) {
+    render_error(res, StatusCode::BAD_REQUEST, ErrorCode::InvalidField, format!("Error parsing `{field}`: {error}"), Some(json!({ "field": field })));
+}
+
+/// Unique violations are conflicts and missing references unprocessable, anything else is internal
+pub fn render_db_error(res: &mut Response, error: diesel::result::Error) {
+    match error {
+        diesel::result::Error::NotFound => {
+            render_error(res, StatusCode::NOT_FOUND, ErrorCode::NotFound, "Resource not found", None);
+        },
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, ref info) => {
+            let details = internal_details(info.constraint_name().unwrap_or(info.message()), showing_internal_details());
+
+            render_error(res, StatusCode::CONFLICT, ErrorCode::Conflict, "The resource already exists", details);
+        },
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, ref info) => {
+            let details = internal_details(info.constraint_name().unwrap_or(info.message()), showing_internal_details());
+
+            render_error(res, StatusCode::UNPROCESSABLE_ENTITY, ErrorCode::InvalidReference, "A referenced resource does not exist", details);
+        },
+        other => {
+            render_internal_error(res, other);
+        }
+    }
+}
 
 pub fn render_form_data_error(res: &mut Response, error: impl Display) {
-    res.status_code(StatusCode::BAD_REQUEST);
-    res.render(json(format!("Error getting the form data: {error}")));
+    render_error(res, StatusCode::BAD_REQUEST, ErrorCode::InvalidBody, format!("Error getting the form data: {error}"), None);
 }
 
 pub fn render_resource_not_found(res: &mut Response, resource: impl Display) {
-    res.status_code(StatusCode::NOT_FOUND);
-    res.render(json(format!("Error `{resource}` not found")));
+    render_error(res, StatusCode::NOT_FOUND, ErrorCode::NotFound, format!("Error `{resource}` not found"), None);
 }
 
 #[deprecated]
 pub fn render_cast_error(res: &mut Response, error: impl Debug) {
-    res.status_code(StatusCode::BAD_REQUEST);
-    res.render(json(format!("Error parsing the form data fields: {error:?}")));
+    render_error(res, StatusCode::BAD_REQUEST, ErrorCode::InvalidBody, format!("Error parsing the form data fields: {error:?}"), None);
 }
 
 pub fn render_parse_field_error(res: &mut Response, error: impl Display, field: impl Display) {
-    res.status_code(StatusCode::BAD_REQUEST);
-    res.render(json(format!("Incorrect <{field}>: {error}")));
+    render_error(res, StatusCode::BAD_REQUEST, ErrorCode::InvalidField, format!("Incorrect <{field}>: {error}"), None);
 }
 
 pub fn render_get_user_id_not_found(res: &mut Response) {
-    res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-    res.render(json("Couldn't read `user_id` from depot".to_string()));
+    render_internal_error(res, "Couldn't read `user_id` from depot");
 }
 
 pub fn render_auth_create_token_error(res: &mut Response, error: impl Debug) {
-    res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-    res.render(json(format!("Error creating token: {error:?}")));
+    render_internal_error(res, format!("Error creating token: {error:?}"));
 }
 
 #[deprecated]
 pub fn render_inconsistency_error(res: &mut Response, value: impl Display) {
-    res.status_code(StatusCode::BAD_REQUEST);
-    res.render(json(format!("Error: `{value}` is not consistent")));
+    render_error(res, StatusCode::BAD_REQUEST, ErrorCode::BadRequest, format!("Error: `{value}` is not consistent"), None);
+}
+
+pub fn render_unauthenticated(res: &mut Response) {
+    render_error(res, StatusCode::UNAUTHORIZED, ErrorCode::Unauthenticated, "Authentication required", None);
+}
+
+pub fn render_invalid_token(res: &mut Response) {
+    render_error(res, StatusCode::FORBIDDEN, ErrorCode::InvalidToken, "Invalid or expired token", None);
 }
 
 pub fn render_unauthorized(res: &mut Response) {
-    res.status_code(StatusCode::FORBIDDEN);
-    res.render(json("Not enough privileges".to_string()));
+    render_error(res, StatusCode::FORBIDDEN, ErrorCode::Forbidden, "Not enough privileges", None);
 }
 
 pub fn render_db_resource_not_associated(res: &mut Response, resource: impl Display) {
-    res.status_code(StatusCode::FORBIDDEN);
-    res.render(json(format!("Error: `user` not associated to `{resource}`")));
+    render_error(res, StatusCode::FORBIDDEN, ErrorCode::Forbidden, format!("Error: `user` not associated to `{resource}`"), None);
 }
 
 pub fn render_db_retrieving_error(res: &mut Response, error: impl Display, resource: impl Display) {
-    res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-    res.render(json(format!("Error loading `{resource}`: {error}")));
+    render_internal_error(res, format!("Error loading `{resource}`: {error}"));
 }
 
 pub fn render_db_insert_error(res: &mut Response, error: impl Display, resource: impl Display) {
-    res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-    res.render(json(format!("Error inserting `{resource}`: {error}")));
+    render_internal_error(res, format!("Error inserting `{resource}`: {error}"));
 }
 
 pub fn render_db_update_error(res: &mut Response, error: impl Display, resource: impl Display) {
-    res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-    res.render(json(format!("Error updating `{resource}`: {error}")));
+    render_internal_error(res, format!("Error updating `{resource}`: {error}"));
 }
 
 pub fn render_db_delete_error(res: &mut Response, error: impl Display, resource: impl Display) {
-    res.status_code(StatusCode::NOT_FOUND);
-    res.render(json(format!("Error deleting `{resource}`: {error}")));
+    render_internal_error(res, format!("Error deleting `{resource}`: {error}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::result::{DatabaseErrorKind, Error as DieselError};
+    use salvo::prelude::*;
+    use salvo::test::{ResponseExt, TestClient};
+    use crate::api::utils::assign_request_id;
+    use super::{render_internal_error_with, ApiError, ApiResult, ErrorCode, ErrorResponse};
+
+    fn database_error(kind: DatabaseErrorKind) -> ApiError {
+        ApiError::Diesel(DieselError::DatabaseError(kind, Box::new("users_email_key".to_string())))
+    }
+
+    #[handler]
+    fn fail(req: &Request) -> ApiResult<()> {
+        Err(match req.query::<String>("error").unwrap_or_default().as_str() {
+            "credentials" => ApiError::InvalidCredentials,
+            "unique" => database_error(DatabaseErrorKind::UniqueViolation),
+            "foreign_key" => database_error(DatabaseErrorKind::ForeignKeyViolation),
+            _ => database_error(DatabaseErrorKind::ClosedConnection),
+        })
+    }
+
+    #[handler]
+    fn fail_debugging(res: &mut Response) {
+        render_internal_error_with(res, "connection closed", true);
+    }
+
+    async fn request(error: &str) -> (StatusCode, ErrorResponse) {
+        let router = Router::new()
+            .hoop(assign_request_id)
+            .get(fail)
+            .push(Router::with_path("debugging").get(fail_debugging));
+
+        let service = Service::new(router);
+
+        let path = match error {
+            "debugging" => "debugging".to_string(),
+            error => format!("?error={error}"),
+        };
+
+        let mut response = TestClient::get(format!("http://localhost/{path}"))
+            .add_header("x-request-id", "req-1", true)
+            .send(&service)
+            .await;
+
+        (response.status_code.unwrap(), response.take_json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn error_envelope() {
+        let (status, body) = request("credentials").await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body.error.code, ErrorCode::InvalidCredentials);
+        assert_eq!(body.error.request_id.as_deref(), Some("req-1"), "the request id is echoed");
+
+        let (status, body) = request("unique").await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body.error.code, ErrorCode::Conflict);
+
+        let (status, body) = request("foreign_key").await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body.error.code, ErrorCode::InvalidReference);
+
+        let (status, body) = request("internal").await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body.error.message, "Internal server error");
+        assert_eq!(body.error.details, None, "internal details are hidden");
+
+        let (_, body) = request("debugging").await;
+
+        assert_eq!(body.error.details, Some(serde_json::json!({ "internal": "connection closed" })), "internal details are shown when debugging");
+    }
 }
@@ -0,0 +1,268 @@
+use std::fmt::Display;
+use std::sync::Arc;
+use salvo::prelude::*;
+use diesel::result::Error;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::resources::collaborators::models::CollaboratorRole;
+use crate::api::resources::products::models::{Product, ReviewStatus};
+use crate::api::resources::wishlists::models::{Visibility, Wishlist};
+use crate::api::utils;
+use crate::services::database::contracts::DatabaseService;
+
+/// Who is asking, as read from the bearer token
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Subject {
+    pub user_id: Option<i32>,
+    pub admin: bool,
+}
+
+impl Subject {
+    pub fn from_depot(depot: &Depot) -> Self {
+        Self {
+            user_id: utils::get_user_id(depot),
+            admin: utils::admin(depot),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    /// Read the resource
+    View,
+    /// Add new entries of the resource
+    Create,
+    /// Change the resource or, for wishlists, add, remove or reorder its wishes
+    Edit,
+    /// Delete the resource or, for wishlists, handle its collaborators and share links
+    Manage,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self {
+            Action::View => "see",
+            Action::Create => "create",
+            Action::Edit => "modify",
+            Action::Manage => "manage",
+        };
+
+        write!(f, "{action}")
+    }
+}
+
+/// The resource being accessed, along with what is known about the subject's relation to it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resource<'a> {
+    Admin,
+    /// The products collection as a whole
+    Catalog,
+    Product(&'a Product),
+    Wishlist {
+        wishlist: &'a Wishlist,
+        role: Option<CollaboratorRole>,
+        following: bool,
+    },
+}
+
+impl<'a> Resource<'a> {
+    fn name(&self) -> &'static str {
+        match self {
+            Resource::Admin => "the admin area",
+            Resource::Catalog => "products",
+            Resource::Product(_) => "the product",
+            Resource::Wishlist { .. } => "the wishlist",
+        }
+    }
+}
+
+/// Rules for every resource:
+///
+/// - admin area: admins only
+/// - products: anyone can see the approved ones and any user can submit new
+///   ones for review; submitters see their own, only admins change the catalog
+/// - wishlists: seen according to their visibility, edited by owners and
+///   editors, managed by owners; admins can see and manage any of them
+pub fn can(subject: &Subject, action: Action, resource: &Resource) -> bool {
+    match resource {
+        Resource::Admin => subject.admin,
+
+        Resource::Catalog => match action {
+            Action::View => true,
+            Action::Create => subject.user_id.is_some(),
+            Action::Edit | Action::Manage => subject.admin,
+        },
+
+        Resource::Product(product) => match action {
+            Action::View => subject.admin
+                || product.review_status == ReviewStatus::Approved
+                || (subject.user_id.is_some() && product.submitted_by == subject.user_id),
+            _ => subject.admin,
+        },
+
+        Resource::Wishlist { wishlist, role, following } => match action {
+            Action::View => subject.admin
+                || role.is_some()
+                || wishlist.visibility == Visibility::Public
+                || (wishlist.visibility == Visibility::FollowersOnly && *following),
+            Action::Create | Action::Edit => role.is_some(),
+            Action::Manage => subject.admin || *role == Some(CollaboratorRole::Owner),
+        },
+    }
+}
+
+pub fn authorize(subject: &Subject, action: Action, resource: &Resource) -> ApiResult<()> {
+    match can(subject, action, resource) {
+        true => Ok(()),
+        false => Err(ApiError::NotAllowed(format!("Not allowed to {action} {}", resource.name()))),
+    }
+}
+
+/// Role of the user on the wishlist, the creator is always an owner
+pub fn wishlist_role(db: &Arc<dyn DatabaseService>, wishlist: &Wishlist, user_id: i32) -> ApiResult<Option<CollaboratorRole>> {
+    if wishlist.user_id == user_id {
+        return Ok(Some(CollaboratorRole::Owner));
+    }
+
+    match db.collaborator_repo().find_one(wishlist.id, user_id) {
+        Ok(collaborator) => Ok(Some(collaborator.role())),
+        Err(Error::NotFound) => Ok(None),
+        Err(error) => Err(ApiError::Diesel(error)),
+    }
+}
+
+/// Loads the subject's relation to the wishlist and checks the action against it
+pub fn authorize_wishlist(db: &Arc<dyn DatabaseService>, subject: &Subject, action: Action, wishlist: &Wishlist) -> ApiResult<()> {
+    let (role, following) = match subject.user_id {
+        None => (None, false),
+        Some(user_id) => {
+            let following = wishlist.visibility == Visibility::FollowersOnly
+                && db.follower_repo().is_following(wishlist.id, user_id)?;
+
+            (wishlist_role(db, wishlist, user_id)?, following)
+        },
+    };
+
+    authorize(subject, action, &Resource::Wishlist { wishlist, role, following })
+}
+
+#[handler]
+pub fn require_admin(depot: &Depot) -> ApiResult<()> {
+    authorize(&Subject::from_depot(depot), Action::Manage, &Resource::Admin)
+}
+
+#[handler]
+pub fn require_product_writes(depot: &Depot) -> ApiResult<()> {
+    authorize(&Subject::from_depot(depot), Action::Edit, &Resource::Catalog)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use super::*;
+
+    const ADMIN: Subject = Subject { user_id: Some(1), admin: true };
+    const USER: Subject = Subject { user_id: Some(2), admin: false };
+    const ANONYMOUS: Subject = Subject { user_id: None, admin: false };
+
+    fn wishlist(visibility: Visibility) -> Wishlist {
+        Wishlist { id: 1, title: "Birthday".to_string(), description: None, date: None, user_id: 3, visibility, created_at: NaiveDateTime::default(), updated_at: NaiveDateTime::default() }
+    }
+
+    fn wishlist_resource(wishlist: &Wishlist, role: Option<CollaboratorRole>, following: bool) -> Resource<'_> {
+        Resource::Wishlist { wishlist, role, following }
+    }
+
+    #[test]
+    fn admin_area() {
+        assert!(can(&ADMIN, Action::Manage, &Resource::Admin));
+        assert!(!can(&USER, Action::View, &Resource::Admin));
+        assert!(!can(&ANONYMOUS, Action::View, &Resource::Admin));
+    }
+
+    fn product(review_status: ReviewStatus, submitted_by: Option<i32>) -> Product {
+        Product {
+            id: 1,
+            name: "Kite".to_string(),
+            description: None,
+            url: None,
+            price: 1500.0,
+            available: false,
+            review_status,
+            submitted_by,
+            category_id: None,
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn catalog() {
+        for action in [Action::Edit, Action::Manage] {
+            assert!(can(&ADMIN, action, &Resource::Catalog), "admins {action} products");
+            assert!(!can(&USER, action, &Resource::Catalog), "users don't {action} products");
+        }
+
+        assert!(can(&USER, Action::Create, &Resource::Catalog), "users submit products");
+        assert!(!can(&ANONYMOUS, Action::Create, &Resource::Catalog));
+        assert!(can(&ANONYMOUS, Action::View, &Resource::Catalog));
+    }
+
+    #[test]
+    fn product_review() {
+        let approved = product(ReviewStatus::Approved, None);
+        let submitted = product(ReviewStatus::Pending, USER.user_id);
+        let foreign = product(ReviewStatus::Rejected, Some(3));
+        let orphan = product(ReviewStatus::Pending, None);
+
+        assert!(can(&ANONYMOUS, Action::View, &Resource::Product(&approved)));
+        assert!(can(&USER, Action::View, &Resource::Product(&submitted)), "submitters see their products");
+        assert!(!can(&USER, Action::View, &Resource::Product(&foreign)));
+        assert!(!can(&ANONYMOUS, Action::View, &Resource::Product(&orphan)));
+        assert!(can(&ADMIN, Action::View, &Resource::Product(&foreign)));
+        assert!(!can(&USER, Action::Edit, &Resource::Product(&submitted)), "submitters don't edit products");
+    }
+
+    #[test]
+    fn wishlist_visibility() {
+        let private = wishlist(Visibility::Private);
+        let link_only = wishlist(Visibility::LinkOnly);
+        let followers_only = wishlist(Visibility::FollowersOnly);
+        let public = wishlist(Visibility::Public);
+
+        assert!(can(&USER, Action::View, &wishlist_resource(&public, None, false)));
+        assert!(!can(&USER, Action::View, &wishlist_resource(&private, None, false)));
+        assert!(!can(&USER, Action::View, &wishlist_resource(&link_only, None, true)));
+        assert!(!can(&USER, Action::View, &wishlist_resource(&followers_only, None, false)));
+        assert!(can(&USER, Action::View, &wishlist_resource(&followers_only, None, true)));
+        assert!(can(&USER, Action::View, &wishlist_resource(&private, Some(CollaboratorRole::Editor), false)));
+        assert!(can(&ADMIN, Action::View, &wishlist_resource(&private, None, false)));
+    }
+
+    #[test]
+    fn wishlist_roles() {
+        let public = wishlist(Visibility::Public);
+
+        let owner = wishlist_resource(&public, Some(CollaboratorRole::Owner), false);
+        let editor = wishlist_resource(&public, Some(CollaboratorRole::Editor), false);
+        let follower = wishlist_resource(&public, None, true);
+        let stranger = wishlist_resource(&public, None, false);
+
+        assert!(can(&USER, Action::Edit, &owner));
+        assert!(can(&USER, Action::Manage, &owner));
+        assert!(can(&USER, Action::Edit, &editor));
+        assert!(!can(&USER, Action::Manage, &editor));
+        assert!(!can(&USER, Action::Edit, &follower));
+        assert!(!can(&USER, Action::Edit, &stranger));
+        assert!(!can(&ADMIN, Action::Edit, &stranger), "admins moderate wishlists but don't edit them");
+        assert!(can(&ADMIN, Action::Manage, &stranger));
+    }
+
+    #[test]
+    fn denied_message() {
+        let private = wishlist(Visibility::Private);
+
+        let result = authorize(&USER, Action::Edit, &wishlist_resource(&private, None, false));
+
+        assert!(matches!(result, Err(ApiError::NotAllowed(message)) if message == "Not allowed to modify the wishlist"));
+    }
+}
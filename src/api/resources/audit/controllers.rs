@@ -0,0 +1,46 @@
+use salvo::prelude::*;
+use crate::api::errors::ApiResult;
+use crate::api::responses as api_responses;
+use crate::api::utils::get_db;
+use crate::api::utils::pagination::Pagination;
+use crate::api::validations::{QueryValidator, Validator};
+use super::models::AuditFilter;
+
+/// Latest entries first, filtered by `actor`, `action`, `resource`, `resource_id`, `from` and `to`
+#[handler]
+pub fn list_audit_logs(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.audit_repo();
+
+    let filter = cast_query_to_audit_filter(req)?;
+
+    match req.query::<i64>("per_page") {
+        None => {
+            api_responses::render_collection(res, repo.list(&filter)?);
+        },
+
+        Some(per_page) => {
+            let page = req.query::<i64>("page").unwrap_or(1);
+
+            let (entries, logs) = repo.list_paginated(&filter, page, per_page)?;
+
+            api_responses::render_collection_paginated(res, logs, Pagination::new(page, per_page, entries));
+        }
+    };
+
+    Ok(())
+}
+
+fn cast_query_to_audit_filter(req: &Request) -> ApiResult<AuditFilter> {
+    let validator = QueryValidator(req);
+
+    let filter = AuditFilter {
+        actor_id: validator.optional_integer("actor")?,
+        action: validator.optional_string("action")?,
+        resource_type: validator.optional_string("resource")?,
+        resource_id: validator.optional_integer("resource_id")?,
+        from: validator.optional_date("from")?,
+        to: validator.optional_date("to")?,
+    };
+
+    Ok(filter)
+}
@@ -0,0 +1,29 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use salvo::prelude::*;
+use crate::api::utils::{client_ip, get_db, get_user_id};
+use self::controllers::list_audit_logs;
+use self::models::NewAuditLog;
+
+pub fn get_admin_router() -> Router {
+    Router::with_path("audit")
+        .get(list_audit_logs)
+}
+
+/// Stores the entry along with the authenticated user and their address,
+/// a failure to record it doesn't undo the action
+pub fn record(req: &Request, depot: &Depot, entry: NewAuditLog) {
+    let entry = NewAuditLog {
+        actor_id: entry.actor_id.or(get_user_id(depot)),
+        ip: client_ip(req),
+        ..entry
+    };
+
+    let recorded = get_db(depot).map(|db| db.audit_repo().insert(entry));
+
+    if let Ok(Err(error)) = recorded {
+        tracing::error!("Recording the audit log failed: {error}");
+    }
+}
@@ -0,0 +1,122 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use chrono::NaiveDateTime;
+use crate::api::utils::formatters::date;
+use crate::schema::audit_logs;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = audit_logs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditLog {
+    pub id: i32,
+    /// Nobody when the request wasn't authenticated, e.g. a failed login
+    pub actor_id: Option<i32>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<i32>,
+    /// Fields changed by the action, as `{"before": {..}, "after": {..}}`
+    pub changes: Option<Value>,
+    pub ip: Option<String>,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Insertable)]
+#[diesel(table_name = audit_logs)]
+pub struct NewAuditLog {
+    pub actor_id: Option<i32>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<i32>,
+    pub changes: Option<Value>,
+    pub ip: Option<String>,
+}
+
+impl NewAuditLog {
+    /// The actor and the address are taken from the request when recorded
+    pub fn new(action: &str, resource_type: &str, resource_id: Option<i32>) -> Self {
+        Self {
+            actor_id: None,
+            action: action.to_string(),
+            resource_type: resource_type.to_string(),
+            resource_id,
+            changes: None,
+            ip: None,
+        }
+    }
+
+    pub fn actor(self, actor_id: i32) -> Self {
+        Self { actor_id: Some(actor_id), ..self }
+    }
+
+    pub fn changes<T: Serialize>(self, before: Option<&T>, after: Option<&T>) -> Self {
+        Self { changes: diff(before, after), ..self }
+    }
+
+    pub fn details(self, details: Value) -> Self {
+        Self { changes: Some(details), ..self }
+    }
+}
+
+/// The fields that differ between both versions, every field when one of them is missing
+pub fn diff<T: Serialize>(before: Option<&T>, after: Option<&T>) -> Option<Value> {
+    let fields = |value: Option<&T>| match value.map(serde_json::to_value) {
+        Some(Ok(Value::Object(fields))) => fields,
+        _ => Map::new(),
+    };
+
+    let (mut before, mut after) = (fields(before), fields(after));
+
+    let unchanged: Vec<String> = before.iter()
+        .filter(|(key, value)| after.get(*key) == Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    for key in unchanged {
+        before.remove(&key);
+        after.remove(&key);
+    }
+
+    match before.is_empty() && after.is_empty() {
+        true => None,
+        false => Some(json!({ "before": before, "after": after })),
+    }
+}
+
+/// Criteria for listing the audit log, every `None` leaves the entries unfiltered
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AuditFilter {
+    pub actor_id: Option<i32>,
+    pub action: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<i32>,
+    /// Entries recorded from then on
+    pub from: Option<NaiveDateTime>,
+    /// Entries recorded until then
+    pub to: Option<NaiveDateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Kite {
+        name: &'static str,
+        price: f32,
+    }
+
+    #[test]
+    fn changes() {
+        let before = Kite { name: "Kite", price: 1500.0 };
+        let after = Kite { name: "Kite", price: 1200.0 };
+
+        assert_eq!(diff(Some(&before), Some(&after)), Some(json!({ "before": { "price": 1500.0 }, "after": { "price": 1200.0 } })));
+        assert_eq!(diff(Some(&before), Some(&before)), None, "nothing changed");
+        assert_eq!(diff(None, Some(&after)), Some(json!({ "before": {}, "after": { "name": "Kite", "price": 1200.0 } })));
+        assert_eq!(diff(Some(&before), None), Some(json!({ "before": { "name": "Kite", "price": 1500.0 }, "after": {} })));
+    }
+}
@@ -0,0 +1,78 @@
+use diesel::prelude::*;
+use diesel::pg::Pg;
+use diesel::result::Error;
+use crate::api::utils::pagination::Paginate;
+use crate::schema::audit_logs;
+use crate::schema::audit_logs::table as audit_logs_table;
+use crate::services::database::{contracts::AuditRepo, establish_connection};
+use super::models::{AuditFilter, AuditLog, NewAuditLog};
+
+pub struct Repo;
+
+impl AuditRepo for Repo {
+    fn insert(&self, new_log: NewAuditLog) -> Result<AuditLog, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::insert_into(audit_logs_table)
+            .values(&new_log)
+            .returning(AuditLog::as_returning())
+            .get_result(conn)
+    }
+
+    fn list(&self, filter: &AuditFilter) -> Result<Vec<AuditLog>, Error> {
+        let conn = &mut establish_connection();
+
+        filtered(filter)
+            .select(AuditLog::as_select())
+            .load(conn)
+    }
+
+    fn list_paginated(&self, filter: &AuditFilter, page: i64, per_page: i64) -> Result<(i64, Vec<AuditLog>), Error> {
+        let conn = &mut establish_connection();
+
+        let results: Vec<(AuditLog, i64)> = filtered(filter)
+            .select(AuditLog::as_select())
+            .paginate(page)
+            .per_page(per_page)
+            .get_results(conn)?;
+
+        match results.first() {
+            None => Ok((0, vec![])),
+            Some((_, entries)) => Ok((
+                *entries,
+                results.into_iter().map(|(log, _)| log).collect())
+            )
+        }
+    }
+}
+
+/// Entries matching the filter, the latest first
+fn filtered(filter: &AuditFilter) -> audit_logs::BoxedQuery<'static, Pg> {
+    let mut query = audit_logs_table.into_boxed();
+
+    if let Some(actor_id) = filter.actor_id {
+        query = query.filter(audit_logs::actor_id.eq(actor_id));
+    }
+
+    if let Some(action) = &filter.action {
+        query = query.filter(audit_logs::action.eq(action.clone()));
+    }
+
+    if let Some(resource_type) = &filter.resource_type {
+        query = query.filter(audit_logs::resource_type.eq(resource_type.clone()));
+    }
+
+    if let Some(resource_id) = filter.resource_id {
+        query = query.filter(audit_logs::resource_id.eq(resource_id));
+    }
+
+    if let Some(from) = filter.from {
+        query = query.filter(audit_logs::created_at.ge(from));
+    }
+
+    if let Some(to) = filter.to {
+        query = query.filter(audit_logs::created_at.le(to));
+    }
+
+    query.order(audit_logs::id.desc())
+}
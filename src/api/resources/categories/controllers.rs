@@ -0,0 +1,123 @@
+use salvo::prelude::*;
+use salvo::http::form::FormData;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::utils::get_db;
+use crate::api::validations::{FormValidator, Validator};
+use crate::api::responses as api_responses;
+use crate::models::Mergeable;
+use super::models::{slugify, NewCategory};
+
+#[handler]
+pub fn list_categories(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.category_repo();
+
+    let categories = repo.list()?;
+
+    api_responses::render_collection(res, categories);
+
+    Ok(())
+}
+
+#[handler]
+pub fn show_category(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.category_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let category = repo.find_one(id)?;
+
+    api_responses::render_resource(res, category);
+
+    Ok(())
+}
+
+#[handler]
+pub async fn create_category(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.category_repo();
+
+    let form_data = req.form_data().await?;
+
+    let new_category = cast_form_data_to_new_category(form_data)?;
+
+    if let Some(parent_id) = new_category.parent_id {
+        repo.find_one(parent_id).map_err(|_| ApiError::BadRequestError("Parent category not found".to_string()))?;
+    }
+
+    let category = repo.insert(new_category)?;
+
+    audit::record(req, depot, NewAuditLog::new("create", "category", Some(category.id)).changes(None, Some(&category)));
+
+    api_responses::render_resource_created(res, category);
+
+    Ok(())
+}
+
+#[handler]
+pub async fn update_category(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.category_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let form_data = req.form_data().await?;
+
+    let current_category = repo.find_one(id)?;
+
+    let category = current_category.clone().merge(&FormValidator(form_data))?;
+
+    if let Some(parent_id) = category.parent_id {
+        repo.find_one(parent_id).map_err(|_| ApiError::BadRequestError("Parent category not found".to_string()))?;
+
+        // A category can't hang from itself or from any of its subcategories
+        if repo.descendant_ids(id)?.contains(&parent_id) {
+            return Err(ApiError::BadRequestError("A category can't be its own ancestor".to_string()));
+        }
+    }
+
+    let updated_category = repo.update(&category)?;
+
+    audit::record(req, depot, NewAuditLog::new("update", "category", Some(id)).changes(Some(&current_category), Some(&updated_category)));
+
+    api_responses::render_resource_updated(res, updated_category);
+
+    Ok(())
+}
+
+#[handler]
+pub fn delete_category(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.category_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let category = repo.find_one(id).ok();
+
+    let total = repo.delete(id)?;
+
+    if total == 0 {
+        return Err(ApiError::Diesel(diesel::result::Error::NotFound));
+    }
+
+    audit::record(req, depot, NewAuditLog::new("delete", "category", Some(id)).changes(category.as_ref(), None));
+
+    api_responses::render_db_execution(res, total);
+
+    Ok(())
+}
+
+fn cast_form_data_to_new_category(form_data: &FormData) -> ApiResult<NewCategory> {
+    let validator = FormValidator(form_data);
+
+    let name = validator.string("name")?;
+
+    let mut new_category = NewCategory::new(&name, validator.optional_integer("parent_id")?);
+
+    if let Some(slug) = validator.optional_string("slug")? {
+        new_category.slug = slugify(&slug);
+    }
+
+    if new_category.slug.is_empty() {
+        return Err(ApiError::BadRequestError("`name` cannot be empty".to_string()));
+    }
+
+    Ok(new_category)
+}
@@ -0,0 +1,23 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use salvo::prelude::*;
+use self::controllers::{create_category, delete_category, list_categories, show_category, update_category};
+
+pub fn get_router() -> Router {
+    Router::with_path("categories")
+        .get(list_categories)
+        .push(Router::with_path("<id>")
+            .get(show_category)
+        )
+}
+
+pub fn get_admin_router() -> Router {
+    Router::with_path("categories")
+        .post(create_category)
+        .push(Router::with_path("<id>")
+            .put(update_category)
+            .delete(delete_category)
+        )
+}
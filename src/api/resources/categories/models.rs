@@ -0,0 +1,108 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::validations::Validator;
+use crate::models::Mergeable;
+use crate::schema;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable, Identifiable, AsChangeset)]
+#[diesel(table_name = schema::categories)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Category {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<i32>,
+}
+
+/// What listed products tell about their category
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = schema::categories)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ListedCategory {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+}
+
+impl From<Category> for ListedCategory {
+    fn from(value: Category) -> Self {
+        let Category { id, name, slug, .. } = value;
+
+        Self { id, name, slug }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Insertable)]
+#[diesel(table_name = schema::categories)]
+pub struct NewCategory {
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<i32>,
+}
+
+impl NewCategory {
+    pub fn new(name: &str, parent_id: Option<i32>) -> Self {
+        Self { name: name.trim().to_string(), slug: slugify(name), parent_id }
+    }
+}
+
+impl Mergeable for Category {
+    fn merge(self, validator: &dyn Validator) -> ApiResult<Self> {
+        let mut updated = self.clone();
+
+        if validator.contains("name") {
+            let name = validator.optional_string("name")?.unwrap_or_default();
+
+            if name.trim().is_empty() {
+                return Err(ApiError::BadRequestError("`name` cannot be empty".to_string()));
+            }
+
+            updated.name = name.trim().to_string();
+        }
+
+        if validator.contains("slug") {
+            let slug = slugify(&validator.optional_string("slug")?.unwrap_or_default());
+
+            if slug.is_empty() {
+                return Err(ApiError::BadRequestError("`slug` cannot be empty".to_string()));
+            }
+
+            updated.slug = slug;
+        }
+
+        if validator.contains("parent_id") {
+            updated.parent_id = match validator.optional_string("parent_id")?.unwrap_or_default().is_empty() {
+                true => None,
+                false => Some(validator.integer("parent_id")?),
+            };
+        }
+
+        Ok(updated)
+    }
+}
+
+/// Lowercase ascii words joined by dashes, e.g. `Video Juegos!` -> `video-juegos`
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::slugify;
+
+    #[test]
+    fn slugify_names() {
+        assert_eq!(slugify("Consolas"), "consolas");
+        assert_eq!(slugify("  Video Juegos! "), "video-juegos");
+        assert_eq!(slugify("Hogar & Cocina"), "hogar-cocina");
+        assert_eq!(slugify("Electrónica"), "electrónica");
+    }
+}
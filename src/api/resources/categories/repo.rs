@@ -0,0 +1,101 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::Int4;
+use crate::schema::categories;
+use crate::schema::categories::table as categories_table;
+use crate::services::database::{contracts::CategoryRepo, establish_connection};
+use super::models::{Category, NewCategory};
+
+#[derive(QueryableByName)]
+struct CategoryId {
+    #[diesel(sql_type = Int4)]
+    id: i32,
+}
+
+pub struct Repo;
+
+impl CategoryRepo for Repo {
+    fn list(&self) -> Result<Vec<Category>, Error> {
+        let conn = &mut establish_connection();
+
+        categories_table
+            .order((categories::parent_id.asc().nulls_first(), categories::name.asc()))
+            .select(Category::as_select())
+            .load(conn)
+    }
+
+    fn find_one(&self, id: i32) -> Result<Category, Error> {
+        let conn = &mut establish_connection();
+
+        categories_table
+            .find(id)
+            .select(Category::as_select())
+            .first(conn)
+    }
+
+    fn find_by_slug(&self, slug: &str) -> Result<Category, Error> {
+        let conn = &mut establish_connection();
+
+        categories_table
+            .filter(categories::slug.eq(slug))
+            .select(Category::as_select())
+            .first(conn)
+    }
+
+    fn find_or_insert(&self, new_category: NewCategory) -> Result<Category, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::insert_into(categories_table)
+            .values(&new_category)
+            .on_conflict(categories::slug)
+            .do_nothing()
+            .execute(conn)?;
+
+        categories_table
+            .filter(categories::slug.eq(&new_category.slug))
+            .select(Category::as_select())
+            .first(conn)
+    }
+
+    fn insert(&self, new_category: NewCategory) -> Result<Category, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::insert_into(categories_table)
+            .values(&new_category)
+            .returning(Category::as_returning())
+            .get_result(conn)
+    }
+
+    fn update(&self, category: &Category) -> Result<Category, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(categories_table.find(category.id))
+            .set(category)
+            .returning(Category::as_returning())
+            .get_result(conn)
+    }
+
+    fn delete(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::delete(categories_table.find(id))
+            .execute(conn)
+    }
+
+    fn descendant_ids(&self, id: i32) -> Result<Vec<i32>, Error> {
+        let conn = &mut establish_connection();
+
+        let ids: Vec<CategoryId> = diesel::sql_query(r#"
+            WITH RECURSIVE tree AS (
+                SELECT id FROM categories WHERE id = $1
+                UNION
+                SELECT c.id FROM categories c INNER JOIN tree ON c.parent_id = tree.id
+            )
+            SELECT id FROM tree
+        "#)
+            .bind::<Int4, _>(id)
+            .load(conn)?;
+
+        Ok(ids.into_iter().map(|category| category.id).collect())
+    }
+}
@@ -0,0 +1,104 @@
+use std::str::FromStr;
+use salvo::prelude::*;
+use salvo::http::form::FormData;
+use serde_json::json;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::policy::{authorize_wishlist, Action, Subject};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::utils::{get_db, get_user_id};
+use crate::api::validations::{FormValidator, Validator};
+use crate::api::responses as api_responses;
+use super::models::{CollaboratorRole, NewCollaborator};
+
+#[handler]
+pub fn list_collaborators(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Edit, &wishlist)?;
+
+    let collaborators = db.collaborator_repo().list_by_wishlist(wishlist_id)?;
+
+    api_responses::render_collection(res, collaborators);
+
+    Ok(())
+}
+
+#[handler]
+pub async fn add_collaborator(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let form_data = req.form_data().await?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Manage, &wishlist)?;
+
+    let new_collaborator = cast_form_data_to_new_collaborator(form_data, wishlist_id)?;
+
+    if new_collaborator.user_id == wishlist.user_id {
+        return Err(ApiError::BadRequestError("The wishlist creator is already an owner".to_string()));
+    }
+
+    let collaborator = db.collaborator_repo().insert(new_collaborator)?;
+
+    audit::record(req, depot, NewAuditLog::new("add_collaborator", "wishlist", Some(wishlist_id))
+        .details(json!({ "user_id": collaborator.user_id, "role": collaborator.role })));
+
+    api_responses::render_resource_created(res, collaborator);
+
+    Ok(())
+}
+
+#[handler]
+pub fn remove_collaborator(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let collaborator_id = req.param::<i32>("user_id").ok_or(ApiError::FieldNotFound("user_id".to_string()))?;
+
+    let user_id = get_user_id(depot).ok_or(ApiError::NotAllowed("User not found".to_string()))?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    // Collaborators can always leave a wishlist by themselves
+    if collaborator_id != user_id {
+        authorize_wishlist(db, &Subject::from_depot(depot), Action::Manage, &wishlist)?;
+    }
+
+    let total = db.collaborator_repo().delete(wishlist_id, collaborator_id)?;
+
+    if total == 0 {
+        return Err(ApiError::Diesel(diesel::result::Error::NotFound));
+    }
+
+    audit::record(req, depot, NewAuditLog::new("remove_collaborator", "wishlist", Some(wishlist_id))
+        .details(json!({ "user_id": collaborator_id })));
+
+    api_responses::render_db_execution(res, total);
+
+    Ok(())
+}
+
+fn cast_form_data_to_new_collaborator(form_data: &FormData, wishlist_id: i32) -> ApiResult<NewCollaborator> {
+    let validator = FormValidator(form_data);
+
+    let role = match validator.optional_string("role")? {
+        None => CollaboratorRole::Editor,
+        Some(role) => CollaboratorRole::from_str(&role).map_err(ApiError::BadRequestError)?,
+    };
+
+    let new_collaborator = NewCollaborator {
+        wishlist_id,
+        user_id: validator.integer("user_id")?,
+        role: role.to_string(),
+    };
+
+    Ok(new_collaborator)
+}
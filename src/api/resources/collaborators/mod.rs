@@ -0,0 +1,18 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use salvo::prelude::*;
+use crate::api::auth;
+use auth::controllers::handle_auth;
+use self::controllers::{add_collaborator, list_collaborators, remove_collaborator};
+
+pub fn get_router_for_wishlists() -> Router {
+    Router::with_path("wishlists/<wishlist_id>/collaborators")
+        .hoop(handle_auth)
+        .get(list_collaborators)
+        .post(add_collaborator)
+        .push(Router::with_path("<user_id>")
+            .delete(remove_collaborator)
+        )
+}
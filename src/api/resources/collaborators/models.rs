@@ -0,0 +1,68 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::api::resources::users::models::User;
+use crate::api::resources::wishlists::models::Wishlist;
+use crate::schema;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum CollaboratorRole {
+    Owner,
+    Editor,
+}
+
+impl CollaboratorRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CollaboratorRole::Owner => "OWNER",
+            CollaboratorRole::Editor => "EDITOR",
+        }
+    }
+}
+
+impl FromStr for CollaboratorRole {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_uppercase().as_str() {
+            "OWNER" => Ok(CollaboratorRole::Owner),
+            "EDITOR" => Ok(CollaboratorRole::Editor),
+            other => Err(format!("`{other}` is not a valid collaborator role")),
+        }
+    }
+}
+
+impl Display for CollaboratorRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable, Identifiable, Associations)]
+#[diesel(belongs_to(Wishlist))]
+#[diesel(belongs_to(User))]
+#[diesel(table_name = schema::collaborators)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Collaborator {
+    pub id: i32,
+    pub wishlist_id: i32,
+    pub user_id: i32,
+    pub role: String,
+}
+
+impl Collaborator {
+    pub fn role(&self) -> CollaboratorRole {
+        CollaboratorRole::from_str(&self.role).unwrap_or(CollaboratorRole::Editor)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[derive(Insertable)]
+#[diesel(table_name = schema::collaborators)]
+pub struct NewCollaborator {
+    pub wishlist_id: i32,
+    pub user_id: i32,
+    pub role: String,
+}
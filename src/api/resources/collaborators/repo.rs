@@ -0,0 +1,51 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+use crate::schema::collaborators::{self, table as collaborators_table};
+use crate::services::database::{contracts::CollaboratorRepo, establish_connection};
+use super::models::{Collaborator, NewCollaborator};
+
+pub struct Repo;
+
+impl CollaboratorRepo for Repo {
+    fn list_by_wishlist(&self, wishlist_id: i32) -> Result<Vec<Collaborator>, Error> {
+        let conn = &mut establish_connection();
+
+        collaborators_table
+            .filter(collaborators::wishlist_id.eq(wishlist_id))
+            .select(Collaborator::as_select())
+            .load(conn)
+    }
+
+    fn find_one(&self, wishlist_id: i32, user_id: i32) -> Result<Collaborator, Error> {
+        let conn = &mut establish_connection();
+
+        collaborators_table
+            .filter(collaborators::wishlist_id.eq(wishlist_id))
+            .filter(collaborators::user_id.eq(user_id))
+            .select(Collaborator::as_select())
+            .first(conn)
+    }
+
+    fn insert(&self, new_collaborator: NewCollaborator) -> Result<Collaborator, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::insert_into(collaborators_table)
+            .values(&new_collaborator)
+            .on_conflict((collaborators::wishlist_id, collaborators::user_id))
+            .do_update()
+            .set(collaborators::role.eq(&new_collaborator.role))
+            .returning(Collaborator::as_returning())
+            .get_result(conn)
+    }
+
+    fn delete(&self, wishlist_id: i32, user_id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::delete(
+            collaborators_table
+                .filter(collaborators::wishlist_id.eq(wishlist_id))
+                .filter(collaborators::user_id.eq(user_id)),
+        )
+        .execute(conn)
+    }
+}
@@ -1,8 +1,10 @@
 use diesel::prelude::*;
 use serde::Serialize;
+use chrono::NaiveDateTime;
 use crate::api::resources::users::models::User;
 use crate::api::resources::wishlists::models::Wishlist;
 use crate::schema;
+use crate::api::utils::formatters::date;
 
 
 #[derive(Serialize, Debug, Clone)]
@@ -16,6 +18,10 @@ pub struct Follower {
     pub wishlist_id: i32,
     pub user_id: i32,
     pub active: bool,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
 }
 
 #[derive(Debug, PartialEq, PartialOrd)]
@@ -1,4 +1,7 @@
+use diesel::prelude::*;
 use crate::db::contracts::FollowerRepo;
+use crate::db::establish_connection;
+use crate::schema::followers::{self, table as followers_table};
 use diesel::result::Error;
 use super::models::{Follower, NewFollower};
 
@@ -7,15 +10,27 @@ pub struct Repo;
 
 
 impl FollowerRepo for Repo {
-    fn insert(&self, new_follower: NewFollower) -> Result<Follower, Error> {
+    fn insert(&self, _new_follower: NewFollower) -> Result<Follower, Error> {
         todo!()
     }
 
-    fn update(&self, follower: &Follower) -> Result<Follower, Error> {
+    fn update(&self, _follower: &Follower) -> Result<Follower, Error> {
         todo!()
     }
 
-    fn delete(&self, id: i32) -> Result<usize, Error> {
+    fn delete(&self, _id: i32) -> Result<usize, Error> {
         todo!()
     }
+
+    fn is_following(&self, wishlist_id: i32, user_id: i32) -> Result<bool, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::select(diesel::dsl::exists(
+            followers_table
+                .filter(followers::wishlist_id.eq(wishlist_id))
+                .filter(followers::user_id.eq(user_id))
+                .filter(followers::active.eq(true)),
+        ))
+        .get_result(conn)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,206 @@
+use salvo::prelude::*;
+use salvo::http::header::{HeaderValue, CONTENT_TYPE};
+use serde_json::json;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::policy::{authorize, authorize_wishlist, Action, Resource, Subject};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::utils::{get_db, get_storage, random_token, read_file_field, UploadedFile};
+use crate::api::responses as api_responses;
+use crate::services::storage::contracts::Storage;
+use super::models::{ImageResource, NewImage};
+use super::upload::{self, ProcessedImage, MAX_IMAGE_SIZE};
+
+const KEY_LENGTH: usize = 24;
+
+#[handler]
+pub fn list_product_images(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+    let storage = get_storage(depot)?;
+
+    let product_id = req.param::<i32>("product_id").ok_or(ApiError::FieldNotFound("product_id".to_string()))?;
+
+    let product = db.product_repo().find_one(product_id)?;
+
+    authorize(&Subject::from_depot(depot), Action::View, &Resource::Product(&product))?;
+
+    let images = db.image_repo().list_by_product(product_id)?
+        .into_iter()
+        .map(|image| ImageResource::new(image, storage.as_ref()))
+        .collect();
+
+    api_responses::render_collection(res, images);
+
+    Ok(())
+}
+
+#[handler]
+pub async fn add_product_image(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+    let storage = get_storage(depot)?;
+
+    let product_id = req.param::<i32>("product_id").ok_or(ApiError::FieldNotFound("product_id".to_string()))?;
+
+    db.product_repo().find_one(product_id)?;
+
+    let processed = read_image(req).await?;
+
+    let new_image = store_image(storage.as_ref(), &format!("products/{product_id}"), processed)?;
+
+    let image = db.image_repo().insert(NewImage { product_id: Some(product_id), ..new_image })?;
+
+    audit::record(req, depot, NewAuditLog::new("add_image", "product", Some(product_id)).details(json!({ "image_id": image.id })));
+
+    api_responses::render_resource_created(res, ImageResource::new(image, storage.as_ref()));
+
+    Ok(())
+}
+
+#[handler]
+pub fn remove_product_image(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.image_repo();
+    let storage = get_storage(depot)?;
+
+    let product_id = req.param::<i32>("product_id").ok_or(ApiError::FieldNotFound("product_id".to_string()))?;
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let image = repo.find_one(id)?;
+
+    if image.product_id != Some(product_id) {
+        return Err(ApiError::Diesel(diesel::result::Error::NotFound));
+    }
+
+    let total = repo.delete(id)?;
+
+    audit::record(req, depot, NewAuditLog::new("remove_image", "product", Some(product_id)).details(json!({ "image_id": id })));
+
+    upload::discard_files(storage.as_ref(), &[image]);
+
+    api_responses::render_db_execution(res, total);
+
+    Ok(())
+}
+
+#[handler]
+pub fn show_wishlist_cover(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+    let storage = get_storage(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::View, &wishlist)?;
+
+    let cover = db.image_repo().find_wishlist_cover(wishlist_id)?;
+
+    api_responses::render_resource(res, ImageResource::new(cover, storage.as_ref()));
+
+    Ok(())
+}
+
+/// Uploads the cover of the wishlist, replacing the previous one
+#[handler]
+pub async fn set_wishlist_cover(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+    let storage = get_storage(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Edit, &wishlist)?;
+
+    let processed = read_image(req).await?;
+
+    let new_image = store_image(storage.as_ref(), &format!("wishlists/{wishlist_id}"), processed)?;
+
+    let (cover, previous) = db.image_repo().replace_wishlist_cover(NewImage { wishlist_id: Some(wishlist_id), ..new_image })?;
+
+    audit::record(req, depot, NewAuditLog::new("set_cover", "wishlist", Some(wishlist_id)).details(json!({
+        "before": { "image_id": previous.as_ref().map(|image| image.id) },
+        "after": { "image_id": cover.id },
+    })));
+
+    upload::discard_files(storage.as_ref(), &Vec::from_iter(previous));
+
+    api_responses::render_resource_updated(res, ImageResource::new(cover, storage.as_ref()));
+
+    Ok(())
+}
+
+#[handler]
+pub fn remove_wishlist_cover(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+    let storage = get_storage(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Edit, &wishlist)?;
+
+    let repo = db.image_repo();
+
+    let cover = repo.find_wishlist_cover(wishlist_id)?;
+
+    let total = repo.delete(cover.id)?;
+
+    audit::record(req, depot, NewAuditLog::new("remove_cover", "wishlist", Some(wishlist_id)).details(json!({ "image_id": cover.id })));
+
+    upload::discard_files(storage.as_ref(), &[cover]);
+
+    api_responses::render_db_execution(res, total);
+
+    Ok(())
+}
+
+/// Serves the stored files, their keys are random so only those who got them
+/// from the api can fetch them
+#[handler]
+pub fn serve_upload(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let storage = get_storage(depot)?;
+
+    let path = req.param::<String>("**path").ok_or(ApiError::FieldNotFound("path".to_string()))?;
+
+    let content = storage.get(&path)?;
+
+    let content_type = match path.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("png") => "image/png",
+        Some("jpg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    };
+
+    res.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    res.body(content.into());
+
+    Ok(())
+}
+
+async fn read_image(req: &mut Request) -> ApiResult<ProcessedImage> {
+    let UploadedFile { content_type, content } = read_file_field(req, "image", MAX_IMAGE_SIZE).await?;
+
+    // Decoding and resizing are cpu bound, keep them off the async workers
+    tokio::task::spawn_blocking(move || upload::process(content_type.as_deref(), content)).await?
+}
+
+/// Saves the image and its thumbnail under random keys within the prefix
+fn store_image(storage: &dyn Storage, prefix: &str, processed: ProcessedImage) -> ApiResult<NewImage> {
+    let ProcessedImage { content_type, extension, content, thumbnail } = processed;
+
+    let key = random_token(KEY_LENGTH);
+    let path = format!("{prefix}/{key}.{extension}");
+    let thumbnail_path = format!("{prefix}/{key}_thumb.png");
+
+    storage.put(&path, &content)?;
+    storage.put(&thumbnail_path, &thumbnail)?;
+
+    Ok(NewImage {
+        product_id: None,
+        wishlist_id: None,
+        content_type: content_type.to_string(),
+        path,
+        thumbnail_path,
+    })
+}
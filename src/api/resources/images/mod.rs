@@ -0,0 +1,46 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+pub mod upload;
+
+use salvo::prelude::*;
+use crate::api::auth;
+use crate::api::policy::require_product_writes;
+use auth::controllers::handle_auth;
+use self::controllers::{
+    add_product_image,
+    list_product_images,
+    remove_product_image,
+    remove_wishlist_cover,
+    serve_upload,
+    set_wishlist_cover,
+    show_wishlist_cover,
+};
+
+pub fn get_router_for_products() -> Router {
+    Router::with_path("products/<product_id>/images")
+        .get(list_product_images)
+        .push(Router::new()
+            .hoop(handle_auth)
+            .hoop(require_product_writes)
+            .post(add_product_image)
+            .push(Router::with_path("<id>")
+                .delete(remove_product_image)
+            )
+        )
+}
+
+pub fn get_router_for_wishlists() -> Router {
+    Router::with_path("wishlists/<wishlist_id>/cover")
+        .get(show_wishlist_cover)
+        .push(Router::new()
+            .hoop(handle_auth)
+            .put(set_wishlist_cover)
+            .delete(remove_wishlist_cover)
+        )
+}
+
+pub fn get_uploads_router() -> Router {
+    Router::with_path("uploads/<**path>")
+        .get(serve_upload)
+}
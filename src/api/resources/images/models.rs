@@ -0,0 +1,54 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::schema;
+use crate::services::storage::contracts::Storage;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = schema::images)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Image {
+    pub id: i32,
+    pub product_id: Option<i32>,
+    pub wishlist_id: Option<i32>,
+    pub content_type: String,
+    pub path: String,
+    pub thumbnail_path: String,
+}
+
+impl Image {
+    /// Storage keys of the image and its thumbnail
+    pub fn paths(&self) -> [&str; 2] {
+        [&self.path, &self.thumbnail_path]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Insertable)]
+#[diesel(table_name = schema::images)]
+pub struct NewImage {
+    pub product_id: Option<i32>,
+    pub wishlist_id: Option<i32>,
+    pub content_type: String,
+    pub path: String,
+    pub thumbnail_path: String,
+}
+
+/// Images as rendered, with the addresses where the storage serves them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ImageResource {
+    pub id: i32,
+    pub content_type: String,
+    pub url: String,
+    pub thumbnail_url: String,
+}
+
+impl ImageResource {
+    pub fn new(image: Image, storage: &dyn Storage) -> Self {
+        Self {
+            id: image.id,
+            url: storage.url(&image.path),
+            thumbnail_url: storage.url(&image.thumbnail_path),
+            content_type: image.content_type,
+        }
+    }
+}
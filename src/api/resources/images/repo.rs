@@ -0,0 +1,71 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+use crate::schema::images::{self, table as images_table};
+use crate::services::database::{contracts::ImageRepo, establish_connection};
+use super::models::{Image, NewImage};
+
+pub struct Repo;
+
+impl ImageRepo for Repo {
+    fn find_one(&self, id: i32) -> Result<Image, Error> {
+        let conn = &mut establish_connection();
+
+        images_table
+            .find(id)
+            .select(Image::as_select())
+            .first(conn)
+    }
+
+    fn list_by_product(&self, product_id: i32) -> Result<Vec<Image>, Error> {
+        let conn = &mut establish_connection();
+
+        images_table
+            .filter(images::product_id.eq(product_id))
+            .order(images::id)
+            .select(Image::as_select())
+            .load(conn)
+    }
+
+    fn find_wishlist_cover(&self, wishlist_id: i32) -> Result<Image, Error> {
+        let conn = &mut establish_connection();
+
+        images_table
+            .filter(images::wishlist_id.eq(wishlist_id))
+            .select(Image::as_select())
+            .first(conn)
+    }
+
+    fn insert(&self, new_image: NewImage) -> Result<Image, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::insert_into(images_table)
+            .values(&new_image)
+            .returning(Image::as_returning())
+            .get_result(conn)
+    }
+
+    fn replace_wishlist_cover(&self, new_image: NewImage) -> Result<(Image, Option<Image>), Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let previous = diesel::delete(images_table)
+                .filter(images::wishlist_id.eq(new_image.wishlist_id))
+                .returning(Image::as_returning())
+                .get_result(conn)
+                .optional()?;
+
+            let image = diesel::insert_into(images_table)
+                .values(&new_image)
+                .returning(Image::as_returning())
+                .get_result(conn)?;
+
+            Ok((image, previous))
+        })
+    }
+
+    fn delete(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::delete(images_table.find(id)).execute(conn)
+    }
+}
@@ -0,0 +1,124 @@
+use std::io::Cursor;
+use image::{ImageFormat, ImageOutputFormat};
+use image::io::{Limits, Reader};
+use crate::api::errors::{ApiError, ApiResult};
+use crate::services::storage::contracts::Storage;
+use super::models::Image;
+
+/// Uploads above this size are rejected before being decoded
+pub const MAX_IMAGE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Images wider or taller than this are rejected without being decoded
+pub const MAX_IMAGE_DIMENSION: u32 = 8000;
+
+/// Upper bound of the memory allocated while decoding an image
+const MAX_DECODING_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// Thumbnails fit in a square of this side, keeping the aspect ratio
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+/// A validated upload, ready to be stored
+#[derive(Debug)]
+pub struct ProcessedImage {
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub content: Vec<u8>,
+    /// Always encoded as png
+    pub thumbnail: Vec<u8>,
+}
+
+fn accepted_format(content_type: &str) -> Option<(ImageFormat, &'static str, &'static str)> {
+    match content_type {
+        "image/png" => Some((ImageFormat::Png, "image/png", "png")),
+        "image/jpeg" => Some((ImageFormat::Jpeg, "image/jpeg", "jpg")),
+        "image/webp" => Some((ImageFormat::WebP, "image/webp", "webp")),
+        _ => None,
+    }
+}
+
+/// Checks that the content is a png, jpeg or webp image matching its declared
+/// content type, and generates its thumbnail
+pub fn process(content_type: Option<&str>, content: Vec<u8>) -> ApiResult<ProcessedImage> {
+    if content.len() as u64 > MAX_IMAGE_SIZE {
+        return Err(ApiError::PayloadTooLarge(format!("Images can't be larger than {MAX_IMAGE_SIZE} bytes")));
+    }
+
+    let declared = content_type.unwrap_or_default();
+
+    let (format, content_type, extension) = accepted_format(declared)
+        .ok_or(ApiError::BadRequestError(format!("Unsupported image type `{declared}`, use png, jpeg or webp")))?;
+
+    if image::guess_format(&content).ok() != Some(format) {
+        return Err(ApiError::BadRequestError(format!("The file content is not a valid `{content_type}`")));
+    }
+
+    let mut limits = Limits::default();
+
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODING_ALLOC);
+
+    let mut reader = Reader::with_format(Cursor::new(&content), format);
+
+    reader.limits(limits);
+
+    let decoded = reader.decode()
+        .map_err(|error| ApiError::BadRequestError(format!("Error decoding the image: {error}")))?;
+
+    let mut thumbnail = Cursor::new(vec![]);
+
+    decoded.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .write_to(&mut thumbnail, ImageOutputFormat::Png)
+        .map_err(|error| ApiError::BadRequestError(format!("Error generating the thumbnail: {error}")))?;
+
+    Ok(ProcessedImage { content_type, extension, content, thumbnail: thumbnail.into_inner() })
+}
+
+/// Removes the stored files of images whose rows are gone, a failure only leaves orphan files behind
+pub fn discard_files(storage: &dyn Storage, images: &[Image]) {
+    for path in images.iter().flat_map(|image| image.paths()) {
+        let _ = storage.delete(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, GenericImageView};
+
+    fn encoded(width: u32, height: u32, format: ImageOutputFormat) -> Vec<u8> {
+        let mut content = Cursor::new(vec![]);
+
+        DynamicImage::new_rgb8(width, height).write_to(&mut content, format).unwrap();
+
+        content.into_inner()
+    }
+
+    #[test]
+    fn process_image() {
+        let processed = process(Some("image/jpeg"), encoded(800, 400, ImageOutputFormat::Jpeg(80))).unwrap();
+        let thumbnail = image::load_from_memory(&processed.thumbnail).unwrap();
+
+        assert_eq!(processed.extension, "jpg");
+        assert_eq!(thumbnail.dimensions(), (THUMBNAIL_SIZE, THUMBNAIL_SIZE / 2), "thumbnails keep the aspect ratio");
+        assert_eq!(image::guess_format(&processed.thumbnail).unwrap(), ImageFormat::Png);
+    }
+
+    #[test]
+    fn reject_invalid_images() {
+        let png = encoded(10, 10, ImageOutputFormat::Png);
+
+        assert!(matches!(process(Some("image/gif"), png.clone()), Err(ApiError::BadRequestError(_))), "only png, jpeg and webp");
+        assert!(matches!(process(None, png.clone()), Err(ApiError::BadRequestError(_))), "the content type is required");
+        assert!(matches!(process(Some("image/jpeg"), png), Err(ApiError::BadRequestError(_))), "the content must match its type");
+        assert!(matches!(process(Some("image/png"), b"not an image".to_vec()), Err(ApiError::BadRequestError(_))));
+        assert!(matches!(process(Some("image/png"), vec![0; MAX_IMAGE_SIZE as usize + 1]), Err(ApiError::PayloadTooLarge(_))));
+    }
+
+    #[test]
+    fn reject_large_dimensions() {
+        let png = encoded(MAX_IMAGE_DIMENSION + 1, 1, ImageOutputFormat::Png);
+
+        assert!(matches!(process(Some("image/png"), png), Err(ApiError::BadRequestError(_))));
+    }
+}
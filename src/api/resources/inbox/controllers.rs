@@ -0,0 +1,69 @@
+use salvo::prelude::*;
+use crate::api::errors::{self as api_errors, ApiError, ApiResult};
+use crate::api::responses as api_responses;
+use crate::api::utils::{get_db, get_user_id};
+use crate::api::utils::pagination::{Pagination, DEFAULT_PER_PAGE};
+use crate::api::validations::{QueryValidator, Validator};
+use crate::services::notifications::messages::Locale;
+use super::models::{DetailedNotification, UnreadCount};
+
+/// The user's notifications written in their locale, the latest first, only the unread ones with `unread=true`
+#[handler]
+pub fn list_notifications(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let user = db.user_repo().find_user(get_user_id(depot).unwrap_or_default())?;
+
+    let unread = QueryValidator(req).optional_boolean("unread")?.unwrap_or(false);
+    let page = req.query::<i64>("page").unwrap_or(1).max(1);
+    let per_page = req.query::<i64>("per_page").unwrap_or(DEFAULT_PER_PAGE).max(1);
+
+    let (entries, notifications) = db.inbox_repo().list_paginated(user.id, unread, page, per_page)?;
+
+    let locale = Locale::from(user.locale.as_str());
+
+    let notifications = notifications.into_iter()
+        .map(|notification| DetailedNotification::new(notification, &user.name, locale))
+        .collect();
+
+    api_responses::render_collection_paginated(res, notifications, Pagination::new(page, per_page, entries));
+
+    Ok(())
+}
+
+/// For the bell icon
+#[handler]
+pub fn count_unread_notifications(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.inbox_repo();
+
+    let unread = repo.count_unread(get_user_id(depot).unwrap_or_default())?;
+
+    api_responses::render_resource(res, UnreadCount { unread });
+
+    Ok(())
+}
+
+#[handler]
+pub fn read_notification(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.inbox_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    match repo.mark_read(get_user_id(depot).unwrap_or_default(), id)? {
+        0 => api_errors::render_resource_not_found(res, "notification"),
+        total => api_responses::render_db_execution(res, total),
+    }
+
+    Ok(())
+}
+
+#[handler]
+pub fn read_all_notifications(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.inbox_repo();
+
+    let total = repo.mark_all_read(get_user_id(depot).unwrap_or_default())?;
+
+    api_responses::render_db_execution(res, total);
+
+    Ok(())
+}
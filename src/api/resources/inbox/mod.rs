@@ -0,0 +1,42 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use std::error::Error;
+use salvo::prelude::*;
+use crate::api::auth::controllers::handle_auth;
+use crate::api::resources::outbox;
+use crate::api::utils::get_db;
+use crate::services::notifications::contracts::Notifier;
+use crate::services::notifications::messages::Message;
+use crate::services::notifications::InboxNotifier;
+use self::controllers::{count_unread_notifications, list_notifications, read_all_notifications, read_notification};
+
+pub fn get_router() -> Router {
+    Router::with_path("me/notifications")
+        .hoop(handle_auth)
+        .get(list_notifications)
+        .push(Router::with_path("unread").get(count_unread_notifications))
+        .push(Router::with_path("read").put(read_all_notifications))
+        .push(Router::with_path("<id>/read").put(read_notification))
+}
+
+/// Shows the message in the recipient's inbox and queues its delivery,
+/// a failure to notify doesn't undo the action
+pub fn notify(depot: &Depot, recipient_id: i32, message: &Message) {
+    if let Err(error) = store_and_enqueue(depot, recipient_id, message) {
+        tracing::error!("Notifying user {recipient_id} of {} failed: {error}", message.kind());
+    }
+}
+
+fn store_and_enqueue(depot: &Depot, recipient_id: i32, message: &Message) -> Result<(), Box<dyn Error>> {
+    let db = get_db(depot)?;
+
+    let recipient = db.user_repo().find_user(recipient_id)?;
+
+    InboxNotifier::new(db.clone()).send(&recipient, message)?;
+
+    outbox::enqueue(depot, recipient_id, message)?;
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use chrono::NaiveDateTime;
+use crate::api::utils::formatters::{date, optional_date};
+use crate::schema::notifications;
+use crate::services::notifications::messages::{Locale, Message};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = notifications)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Notification {
+    pub id: i32,
+    pub user_id: i32,
+    pub kind: String,
+    /// The serialized `Message`, rendered in the user's locale when listed
+    pub message: Value,
+    #[serde(with = "optional_date")]
+    pub read_at: Option<NaiveDateTime>,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+}
+
+impl Notification {
+    pub fn message(&self) -> Result<Message, serde_json::Error> {
+        serde_json::from_value(self.message.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Insertable)]
+#[diesel(table_name = notifications)]
+pub struct NewNotification {
+    pub user_id: i32,
+    pub kind: String,
+    pub message: Value,
+}
+
+impl NewNotification {
+    pub fn new(user_id: i32, message: &Message) -> Self {
+        Self {
+            user_id,
+            kind: message.kind().to_string(),
+            message: serde_json::to_value(message).unwrap_or_default(),
+        }
+    }
+}
+
+/// A notification as shown in the app, written in the user's locale
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DetailedNotification {
+    pub id: i32,
+    pub kind: String,
+    pub subject: String,
+    pub text: String,
+    pub message: Value,
+    pub read: bool,
+    #[serde(with = "optional_date")]
+    pub read_at: Option<NaiveDateTime>,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+}
+
+impl DetailedNotification {
+    /// Messages that can't be read anymore keep an empty subject and text
+    pub fn new(notification: Notification, recipient: &str, locale: Locale) -> Self {
+        let (subject, text) = match notification.message() {
+            Ok(message) => {
+                let rendered = message.render(recipient, locale);
+                (rendered.subject, rendered.text)
+            },
+            Err(_) => (String::new(), String::new()),
+        };
+
+        let Notification { id, kind, message, read_at, created_at, .. } = notification;
+
+        Self { id, kind, subject, text, message, read: read_at.is_some(), read_at, created_at }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct UnreadCount {
+    pub unread: i64,
+}
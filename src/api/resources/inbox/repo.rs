@@ -0,0 +1,88 @@
+use diesel::prelude::*;
+use diesel::pg::Pg;
+use diesel::result::Error;
+use diesel::sql_types::{Nullable, Timestamp};
+use chrono::Utc;
+use crate::api::utils::pagination::Paginate;
+use crate::schema::notifications;
+use crate::schema::notifications::table as notifications_table;
+use crate::services::database::{contracts::InboxRepo, establish_connection};
+use super::models::{NewNotification, Notification};
+
+pub struct Repo;
+
+sql_function!(fn coalesce(x: Nullable<Timestamp>, y: Timestamp) -> Timestamp);
+
+impl InboxRepo for Repo {
+    fn insert(&self, new_notification: NewNotification) -> Result<Notification, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::insert_into(notifications_table)
+            .values(&new_notification)
+            .returning(Notification::as_returning())
+            .get_result(conn)
+    }
+
+    fn list_paginated(&self, user_id: i32, unread: bool, page: i64, per_page: i64) -> Result<(i64, Vec<Notification>), Error> {
+        let conn = &mut establish_connection();
+
+        let results: Vec<(Notification, i64)> = filtered(user_id, unread)
+            .order(notifications::id.desc())
+            .select(Notification::as_select())
+            .paginate(page)
+            .per_page(per_page)
+            .get_results(conn)?;
+
+        match results.first() {
+            None => Ok((0, vec![])),
+            Some((_, entries)) => Ok((
+                *entries,
+                results.into_iter().map(|(notification, _)| notification).collect())
+            )
+        }
+    }
+
+    fn count_unread(&self, user_id: i32) -> Result<i64, Error> {
+        let conn = &mut establish_connection();
+
+        filtered(user_id, true)
+            .count()
+            .get_result(conn)
+    }
+
+    fn mark_read(&self, user_id: i32, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        let notification = notifications_table
+            .filter(notifications::id.eq(id))
+            .filter(notifications::user_id.eq(user_id));
+
+        // reading it again keeps the first time
+        diesel::update(notification)
+            .set(notifications::read_at.eq(coalesce(notifications::read_at, Utc::now().naive_utc()).nullable()))
+            .execute(conn)
+    }
+
+    fn mark_all_read(&self, user_id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(notifications_table
+            .filter(notifications::user_id.eq(user_id))
+            .filter(notifications::read_at.is_null()))
+            .set(notifications::read_at.eq(Utc::now().naive_utc()))
+            .execute(conn)
+    }
+}
+
+/// The user's notifications, only the unread ones if asked
+fn filtered(user_id: i32, unread: bool) -> notifications::BoxedQuery<'static, Pg> {
+    let mut query = notifications_table
+        .filter(notifications::user_id.eq(user_id))
+        .into_boxed();
+
+    if unread {
+        query = query.filter(notifications::read_at.is_null());
+    }
+
+    query
+}
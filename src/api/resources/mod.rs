@@ -4,3 +4,13 @@ pub mod wishlists;
 pub mod wishes;
 pub mod sponsors;
 pub mod followers;
+pub mod templates;
+pub mod collaborators;
+pub mod shares;
+pub mod search;
+pub mod categories;
+pub mod tags;
+pub mod images;
+pub mod audit;
+pub mod outbox;
+pub mod inbox;
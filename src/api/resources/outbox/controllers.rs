@@ -0,0 +1,65 @@
+use salvo::prelude::*;
+use crate::api::errors::{self as api_errors, ApiError, ApiResult};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::responses as api_responses;
+use crate::api::utils::get_db;
+use crate::api::utils::pagination::Pagination;
+use crate::api::validations::{QueryValidator, Validator};
+use super::models::OutboxStatus;
+
+/// Latest entries first, filtered by `status`
+#[handler]
+pub fn list_outbox(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.outbox_repo();
+
+    let status = QueryValidator(req).optional_string("status")?
+        .map(|status| status.parse::<OutboxStatus>())
+        .transpose()
+        .map_err(ApiError::BadRequestError)?;
+
+    match req.query::<i64>("per_page") {
+        None => {
+            api_responses::render_collection(res, repo.list(status)?);
+        },
+
+        Some(per_page) => {
+            let page = req.query::<i64>("page").unwrap_or(1);
+
+            let (entries, outbox) = repo.list_paginated(status, page, per_page)?;
+
+            api_responses::render_collection_paginated(res, outbox, Pagination::new(page, per_page, entries));
+        }
+    };
+
+    Ok(())
+}
+
+/// Entries per status
+#[handler]
+pub fn summarize_outbox(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.outbox_repo();
+
+    api_responses::render_resource(res, repo.summary()?);
+
+    Ok(())
+}
+
+/// Queues a failed entry again, its attempts start over
+#[handler]
+pub fn retry_outbox_entry(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.outbox_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let total_retried = repo.retry(id)?;
+
+    if total_retried == 0 {
+        api_errors::render_resource_not_found(res, "failed notification");
+    } else {
+        audit::record(req, depot, NewAuditLog::new("retry", "notification", Some(id)));
+
+        api_responses::render_db_execution(res, total_retried);
+    }
+
+    Ok(())
+}
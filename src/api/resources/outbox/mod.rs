@@ -0,0 +1,24 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use salvo::prelude::*;
+use crate::api::errors::ApiResult;
+use crate::api::utils::get_db;
+use crate::services::notifications::messages::Message;
+use self::controllers::{list_outbox, retry_outbox_entry, summarize_outbox};
+use self::models::NewOutboxEntry;
+
+pub fn get_admin_router() -> Router {
+    Router::with_path("outbox")
+        .get(list_outbox)
+        .push(Router::with_path("summary").get(summarize_outbox))
+        .push(Router::with_path("<id>/retry").put(retry_outbox_entry))
+}
+
+/// Queues the message for the outbox worker, which delivers it in the recipient's locale
+pub fn enqueue(depot: &Depot, recipient_id: i32, message: &Message) -> ApiResult<()> {
+    get_db(depot)?.outbox_repo().insert(NewOutboxEntry::new(recipient_id, message))?;
+
+    Ok(())
+}
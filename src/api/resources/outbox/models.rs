@@ -0,0 +1,166 @@
+use std::str::FromStr;
+use diesel::prelude::*;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Varchar;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use chrono::{Duration, NaiveDateTime};
+use crate::api::utils::formatters::{date, optional_date};
+use crate::schema::notification_outbox;
+use crate::services::notifications::messages::Message;
+
+/// Delays between attempts double from the first one up to the last
+const FIRST_RETRY_SECONDS: i64 = 30;
+const LAST_RETRY_SECONDS: i64 = 6 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(AsExpression, FromSqlRow)]
+#[diesel(sql_type = Varchar)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxStatus {
+    /// Waiting for its next attempt
+    Pending,
+    Sent,
+    /// Gave up after the last attempt, admins can retry it
+    Failed,
+}
+
+impl OutboxStatus {
+    pub const ALL: [OutboxStatus; 3] = [OutboxStatus::Pending, OutboxStatus::Sent, OutboxStatus::Failed];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutboxStatus::Pending => "PENDING",
+            OutboxStatus::Sent => "SENT",
+            OutboxStatus::Failed => "FAILED",
+        }
+    }
+}
+
+impl FromStr for OutboxStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_uppercase().as_str() {
+            "PENDING" => Ok(OutboxStatus::Pending),
+            "SENT" => Ok(OutboxStatus::Sent),
+            "FAILED" => Ok(OutboxStatus::Failed),
+            other => Err(format!("`{other}` is not a valid outbox status")),
+        }
+    }
+}
+
+impl ToSql<Varchar, Pg> for OutboxStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <str as ToSql<Varchar, Pg>>::to_sql(self.as_str(), out)
+    }
+}
+
+impl FromSql<Varchar, Pg> for OutboxStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let value = <String as FromSql<Varchar, Pg>>::from_sql(bytes)?;
+
+        OutboxStatus::from_str(&value).map_err(|error| error.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = notification_outbox)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OutboxEntry {
+    pub id: i32,
+    /// Recipient, rendered in their locale when delivered
+    pub user_id: i32,
+    pub kind: String,
+    /// The serialized `Message`
+    pub message: Value,
+    pub status: OutboxStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    #[serde(with = "date")]
+    pub next_attempt_at: NaiveDateTime,
+    #[serde(with = "optional_date")]
+    pub sent_at: Option<NaiveDateTime>,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
+}
+
+impl OutboxEntry {
+    pub fn message(&self) -> Result<Message, serde_json::Error> {
+        serde_json::from_value(self.message.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Insertable)]
+#[diesel(table_name = notification_outbox)]
+pub struct NewOutboxEntry {
+    pub user_id: i32,
+    pub kind: String,
+    pub message: Value,
+}
+
+impl NewOutboxEntry {
+    pub fn new(user_id: i32, message: &Message) -> Self {
+        Self {
+            user_id,
+            kind: message.kind().to_string(),
+            message: serde_json::to_value(message).unwrap_or_default(),
+        }
+    }
+}
+
+/// Entries per status
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct OutboxSummary {
+    pub pending: i64,
+    pub sent: i64,
+    pub failed: i64,
+}
+
+impl OutboxSummary {
+    pub fn add(self, status: OutboxStatus, total: i64) -> Self {
+        match status {
+            OutboxStatus::Pending => Self { pending: self.pending + total, ..self },
+            OutboxStatus::Sent => Self { sent: self.sent + total, ..self },
+            OutboxStatus::Failed => Self { failed: self.failed + total, ..self },
+        }
+    }
+}
+
+/// Wait before the attempt following the given failed ones
+pub fn backoff(attempts: i32) -> Duration {
+    let exponent = attempts.clamp(1, 20) as u32 - 1;
+
+    Duration::seconds((FIRST_RETRY_SECONDS << exponent).min(LAST_RETRY_SECONDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff() {
+        assert_eq!(backoff(1), Duration::seconds(30));
+        assert_eq!(backoff(2), Duration::seconds(60));
+        assert_eq!(backoff(5), Duration::seconds(480));
+        assert_eq!(backoff(15), Duration::hours(6), "delays are capped");
+        assert_eq!(backoff(i32::MAX), Duration::hours(6));
+    }
+
+    #[test]
+    fn queue_messages() {
+        let message = Message::AccountActivation { token: "abc".to_string() };
+
+        let entry = NewOutboxEntry::new(3, &message);
+
+        assert_eq!(entry.kind, "account_activation");
+        assert_eq!(serde_json::from_value::<Message>(entry.message).unwrap(), message);
+    }
+}
@@ -0,0 +1,143 @@
+use diesel::prelude::*;
+use diesel::dsl::{count_star, now, IntervalDsl};
+use diesel::pg::Pg;
+use diesel::result::Error;
+use chrono::Duration;
+use crate::api::utils::pagination::Paginate;
+use crate::schema::notification_outbox;
+use crate::schema::notification_outbox::table as outbox_table;
+use crate::services::database::{contracts::OutboxRepo, establish_connection};
+use super::models::{NewOutboxEntry, OutboxEntry, OutboxStatus, OutboxSummary};
+
+// Times come from the database clock, the one the column defaults use
+
+pub struct Repo;
+
+impl OutboxRepo for Repo {
+    fn insert(&self, new_entry: NewOutboxEntry) -> Result<OutboxEntry, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::insert_into(outbox_table)
+            .values(&new_entry)
+            .returning(OutboxEntry::as_returning())
+            .get_result(conn)
+    }
+
+    fn list(&self, status: Option<OutboxStatus>) -> Result<Vec<OutboxEntry>, Error> {
+        let conn = &mut establish_connection();
+
+        filtered(status)
+            .select(OutboxEntry::as_select())
+            .load(conn)
+    }
+
+    fn list_paginated(&self, status: Option<OutboxStatus>, page: i64, per_page: i64) -> Result<(i64, Vec<OutboxEntry>), Error> {
+        let conn = &mut establish_connection();
+
+        let results: Vec<(OutboxEntry, i64)> = filtered(status)
+            .select(OutboxEntry::as_select())
+            .paginate(page)
+            .per_page(per_page)
+            .get_results(conn)?;
+
+        match results.first() {
+            None => Ok((0, vec![])),
+            Some((_, entries)) => Ok((
+                *entries,
+                results.into_iter().map(|(entry, _)| entry).collect())
+            )
+        }
+    }
+
+    fn summary(&self) -> Result<OutboxSummary, Error> {
+        let conn = &mut establish_connection();
+
+        let totals: Vec<(OutboxStatus, i64)> = outbox_table
+            .group_by(notification_outbox::status)
+            .select((notification_outbox::status, count_star()))
+            .load(conn)?;
+
+        Ok(totals.into_iter().fold(OutboxSummary::default(), |summary, (status, total)| summary.add(status, total)))
+    }
+
+    fn claim_due(&self, limit: i64, lease: Duration) -> Result<Vec<OutboxEntry>, Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let ids: Vec<i32> = outbox_table
+                .filter(notification_outbox::status.eq(OutboxStatus::Pending))
+                .filter(notification_outbox::next_attempt_at.le(now))
+                .order(notification_outbox::next_attempt_at.asc())
+                .limit(limit)
+                .select(notification_outbox::id)
+                .for_update()
+                .skip_locked()
+                .load(conn)?;
+
+            diesel::update(outbox_table.filter(notification_outbox::id.eq_any(ids)))
+                .set(notification_outbox::next_attempt_at.eq(now + lease.num_seconds().seconds()))
+                .returning(OutboxEntry::as_returning())
+                .get_results(conn)
+        })
+    }
+
+    fn mark_sent(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(outbox_table.find(id))
+            .set((
+                notification_outbox::status.eq(OutboxStatus::Sent),
+                notification_outbox::attempts.eq(notification_outbox::attempts + 1),
+                notification_outbox::last_error.eq(None::<String>),
+                notification_outbox::sent_at.eq(now),
+            ))
+            .execute(conn)
+    }
+
+    fn reschedule(&self, id: i32, error: &str, delay: Duration) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(outbox_table.find(id))
+            .set((
+                notification_outbox::attempts.eq(notification_outbox::attempts + 1),
+                notification_outbox::last_error.eq(error),
+                notification_outbox::next_attempt_at.eq(now + delay.num_seconds().seconds()),
+            ))
+            .execute(conn)
+    }
+
+    fn mark_failed(&self, id: i32, error: &str) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(outbox_table.find(id))
+            .set((
+                notification_outbox::status.eq(OutboxStatus::Failed),
+                notification_outbox::attempts.eq(notification_outbox::attempts + 1),
+                notification_outbox::last_error.eq(error),
+            ))
+            .execute(conn)
+    }
+
+    fn retry(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(outbox_table.find(id).filter(notification_outbox::status.eq(OutboxStatus::Failed)))
+            .set((
+                notification_outbox::status.eq(OutboxStatus::Pending),
+                notification_outbox::attempts.eq(0),
+                notification_outbox::next_attempt_at.eq(now),
+            ))
+            .execute(conn)
+    }
+}
+
+/// Entries with the status, or every one, the latest first
+fn filtered(status: Option<OutboxStatus>) -> notification_outbox::BoxedQuery<'static, Pg> {
+    let mut query = outbox_table.into_boxed();
+
+    if let Some(status) = status {
+        query = query.filter(notification_outbox::status.eq(status));
+    }
+
+    query.order(notification_outbox::id.desc())
+}
@@ -1,20 +1,35 @@
+use std::str::FromStr;
+use std::sync::Arc;
 use salvo::prelude::*;
-use salvo::http::form::FormData;
+use serde_json::json;
 use crate::api::errors::{ApiResult, ApiError};
-use crate::api::utils::get_db;
+use crate::api::policy::{authorize, Action, Resource, Subject};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::resources::images::models::ImageResource;
+use crate::api::utils::{get_db, get_storage, read_file_field};
 use crate::api::utils::pagination::Pagination;
-use crate::api::validations::{Validator, FormValidator};
-use crate::api::responses as api_responses;
+use crate::api::utils::sorting::SortDirection;
+use crate::api::validations::{FieldValidator, Validator, BodyValidator, QueryValidator};
+use crate::api::{errors as api_errors, responses as api_responses};
 use crate::models::Mergeable;
-use super::models::NewProduct;
+use crate::services::database::contracts::DatabaseService;
+use super::{duplicates, import};
+use super::models::{DetailedProduct, NewProduct, Product, ProductFilter, ProductSortField, ReviewStatus};
+use super::models::{DESCRIPTION_CONSTRAINTS, NAME_CONSTRAINTS, PRICE_CONSTRAINTS, URL_CONSTRAINTS};
+
+/// Saved pages above this size are rejected
+const MAX_DOCUMENT_SIZE: u64 = 2 * 1024 * 1024;
 
 #[handler]
 pub fn list_products(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
-    let repo = get_db(depot)?.product_repo();
+    let db = get_db(depot)?;
+    let repo = db.product_repo();
+
+    let filter = cast_query_to_product_filter(req, db, &Subject::from_depot(depot))?;
 
     match req.query::<i64>("per_page") {
         None => {
-            let products = repo.list()?;
+            let products = repo.list(&filter)?;
 
             api_responses::render_collection(res, products);
         },
@@ -22,7 +37,7 @@ pub fn list_products(req: &mut Request, depot: &Depot, res: &mut Response) -> Ap
         Some(per_page) => {
             let page = req.query::<i64>("page").unwrap_or(1);
 
-            let (entries, products) = repo.list_paginated(page, per_page)?;
+            let (entries, products) = repo.list_paginated(&filter, page, per_page)?;
 
             api_responses::render_collection_paginated(res, products, Pagination::new(page, per_page, entries));
         }
@@ -33,82 +48,327 @@ pub fn list_products(req: &mut Request, depot: &Depot, res: &mut Response) -> Ap
 
 #[handler]
 pub async fn add_product(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
-    let repo = get_db(depot)?.product_repo();
+    let db = get_db(depot)?;
+    let repo = db.product_repo();
+
+    let body = BodyValidator::from_request(req).await?;
 
-    let form_data = req.form_data().await?;
+    let subject = Subject::from_depot(depot);
 
-    let new_product = cast_form_data_to_new_product(form_data)?;
+    authorize(&subject, Action::Create, &Resource::Catalog)?;
+
+    let new_product = cast_form_data_to_new_product(&body, subject.user_id, review_status(&subject))?;
+
+    check_category(db, new_product.category_id)?;
 
     let product = repo.insert(new_product)?;
 
+    audit::record(req, depot, NewAuditLog::new("create", "product", Some(product.id)).changes(None, Some(&product)));
+
     api_responses::render_resource_created(res, product);
     
     Ok(())
 }
 
+/// Pre-fills a product from a saved store page, nothing is stored until it's submitted
+#[handler]
+pub async fn import_product(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let subject = Subject::from_depot(depot);
+
+    authorize(&subject, Action::Create, &Resource::Catalog)?;
+
+    let content = read_file_field(req, "document", MAX_DOCUMENT_SIZE).await?.content;
+
+    let imported = import::parse_product(&String::from_utf8_lossy(&content))
+        .into_imported(subject.user_id, review_status(&subject))?;
+
+    api_responses::render_resource(res, imported);
+
+    Ok(())
+}
+
 #[handler]
 pub fn show_product(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
-    let repo = get_db(depot)?.product_repo();
+    let db = get_db(depot)?;
+    let storage = get_storage(depot)?;
     
     let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
     
-    let product = repo.find_one(id)?;
+    let product = db.product_repo().find_one(id)?;
+
+    authorize(&Subject::from_depot(depot), Action::View, &Resource::Product(&product))?;
+
+    let category = match product.category_id {
+        None => None,
+        Some(category_id) => Some(db.category_repo().find_one(category_id)?),
+    };
+
+    let tags = db.tag_repo().list_by_product(id)?;
+
+    let images = db.image_repo().list_by_product(id)?
+        .into_iter()
+        .map(|image| ImageResource::new(image, storage.as_ref()))
+        .collect();
     
-    api_responses::render_resource(res, product);
+    api_responses::render_resource(res, DetailedProduct { product, category, tags, images });
+
+    Ok(())
+}
+
+/// Price and availability changes of the product, oldest first
+#[handler]
+pub fn show_product_history(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+    let repo = db.product_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let product = repo.find_one(id)?;
+
+    authorize(&Subject::from_depot(depot), Action::View, &Resource::Product(&product))?;
+
+    api_responses::render_collection(res, repo.list_changes(id)?);
 
     Ok(())
 }
 
 #[handler]
 pub fn remove_product(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
-    let repo = get_db(depot)?.product_repo();
+    let db = get_db(depot)?;
+    let repo = db.product_repo();
 
     let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
 
+    let total_wishes = repo.count_wishes(id)?;
+
+    if total_wishes > 0 {
+        return Err(ApiError::Conflict(format!("Product is referenced by {total_wishes} wishes")));
+    }
+
+    let product = repo.find_one(id).ok();
+
+    // The images are kept along with the product until it's purged
     let total_deleted = repo.delete(id)?;
 
+    if total_deleted > 0 {
+        audit::record(req, depot, NewAuditLog::new("delete", "product", Some(id)).changes(product.as_ref(), None));
+    }
+
     api_responses::render_db_execution(res, total_deleted);
 
     Ok(())
 }
 
+/// Undoes a soft delete, only admins
 #[handler]
-pub async fn update_product(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+pub fn restore_product(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
     let repo = get_db(depot)?.product_repo();
 
     let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
 
-    let form_data = req.form_data().await?;
+    let total_restored = repo.restore(id)?;
+
+    if total_restored == 0 {
+        api_errors::render_resource_not_found(res, "deleted product");
+    } else {
+        audit::record(req, depot, NewAuditLog::new("restore", "product", Some(id)));
+
+        api_responses::render_db_execution(res, total_restored);
+    }
+
+    Ok(())
+}
+
+#[handler]
+pub async fn update_product(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+    let repo = db.product_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let body = BodyValidator::from_request(req).await?;
 
     let product = repo.find_one(id)?;
 
-    let product_updated = product.merge(form_data)?;
+    let product_updated = product.clone().merge(&body)?;
+
+    check_category(db, product_updated.category_id)?;
 
     let updated_product = repo.update(&product_updated)?;
 
+    audit::record(req, depot, NewAuditLog::new("update", "product", Some(id)).changes(Some(&product), Some(&updated_product)));
+
+    api_responses::render_resource_updated(res, updated_product);
+
+    Ok(())
+}
+
+#[handler]
+pub fn list_pending_products(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.product_repo();
+
+    let products = repo.list_by_review_status(ReviewStatus::Pending)?;
+
+    api_responses::render_collection(res, products);
+
+    Ok(())
+}
+
+#[handler]
+pub fn list_duplicate_products(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.product_repo();
+
+    api_responses::render_collection(res, duplicates::list_duplicates(repo.as_ref())?);
+
+    Ok(())
+}
+
+/// Merges the products in `duplicate_ids` into the one in the path
+#[handler]
+pub async fn merge_products(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.product_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let duplicate_ids = BodyValidator::from_request(req).await?.integer_list("duplicate_ids")?;
+
+    let total_merged = duplicates::merge_duplicates(repo.as_ref(), id, &duplicate_ids)?;
+
+    audit::record(req, depot, NewAuditLog::new("merge", "product", Some(id)).details(json!({ "duplicate_ids": duplicate_ids })));
+
+    api_responses::render_db_execution(res, total_merged);
+
+    Ok(())
+}
+
+#[handler]
+pub fn approve_product(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    review_product(req, depot, res, ReviewStatus::Approved)
+}
+
+#[handler]
+pub fn reject_product(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    review_product(req, depot, res, ReviewStatus::Rejected)
+}
+
+fn review_product(req: &Request, depot: &Depot, res: &mut Response, review_status: ReviewStatus) -> ApiResult<()> {
+    let repo = get_db(depot)?.product_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let product = repo.find_one(id)?;
+
+    let action = match review_status {
+        ReviewStatus::Approved => "approve",
+        _ => "reject",
+    };
+
+    let reviewed_product = Product {
+        available: review_status == ReviewStatus::Approved,
+        review_status,
+        ..product.clone()
+    };
+
+    let updated_product = repo.update(&reviewed_product)?;
+
+    audit::record(req, depot, NewAuditLog::new(action, "product", Some(id)).changes(Some(&product), Some(&updated_product)));
+
     api_responses::render_resource_updated(res, updated_product);
 
     Ok(())
 }
 
-fn cast_form_data_to_new_product(form_data: &FormData) -> Result<NewProduct, ApiError> {
-    let validator = FormValidator(form_data);
+fn cast_form_data_to_new_product(validator: &dyn Validator, submitted_by: Option<i32>, review_status: ReviewStatus) -> Result<NewProduct, ApiError> {
+    let mut validator = FieldValidator::new(validator);
+
+    let name = validator.string("name", NAME_CONSTRAINTS);
+    let description = validator.optional_string("description", DESCRIPTION_CONSTRAINTS);
+    let url = validator.optional_string("url", URL_CONSTRAINTS);
+    let price = validator.float("price", PRICE_CONSTRAINTS);
 
-    let name = validator.string("name")?;
-    let description = validator.optional_string("description")?;
-    let url = validator.optional_string("url")?;
-    let price = validator.float("price")?;
+    let category_id = validator.optional_integer("category_id", &[]);
 
-    let new_product = NewProduct { name, description, url, price, available: false };
+    validator.finish()?;
+
+    let new_product = NewProduct { name, description, url, price, available: false, review_status, submitted_by, category_id };
 
     Ok(new_product)
 }
 
+/// Admins add straight to the catalog, everyone else goes through review
+fn review_status(subject: &Subject) -> ReviewStatus {
+    match subject.admin {
+        true => ReviewStatus::Approved,
+        false => ReviewStatus::Pending,
+    }
+}
+
+/// Products can only be filed under existing categories
+fn check_category(db: &Arc<dyn DatabaseService>, category_id: Option<i32>) -> ApiResult<()> {
+    if let Some(category_id) = category_id {
+        db.category_repo()
+            .find_one(category_id)
+            .map_err(|_| ApiError::BadRequestError(format!("Category `{category_id}` not found")))?;
+    }
+
+    Ok(())
+}
+
+/// Finds the category by id or slug, an unknown category matches no product
+fn resolve_category_ids(db: &Arc<dyn DatabaseService>, category: &str) -> ApiResult<Vec<i32>> {
+    let repo = db.category_repo();
+
+    let found = match category.parse::<i32>() {
+        Ok(id) => repo.find_one(id),
+        Err(_) => repo.find_by_slug(category),
+    };
+
+    match found {
+        Ok(category) => Ok(repo.descendant_ids(category.id)?),
+        Err(diesel::result::Error::NotFound) => Ok(vec![]),
+        Err(error) => Err(ApiError::Diesel(error)),
+    }
+}
+
+/// Only admins get the soft deleted products, for anyone else `include_deleted` is ignored
+fn cast_query_to_product_filter(req: &Request, db: &Arc<dyn DatabaseService>, subject: &Subject) -> ApiResult<ProductFilter> {
+    let validator = QueryValidator(req);
+
+    let sort_by = match validator.optional_string("sort")? {
+        None => ProductSortField::default(),
+        Some(field) => ProductSortField::from_str(&field).map_err(ApiError::BadRequestError)?,
+    };
+
+    let direction = match validator.optional_string("order")? {
+        None => SortDirection::default(),
+        Some(direction) => SortDirection::from_str(&direction).map_err(ApiError::BadRequestError)?,
+    };
+
+    let filter = ProductFilter {
+        q: validator.optional_string("q")?.filter(|q| !q.trim().is_empty()),
+        min_price: validator.optional_float("min_price")?,
+        max_price: validator.optional_float("max_price")?,
+        available: validator.optional_boolean("available")?,
+        category_ids: match validator.optional_string("category")? {
+            None => None,
+            Some(category) => Some(resolve_category_ids(db, &category)?),
+        },
+        tag: validator.optional_string("tag")?.map(|tag| tag.trim().to_lowercase()),
+        updated_since: validator.optional_date("updated_since")?,
+        include_deleted: subject.admin && validator.optional_boolean("include_deleted")?.unwrap_or_default(),
+        sort_by,
+        direction,
+    };
+
+    Ok(filter)
+}
+
 #[cfg(test)]
 mod tests {
     use salvo::http::form::FormData;
     use super::cast_form_data_to_new_product;
-    use crate::api::resources::products::models::NewProduct;
+    use crate::api::resources::products::models::{NewProduct, ReviewStatus};
+    use crate::api::validations::FormValidator;
 
     fn create_form_data(fields: &[(&str, &str)]) -> FormData {
         let mut form_data = FormData::new();
@@ -122,9 +382,9 @@ mod tests {
 
     #[test]
     fn test_cast_form_data_to_new_product() {
-        let name = format!("product name");
-        let description = format!("description for product");
-        let url = format!("https://yahoo.com");
+        let name = "product name".to_string();
+        let description = "description for product".to_string();
+        let url = "https://yahoo.com".to_string();
         let price = 123000.05;
 
         let form_data = create_form_data(&[
@@ -137,10 +397,13 @@ mod tests {
             description: None,
             url: None,
             price,
-            available: false
+            available: false,
+            review_status: ReviewStatus::Pending,
+            submitted_by: Some(2),
+            category_id: None,
         };
 
-        let new_min_product = cast_form_data_to_new_product(&form_data)
+        let new_min_product = cast_form_data_to_new_product(&FormValidator(&form_data), Some(2), ReviewStatus::Pending)
             .expect("Error casting");
         
         assert_eq!(new_min_product, test_min_product, "minimal form data casted to new product");
@@ -158,10 +421,13 @@ mod tests {
             description: Some(description),
             url: Some(url),
             price,
-            available: false
+            available: false,
+            review_status: ReviewStatus::Pending,
+            submitted_by: Some(2),
+            category_id: None,
         };
 
-        let new_full_product = cast_form_data_to_new_product(&form_data_2)
+        let new_full_product = cast_form_data_to_new_product(&FormValidator(&form_data_2), Some(2), ReviewStatus::Pending)
             .expect("Error casting");
         
         assert_eq!(new_full_product, test_full_product, "full form data casted to new product");
@@ -170,10 +436,10 @@ mod tests {
     #[test]
     #[should_panic]
     fn test_cast_form_data_to_new_product_fail() {
-        let name = format!("A failing product");
+        let name = "A failing product".to_string();
 
         let form_data = create_form_data(&[ ("name", &name)]);
 
-        cast_form_data_to_new_product(&form_data).unwrap();
+        cast_form_data_to_new_product(&FormValidator(&form_data), None, ReviewStatus::Pending).unwrap();
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, HashSet};
+use serde::{Deserialize, Serialize};
+use diesel::result::Error;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::services::database::contracts::ProductRepo;
+use super::models::{Product, ReviewStatus};
+
+/// Share of the words of the shorter name found in the other one
+pub const NAME_SIMILARITY: f32 = 0.75;
+
+/// Ratio between the lower and the higher price
+pub const PRICE_SIMILARITY: f32 = 0.8;
+
+/// Products likely describing the same item, the first one is suggested to survive a merge
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub products: Vec<Product>,
+}
+
+/// Lowercased words without accents nor punctuation, e.g. `Consola Sony PlayStation® 5` -> `consola sony playstation 5`
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'á' | 'à' | 'ä' | 'â' | 'ã' => 'a',
+            'é' | 'è' | 'ë' | 'ê' => 'e',
+            'í' | 'ì' | 'ï' | 'î' => 'i',
+            'ó' | 'ò' | 'ö' | 'ô' | 'õ' => 'o',
+            'ú' | 'ù' | 'ü' | 'û' => 'u',
+            'ñ' => 'n',
+            'ç' => 'c',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Host and path of the address, ignoring the scheme, `www.`, the query and trailing slashes
+pub fn normalize_url(url: &str) -> Option<String> {
+    let url = url.trim().to_lowercase();
+
+    let without_scheme = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+    let without_query = without_scheme.split(['?', '#']).next().unwrap_or_default();
+    let normalized = without_query.trim_start_matches("www.").trim_end_matches('/');
+
+    match normalized.is_empty() {
+        true => None,
+        false => Some(normalized.to_string()),
+    }
+}
+
+fn words(name: &str) -> HashSet<String> {
+    normalize_name(name).split(' ')
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn name_similarity(a: &str, b: &str) -> f32 {
+    let (a_words, b_words) = (words(a), words(b));
+
+    let shorter = a_words.len().min(b_words.len());
+
+    match shorter {
+        0 => 0.0,
+        _ => a_words.intersection(&b_words).count() as f32 / shorter as f32,
+    }
+}
+
+/// Words with digits (capacities, model codes) of one name can't be missing from the other,
+/// `Nevera 263 Litros` and `Nevera 303 Litros` are different products
+pub fn same_model(a: &str, b: &str) -> bool {
+    let numbered = |name: &str| -> HashSet<String> {
+        words(name).into_iter()
+            .filter(|w| w.chars().any(|c| c.is_ascii_digit()))
+            .collect()
+    };
+
+    let (a_numbers, b_numbers) = (numbered(a), numbered(b));
+
+    a_numbers.is_subset(&b_numbers) || b_numbers.is_subset(&a_numbers)
+}
+
+pub fn price_similarity(a: f32, b: f32) -> f32 {
+    let (lower, higher) = match a < b {
+        true => (a, b),
+        false => (b, a),
+    };
+
+    match higher > 0.0 {
+        true => lower.max(0.0) / higher,
+        false => 1.0,
+    }
+}
+
+/// Same address, or similar names of the same model at similar prices
+pub fn are_duplicates(a: &Product, b: &Product) -> bool {
+    let same_url = match (a.url.as_deref().and_then(normalize_url), b.url.as_deref().and_then(normalize_url)) {
+        (Some(a_url), Some(b_url)) => a_url == b_url,
+        _ => false,
+    };
+
+    same_url || (
+        name_similarity(&a.name, &b.name) >= NAME_SIMILARITY
+            && same_model(&a.name, &b.name)
+            && price_similarity(a.price, b.price) >= PRICE_SIMILARITY
+    )
+}
+
+/// Groups the products transitively duplicated, sorted by id
+pub fn find_duplicates(products: &[Product]) -> Vec<DuplicateGroup> {
+    let mut products = products.to_vec();
+    products.sort_by_key(|p| p.id);
+
+    // Every product starts in its own group, labeled by its position
+    let mut labels: Vec<usize> = (0..products.len()).collect();
+
+    for i in 0..products.len() {
+        for j in (i + 1)..products.len() {
+            if labels[i] != labels[j] && are_duplicates(&products[i], &products[j]) {
+                let (kept, replaced) = (labels[i].min(labels[j]), labels[i].max(labels[j]));
+
+                labels.iter_mut()
+                    .filter(|label| **label == replaced)
+                    .for_each(|label| *label = kept);
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<Product>> = BTreeMap::new();
+
+    for (label, product) in labels.into_iter().zip(products) {
+        groups.entry(label).or_default().push(product);
+    }
+
+    groups.into_values()
+        .filter(|products| products.len() > 1)
+        .map(|products| DuplicateGroup { products })
+        .collect()
+}
+
+/// Duplicates among the approved and pending products, rejected ones are left out
+pub fn list_duplicates(repo: &dyn ProductRepo) -> Result<Vec<DuplicateGroup>, Error> {
+    let mut products = repo.list_by_review_status(ReviewStatus::Approved)?;
+    products.extend(repo.list_by_review_status(ReviewStatus::Pending)?);
+
+    Ok(find_duplicates(&products))
+}
+
+/// Moves the references of the duplicates to the survivor and deletes them, all or nothing
+pub fn merge_duplicates(repo: &dyn ProductRepo, survivor_id: i32, duplicate_ids: &[i32]) -> ApiResult<usize> {
+    if duplicate_ids.is_empty() {
+        return Err(ApiError::BadRequestError("`duplicate_ids` must list at least one product".to_string()));
+    }
+
+    if duplicate_ids.contains(&survivor_id) {
+        return Err(ApiError::BadRequestError("A product can't be merged into itself".to_string()));
+    }
+
+    if duplicate_ids.iter().collect::<HashSet<&i32>>().len() != duplicate_ids.len() {
+        return Err(ApiError::BadRequestError("`duplicate_ids` can't repeat products".to_string()));
+    }
+
+    for id in std::iter::once(&survivor_id).chain(duplicate_ids) {
+        repo.find_one(*id)?;
+    }
+
+    Ok(repo.merge(survivor_id, duplicate_ids)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use super::*;
+
+    fn product(id: i32, name: &str, url: Option<&str>, price: f32) -> Product {
+        Product {
+            id,
+            name: name.to_string(),
+            description: None,
+            url: url.map(|u| u.to_string()),
+            price,
+            available: true,
+            review_status: ReviewStatus::Approved,
+            submitted_by: None,
+            category_id: None,
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn normalization() {
+        assert_eq!(normalize_name("  Consola Sony PlayStation® 5 - Edición Digital "), "consola sony playstation 5 edicion digital");
+        assert_eq!(normalize_url("https://www.Exito.com/ps5/p?utm_source=x#reviews").as_deref(), Some("exito.com/ps5/p"));
+        assert_eq!(normalize_url("http://exito.com/ps5/p/").as_deref(), Some("exito.com/ps5/p"));
+        assert_eq!(normalize_url(" "), None);
+    }
+
+    #[test]
+    fn similarity() {
+        assert_eq!(name_similarity("Consola Sony Playstation 5", "Consola PlayStation 5 Sony 825GB"), 1.0);
+        assert_eq!(name_similarity("Kite", ""), 0.0);
+        assert!(same_model("Consola Sony Playstation 5", "Consola PlayStation 5 Sony 825GB"));
+        assert!(!same_model("Nevera WHIRLPOOL 263 Litros WRW25CKTWW", "Nevera WHIRLPOOL 303 Litros WRW32CKTWW"));
+        assert_eq!(price_similarity(80.0, 100.0), 0.8);
+        assert_eq!(price_similarity(0.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn groups() {
+        let products = vec![
+            product(5, "Consola PlayStation 5 Sony 825GB", None, 2599000.0),
+            product(1, "Consola Sony Playstation 5", Some("https://www.exito.com/ps5/p"), 2679999.0),
+            product(2, "PS5", Some("http://exito.com/ps5/p?ref=home"), 1.0),
+            product(3, "Consola Sony Playstation 5", None, 999.0),
+            product(4, "Kite", None, 1500.0),
+            product(6, "Kite surf board", None, 1400.0),
+        ];
+
+        let ids: Vec<Vec<i32>> = find_duplicates(&products).into_iter()
+            .map(|group| group.products.iter().map(|p| p.id).collect())
+            .collect();
+
+        assert_eq!(ids, vec![vec![1, 2, 5], vec![4, 6]], "the same url or a similar name and price, sorted by id");
+    }
+}
@@ -0,0 +1,376 @@
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::api::errors::{ApiError, ApiResult};
+use super::models::{NewProduct, ReviewStatus};
+
+/// Product details found in a store page, every source may leave some of them out
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProductDetails {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub price: Option<f32>,
+    pub currency: Option<String>,
+    pub image: Option<String>,
+}
+
+/// A product ready to be confirmed, along with the details it has no place for
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ImportedProduct {
+    #[serde(flatten)]
+    pub product: NewProduct,
+    pub currency: Option<String>,
+    pub image: Option<String>,
+}
+
+impl ProductDetails {
+    /// Pre-fills a product, the name and price are required
+    pub fn into_imported(self, submitted_by: Option<i32>, review_status: ReviewStatus) -> ApiResult<ImportedProduct> {
+        let ProductDetails { name, description, url, price, currency, image } = self;
+
+        let missing: Vec<&str> = [("name", name.is_none()), ("price", price.is_none())].iter()
+            .filter(|(_, missing)| *missing)
+            .map(|(field, _)| *field)
+            .collect();
+
+        let (Some(name), Some(price)) = (name, price) else {
+            return Err(ApiError::BadRequestError(format!("Couldn't find the product {} in the document", missing.join(" and "))));
+        };
+
+        let product = NewProduct {
+            name,
+            description,
+            url,
+            price,
+            available: false,
+            review_status,
+            submitted_by,
+            category_id: None,
+        };
+
+        Ok(ImportedProduct { product, currency, image })
+    }
+
+    /// Fills the missing details with the ones of `other`
+    fn or(self, other: Self) -> Self {
+        Self {
+            name: self.name.or(other.name),
+            description: self.description.or(other.description),
+            url: self.url.or(other.url),
+            price: self.price.or(other.price),
+            currency: self.currency.or(other.currency),
+            image: self.image.or(other.image),
+        }
+    }
+}
+
+/// Extracts the product from the document, preferring schema.org JSON-LD,
+/// then microdata and finally OpenGraph tags
+pub fn parse_product(html: &str) -> ProductDetails {
+    let document = Html::parse_document(html);
+
+    from_json_ld(&document)
+        .or(from_microdata(&document))
+        .or(from_open_graph(&document))
+}
+
+fn selector(selectors: &str) -> Selector {
+    Selector::parse(selectors).expect("Invalid selector")
+}
+
+fn clean(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    match text.is_empty() {
+        true => None,
+        false => Some(text),
+    }
+}
+
+/// Reads prices as written in stores: `$ 2.679.999`, `1,299.99` or `19,90 €`
+pub fn parse_price(text: &str) -> Option<f32> {
+    let number: String = text.chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+
+    let decimal_separator = match (number.rfind('.'), number.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(if dot > comma { '.' } else { ',' }),
+        (Some(position), None) | (None, Some(position)) => {
+            let separator = number.as_bytes()[position] as char;
+            let repeated = number.matches(separator).count() > 1;
+            // A leading zero can't be followed by thousands, e.g. `0.999`
+            let thousands = number.len() - position - 1 == 3 && &number[..position] != "0";
+
+            match repeated || thousands {
+                true => None,
+                false => Some(separator),
+            }
+        },
+        (None, None) => None,
+    };
+
+    let normalized: String = number.chars()
+        .filter_map(|c| match c {
+            '.' | ',' if Some(c) == decimal_separator => Some('.'),
+            '.' | ',' => None,
+            digit => Some(digit),
+        })
+        .collect();
+
+    normalized.parse().ok()
+}
+
+/// Reads machine readable prices, which schema.org writes with a `.` as the decimal separator
+pub fn parse_decimal(text: &str) -> Option<f32> {
+    text.trim().parse::<f32>().ok().filter(|price| price.is_finite())
+}
+
+fn from_json_ld(document: &Html) -> ProductDetails {
+    document.select(&selector(r#"script[type="application/ld+json"]"#))
+        .filter_map(|script| serde_json::from_str::<Value>(&script.inner_html()).ok())
+        .find_map(|value| find_json_ld_product(&value).map(json_ld_details))
+        .unwrap_or_default()
+}
+
+fn is_product(value: &Value) -> bool {
+    match &value["@type"] {
+        Value::String(kind) => kind == "Product",
+        Value::Array(kinds) => kinds.iter().any(|kind| kind == "Product"),
+        _ => false,
+    }
+}
+
+/// The product may be nested in arrays or in a `@graph`
+fn find_json_ld_product(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(find_json_ld_product),
+        Value::Object(_) if is_product(value) => Some(value),
+        Value::Object(object) => object.get("@graph").and_then(find_json_ld_product),
+        _ => None,
+    }
+}
+
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => clean(text),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Array(items) => items.iter().find_map(json_text),
+        Value::Object(object) => object.get("url").and_then(json_text),
+        _ => None,
+    }
+}
+
+fn json_ld_details(product: &Value) -> ProductDetails {
+    let offer = match &product["offers"] {
+        Value::Array(offers) => offers.first().unwrap_or(&Value::Null),
+        offer => offer,
+    };
+
+    let price = ["price", "lowPrice"].iter()
+        .find_map(|key| json_text(&offer[key]))
+        .or(json_text(&offer["priceSpecification"]["price"]));
+
+    ProductDetails {
+        name: json_text(&product["name"]),
+        description: json_text(&product["description"]),
+        url: json_text(&product["url"]),
+        price: price.as_deref().and_then(parse_decimal),
+        currency: json_text(&offer["priceCurrency"]).or(json_text(&offer["priceSpecification"]["priceCurrency"])),
+        image: json_text(&product["image"]),
+    }
+}
+
+fn microdata_value(element: ElementRef) -> Option<String> {
+    let attributes = element.value();
+
+    ["content", "src", "href"].iter()
+        .find_map(|attribute| attributes.attr(attribute))
+        .and_then(clean)
+        .or_else(|| clean(&element.text().collect::<String>()))
+}
+
+/// The `content` attribute holds a plain decimal, the visible text is written as in the store
+fn microdata_price(element: ElementRef) -> Option<f32> {
+    match element.value().attr("content").and_then(clean) {
+        Some(content) => parse_decimal(&content),
+        None => parse_price(&element.text().collect::<String>()),
+    }
+}
+
+fn from_microdata(document: &Html) -> ProductDetails {
+    let Some(product) = document.select(&selector(r#"[itemtype$="schema.org/Product"]"#)).next() else {
+        return ProductDetails::default();
+    };
+
+    let element = |name: &str| product.select(&selector(&format!(r#"[itemprop="{name}"]"#))).next();
+
+    let property = |name: &str| element(name).and_then(microdata_value);
+
+    ProductDetails {
+        name: property("name"),
+        description: property("description"),
+        url: property("url"),
+        price: element("price").and_then(microdata_price),
+        currency: property("priceCurrency"),
+        image: property("image"),
+    }
+}
+
+fn from_open_graph(document: &Html) -> ProductDetails {
+    let meta = |properties: &[&str]| {
+        properties.iter().find_map(|property| {
+            document.select(&selector(&format!(r#"meta[property="{property}"], meta[name="{property}"]"#)))
+                .find_map(|element| element.value().attr("content").and_then(clean))
+        })
+    };
+
+    ProductDetails {
+        name: meta(&["og:title"]),
+        description: meta(&["og:description"]),
+        url: meta(&["og:url"]),
+        price: meta(&["product:price:amount", "og:price:amount"]).as_deref().and_then(parse_price),
+        currency: meta(&["product:price:currency", "og:price:currency"]),
+        image: meta(&["og:image"]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices() {
+        assert_eq!(parse_price("2679999"), Some(2679999.0));
+        assert_eq!(parse_price("$ 2.679.999"), Some(2679999.0));
+        assert_eq!(parse_price("1,299.99"), Some(1299.99));
+        assert_eq!(parse_price("19,90 €"), Some(19.9));
+        assert_eq!(parse_price("USD 34000.6"), Some(34000.6));
+        assert_eq!(parse_price("1.250"), Some(1250.0), "three digits after a lone separator are thousands");
+        assert_eq!(parse_price("0.999"), Some(0.999), "unless the integer part is zero");
+        assert_eq!(parse_price("0,250 €"), Some(0.25));
+        assert_eq!(parse_price("free"), None);
+    }
+
+    #[test]
+    fn decimal_prices() {
+        assert_eq!(parse_decimal("1250.123"), Some(1250.123));
+        assert_eq!(parse_decimal(" 1.250 "), Some(1.25), "the dot is always the decimal separator");
+        assert_eq!(parse_decimal("2679999"), Some(2679999.0));
+        assert_eq!(parse_decimal("1,250"), None);
+        assert_eq!(parse_decimal("NaN"), None);
+    }
+
+    #[test]
+    fn json_ld() {
+        let html = r#"
+            <html><head>
+            <script type="application/ld+json">{"@type": "BreadcrumbList", "itemListElement": []}</script>
+            <script type="application/ld+json">
+            {
+                "@context": "https://schema.org",
+                "@graph": [{
+                    "@type": "Product",
+                    "name": "Consola Sony Playstation 5",
+                    "description": "  Nueva consola\n PlayStation 5 ",
+                    "image": ["https://exito.com/ps5.jpg", "https://exito.com/ps5-back.jpg"],
+                    "url": "https://www.exito.com/ps5/p",
+                    "offers": [{"@type": "Offer", "price": "2679999", "priceCurrency": "COP"}]
+                }]
+            }
+            </script>
+            <meta property="og:title" content="PS5 | Éxito">
+            </head></html>
+        "#;
+
+        assert_eq!(parse_product(html), ProductDetails {
+            name: Some("Consola Sony Playstation 5".to_string()),
+            description: Some("Nueva consola PlayStation 5".to_string()),
+            url: Some("https://www.exito.com/ps5/p".to_string()),
+            price: Some(2679999.0),
+            currency: Some("COP".to_string()),
+            image: Some("https://exito.com/ps5.jpg".to_string()),
+        });
+    }
+
+    #[test]
+    fn microdata() {
+        let html = r#"
+            <div itemscope itemtype="https://schema.org/Product">
+                <h1 itemprop="name">Patineta eléctrica</h1>
+                <img itemprop="image" src="/img/patineta.png">
+                <p itemprop="description">Hasta 25 km de autonomía</p>
+                <div itemprop="offers" itemscope itemtype="https://schema.org/Offer">
+                    <span itemprop="price" content="1299.90">$ 1.299,90</span>
+                    <meta itemprop="priceCurrency" content="USD">
+                </div>
+            </div>
+        "#;
+
+        let details = parse_product(html);
+
+        assert_eq!(details.name.as_deref(), Some("Patineta eléctrica"));
+        assert_eq!(details.image.as_deref(), Some("/img/patineta.png"));
+        assert_eq!(details.description.as_deref(), Some("Hasta 25 km de autonomía"));
+        assert_eq!(details.price, Some(1299.9), "the content attribute is preferred over the text");
+        assert_eq!(details.currency.as_deref(), Some("USD"));
+    }
+
+    #[test]
+    fn structured_prices_are_decimals() {
+        let json_ld = |price: &str| parse_product(&format!(
+            r#"<script type="application/ld+json">{{"@type": "Product", "offers": {{"price": {price}}}}}</script>"#
+        )).price;
+
+        assert_eq!(json_ld("1250.123"), Some(1250.123));
+        assert_eq!(json_ld(r#""1.250""#), Some(1.25));
+
+        let microdata = |price: &str| parse_product(&format!(
+            r#"<div itemscope itemtype="https://schema.org/Product">{price}</div>"#
+        )).price;
+
+        assert_eq!(microdata(r#"<span itemprop="price" content="1.250">$ 1,25</span>"#), Some(1.25));
+        assert_eq!(microdata(r#"<span itemprop="price">$ 1.250</span>"#), Some(1250.0), "the visible text is read as in stores");
+    }
+
+    #[test]
+    fn open_graph_fills_the_gaps() {
+        let html = r#"
+            <html><head>
+            <meta property="og:title" content="Bicicleta de ruta">
+            <meta property="og:image" content="https://store.test/bici.jpg">
+            <meta property="og:url" content="https://store.test/bici">
+            <meta property="product:price:amount" content="3.450.000">
+            <meta property="product:price:currency" content="COP">
+            <script type="application/ld+json">{"@type": "Product", "name": "Bicicleta Ruta 700C"}</script>
+            </head></html>
+        "#;
+
+        let details = parse_product(html);
+
+        assert_eq!(details.name.as_deref(), Some("Bicicleta Ruta 700C"), "JSON-LD comes first");
+        assert_eq!(details.price, Some(3450000.0));
+        assert_eq!(details.currency.as_deref(), Some("COP"));
+        assert_eq!(details.image.as_deref(), Some("https://store.test/bici.jpg"));
+        assert_eq!(details.url.as_deref(), Some("https://store.test/bici"));
+        assert_eq!(parse_product("<p>No product here</p>"), ProductDetails::default());
+    }
+
+    #[test]
+    fn imported_product() {
+        let details = ProductDetails { name: Some("Kite".to_string()), ..Default::default() };
+
+        let result = details.clone().into_imported(Some(2), ReviewStatus::Pending);
+
+        assert!(matches!(result, Err(ApiError::BadRequestError(message)) if message == "Couldn't find the product price in the document"));
+
+        let imported = ProductDetails { price: Some(1500.0), ..details }
+            .into_imported(Some(2), ReviewStatus::Pending)
+            .unwrap();
+
+        assert_eq!(imported.product.name, "Kite");
+        assert_eq!(imported.product.submitted_by, Some(2));
+        assert!(!imported.product.available, "imported products wait for confirmation");
+    }
+}
@@ -1,20 +1,65 @@
 pub mod models;
 mod controllers;
 pub mod repo;
+pub mod import;
+pub mod duplicates;
 
 use salvo::prelude::*;
-// use crate::api::auth;
-use self::controllers::{list_products, add_product, show_product, remove_product, update_product};
+use crate::api::auth;
+use crate::api::policy::require_product_writes;
+use auth::controllers::handle_auth;
+use self::controllers::{
+    add_product,
+    approve_product,
+    import_product,
+    list_duplicate_products,
+    list_pending_products,
+    list_products,
+    merge_products,
+    reject_product,
+    restore_product,
+    remove_product,
+    show_product,
+    show_product_history,
+    update_product,
+};
 
 
 pub fn get_router() -> Router {
     Router::with_path("products")
-        // .hoop(auth::handle_auth)
         .get(list_products)
-        .post(add_product)
         .push(Router::with_path("<id>")
             .get(show_product)
-            .delete(remove_product)
-            .put(update_product)
+            .push(Router::with_path("history")
+                .get(show_product_history)
+            )
         )
-}
\ No newline at end of file
+        .push(Router::new()
+            .hoop(handle_auth)
+            .post(add_product)
+            .push(Router::with_path("import")
+                .post(import_product)
+            )
+            .push(Router::with_path("<id>")
+                .hoop(require_product_writes)
+                .delete(remove_product)
+                .put(update_product)
+            )
+        )
+}
+
+pub fn get_admin_router() -> Router {
+    Router::with_path("products")
+        .push(Router::with_path("pending")
+            .get(list_pending_products)
+        )
+        .push(Router::with_path("duplicates")
+            .get(list_duplicate_products)
+        )
+        .push(Router::with_path("<id>")
+            .push(Router::with_path("merge").post(merge_products))
+            .push(Router::with_path("approve").put(approve_product))
+            .push(Router::with_path("reject").put(reject_product))
+            .push(Router::with_path("restore").put(restore_product))
+        )
+}
@@ -1,14 +1,76 @@
+use std::str::FromStr;
 use diesel::prelude::*;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Varchar;
 use serde::{Serialize, Deserialize};
-use salvo::http::form::FormData;
-use crate::api::errors::{ApiError, ApiResult};
-use crate::api::validations::{FormValidator, Validator};
+use chrono::NaiveDateTime;
+use crate::api::errors::ApiResult;
+use crate::api::utils::sorting::SortDirection;
+use crate::api::utils::formatters::{date, optional_date};
+use crate::api::resources::images::models::ImageResource;
+use crate::api::resources::categories::models::{Category, ListedCategory};
+use crate::api::resources::tags::models::Tag;
+use crate::api::validations::{Constraint, FieldValidator, Validator};
 use crate::schema::products;
-use crate::models::Mergeable;
+use crate::models::{Composable, Mergeable};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd)]
+#[derive(AsExpression, FromSqlRow)]
+#[diesel(sql_type = Varchar)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    /// Submitted by a user, waiting for an admin
+    Pending,
+    /// Part of the catalog
+    Approved,
+    /// Turned down by an admin, kept for the submitter
+    Rejected,
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::Pending => "PENDING",
+            ReviewStatus::Approved => "APPROVED",
+            ReviewStatus::Rejected => "REJECTED",
+        }
+    }
+}
+
+impl FromStr for ReviewStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_uppercase().as_str() {
+            "PENDING" => Ok(ReviewStatus::Pending),
+            "APPROVED" => Ok(ReviewStatus::Approved),
+            "REJECTED" => Ok(ReviewStatus::Rejected),
+            other => Err(format!("`{other}` is not a valid review status")),
+        }
+    }
+}
+
+impl ToSql<Varchar, Pg> for ReviewStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <str as ToSql<Varchar, Pg>>::to_sql(self.as_str(), out)
+    }
+}
+
+impl FromSql<Varchar, Pg> for ReviewStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let value = <String as FromSql<Varchar, Pg>>::from_sql(bytes)?;
+
+        ReviewStatus::from_str(&value).map_err(|error| error.into())
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd)]
 #[derive(Queryable, Selectable, AsChangeset)]
 #[diesel(table_name = crate::schema::products)]
+#[diesel(treat_none_as_null = true)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Product {
     pub id: i32,
@@ -17,9 +79,16 @@ pub struct Product {
     pub url: Option<String>,
     pub price: f32,
     pub available: bool,
+    pub review_status: ReviewStatus,
+    pub submitted_by: Option<i32>,
+    pub category_id: Option<i32>,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
 }
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd)]
 #[derive(Insertable)]
 #[diesel(table_name = products)]
 pub struct NewProduct {
@@ -28,37 +97,52 @@ pub struct NewProduct {
     pub url: Option<String>,
     pub available: bool,
     pub price: f32,
+    pub review_status: ReviewStatus,
+    pub submitted_by: Option<i32>,
+    pub category_id: Option<i32>,
 }
 
+/// Checks shared by new products and partial updates
+pub const NAME_CONSTRAINTS: &[Constraint] = &[Constraint::MinLength(1), Constraint::MaxLength(200)];
+pub const DESCRIPTION_CONSTRAINTS: &[Constraint] = &[Constraint::MaxLength(2000)];
+pub const URL_CONSTRAINTS: &[Constraint] = &[Constraint::Url, Constraint::MaxLength(2048)];
+pub const PRICE_CONSTRAINTS: &[Constraint] = &[Constraint::Min(0.0)];
+
 impl Mergeable for Product {
-    fn merge(self, form_data: &FormData) -> ApiResult<Self> {
-        let validator = FormValidator(form_data);
+    fn merge(self, validator: &dyn Validator) -> ApiResult<Self> {
+        let mut validator = FieldValidator::new(validator);
         let mut updatable = self.clone();
 
-        if let Some(name) = validator.get("name") {
-            if name.is_empty() {
-                return Err(ApiError::BadRequestError(format!("`name` cannot be empty")));
-            }
+        if validator.contains("name") {
+            updatable.name = validator.string("name", NAME_CONSTRAINTS);
+        }
 
-            updatable.name = name.to_string();
+        if validator.contains("description") {
+            updatable.description = validator.optional_string("description", DESCRIPTION_CONSTRAINTS);
         }
 
-        if let Some(_) = validator.get("description") {
-            updatable.description = validator.optional_string("description")?;
+        if validator.contains("url") {
+            updatable.url = validator.optional_string("url", URL_CONSTRAINTS);
         }
 
-        if let Some(_) = validator.get("url") {
-            updatable.url = validator.optional_string("url")?;
+        if validator.contains("available") {
+            updatable.available = validator.boolean("available");
         }
 
-        if let Some(_) = validator.get("available") {
-            updatable.available = validator.boolean("available")?;
+        if validator.contains("price") {
+            updatable.price = validator.float("price", PRICE_CONSTRAINTS);
         }
 
-        if let Some(_) = validator.get("price") {
-            updatable.price = validator.float("price")?;
+        // An empty value clears the category
+        if validator.contains("category_id") {
+            updatable.category_id = match validator.optional_string("category_id", &[]).unwrap_or_default().is_empty() {
+                true => None,
+                false => validator.optional_integer("category_id", &[]),
+            };
         }
 
+        validator.finish()?;
+
         Ok(updatable)
     }
 }
@@ -71,12 +155,127 @@ pub struct ListedProduct {
     pub name: String,
     pub price: f32,
     pub available: bool,
+    pub category_id: Option<i32>,
+    #[diesel(embed)]
+    pub category: Option<ListedCategory>,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
+    /// Only set on the tombstones of synced lists
+    #[serde(default, with = "optional_date", skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
+/// The category has to be composed separately
 impl From<Product> for ListedProduct {
     fn from(value: Product) -> Self {
-        let Product { id, name, price, available, .. } = value;
+        let Product { id, name, price, available, category_id, created_at, updated_at, .. } = value;
+
+        Self { id, name, price, available, category_id, category: None, created_at, updated_at, deleted_at: None }
+    }
+}
+
+impl Composable<Product, Option<ListedCategory>> for ListedProduct {
+    fn compose(product: Product, category: Option<ListedCategory>) -> Self {
+        Self { category, ..ListedProduct::from(product) }
+    }
+}
+
+/// A change of price or availability, kept for the product history
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::product_changes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductChange {
+    pub id: i32,
+    pub product_id: i32,
+    pub previous_price: f32,
+    pub price: f32,
+    pub previous_available: bool,
+    pub available: bool,
+    #[serde(with = "date")]
+    pub changed_at: NaiveDateTime,
+}
 
-        Self { id, name, price, available }
+#[derive(Debug)]
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::product_changes)]
+pub struct NewProductChange {
+    pub product_id: i32,
+    pub previous_price: f32,
+    pub price: f32,
+    pub previous_available: bool,
+    pub available: bool,
+}
+
+impl NewProductChange {
+    /// `None` when neither the price nor the availability changed
+    pub fn between(previous: &Product, current: &Product) -> Option<Self> {
+        if previous.price == current.price && previous.available == current.available {
+            return None;
+        }
+
+        Some(Self {
+            product_id: current.id,
+            previous_price: previous.price,
+            price: current.price,
+            previous_available: previous.available,
+            available: current.available,
+        })
     }
-}
\ No newline at end of file
+}
+
+/// A product along with its category, tags and images
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DetailedProduct {
+    #[serde(flatten)]
+    pub product: Product,
+    pub category: Option<Category>,
+    pub tags: Vec<Tag>,
+    pub images: Vec<ImageResource>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ProductSortField {
+    #[default]
+    Id,
+    Name,
+    Price,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl FromStr for ProductSortField {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "id" => Ok(ProductSortField::Id),
+            "name" => Ok(ProductSortField::Name),
+            "price" => Ok(ProductSortField::Price),
+            "created_at" => Ok(ProductSortField::CreatedAt),
+            "updated_at" => Ok(ProductSortField::UpdatedAt),
+            other => Err(format!("`{other}` is not a sortable field, use `id`, `name`, `price`, `created_at` or `updated_at`")),
+        }
+    }
+}
+
+/// Criteria for listing the catalog, every `None` leaves the products unfiltered
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProductFilter {
+    /// Text contained in the name or the description, case insensitive
+    pub q: Option<String>,
+    pub min_price: Option<f32>,
+    pub max_price: Option<f32>,
+    pub available: Option<bool>,
+    /// A category and all its subcategories
+    pub category_ids: Option<Vec<i32>>,
+    pub tag: Option<String>,
+    /// Products created or modified from then on, for incremental syncs
+    pub updated_since: Option<NaiveDateTime>,
+    /// Adds the soft deleted products, with their `deleted_at`, so deletions can be synced too
+    pub include_deleted: bool,
+    pub sort_by: ProductSortField,
+    pub direction: SortDirection,
+}
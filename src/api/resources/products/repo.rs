@@ -1,9 +1,15 @@
 use diesel::prelude::*;
+use diesel::pg::Pg;
+use diesel::dsl::{exists, not, now, IntervalDsl, IntoBoxed, LeftJoin};
+use chrono::NaiveDateTime;
 use crate::db;
 use crate::services::database::{contracts::ProductRepo,  establish_connection};
 use crate::api::utils::pagination::Paginate;
 use crate::schema::products::table as products_table;
-use super::models::{Product, NewProduct, ListedProduct};
+use crate::schema::{categories, images, product_changes, product_tags, products, tags, template_products, wishes};
+use crate::api::utils::sorting::SortDirection;
+use crate::api::resources::images::models::Image;
+use super::models::{Product, NewProduct, NewProductChange, ProductChange, ListedProduct, ProductFilter, ProductSortField, ReviewStatus};
 use diesel::result::Error;
 
 pub struct Repo;
@@ -14,22 +20,24 @@ impl ProductRepo for Repo {
 
         products_table
             .find(id)
+            .filter(products::deleted_at.is_null())
             .select(Product::as_select())
             .first(conn)
     }
 
-    fn list(&self) -> Result<Vec<ListedProduct>, Error> {
+    fn list(&self, filter: &ProductFilter) -> Result<Vec<ListedProduct>, Error> {
         let conn = &mut establish_connection();
 
-        products_table
+        catalog(filter)
             .select(ListedProduct::as_select())
             .load(conn)
     }
 
-    fn list_paginated(&self, page: i64, per_page: i64) -> Result<(i64, Vec<ListedProduct>), Error> {
+    fn list_paginated(&self, filter: &ProductFilter, page: i64, per_page: i64) -> Result<(i64, Vec<ListedProduct>), Error> {
         let conn = &mut establish_connection();
 
-        let results: Vec<(Product, i64)> = products_table.into_boxed()
+        let results: Vec<(ListedProduct, i64)> = catalog(filter)
+            .select(ListedProduct::as_select())
             .paginate(page)
             .per_page(per_page)
             .get_results(conn)?;
@@ -38,7 +46,7 @@ impl ProductRepo for Repo {
             None => Ok((0, vec![])),
             Some((_, entries)) => Ok((
                 *entries,
-                results.into_iter().map(|(p, _)| ListedProduct::from(p)).collect())
+                results.into_iter().map(|(p, _)| p).collect())
             )
         }
     }
@@ -55,16 +63,77 @@ impl ProductRepo for Repo {
     fn delete(&self, id: i32) -> Result<usize, Error> {
         let conn = &mut establish_connection();
 
-        diesel::delete(products_table.find(id))
+        diesel::update(products_table.find(id).filter(products::deleted_at.is_null()))
+            .set(products::deleted_at.eq(now.nullable()))
+            .execute(conn)
+    }
+
+    fn restore(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(products_table.find(id).filter(products::deleted_at.is_not_null()))
+            .set(products::deleted_at.eq(None::<NaiveDateTime>))
             .execute(conn)
     }
 
+    fn purge(&self, retention_days: i32) -> Result<(usize, Vec<Image>), Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            // Products still referenced by a wish or a template are kept until those go away
+            let ids: Vec<i32> = products_table
+                .filter(products::deleted_at.lt((now - retention_days.days()).nullable()))
+                .filter(not(exists(wishes::table.filter(wishes::product_id.eq(products::id)))))
+                .filter(not(exists(template_products::table.filter(template_products::product_id.eq(products::id)))))
+                .select(products::id)
+                .load(conn)?;
+
+            let purged_images = images::table
+                .filter(images::product_id.eq_any(&ids))
+                .select(Image::as_select())
+                .load(conn)?;
+
+            let total = diesel::delete(products_table.filter(products::id.eq_any(&ids)))
+                .execute(conn)?;
+
+            Ok((total, purged_images))
+        })
+    }
+
     fn update(&self, product: &Product) -> Result<Product, Error> {
         let conn = &mut establish_connection();
 
-        diesel::update(products_table.find(product.id))
-            .set(product)
-            .get_result(conn)
+        conn.transaction(|conn| {
+            let previous = products_table
+                .find(product.id)
+                .filter(products::deleted_at.is_null())
+                .select(Product::as_select())
+                .for_update()
+                .first(conn)?;
+
+            let updated = diesel::update(products_table.find(product.id))
+                .set(product)
+                .returning(Product::as_returning())
+                .get_result(conn)?;
+
+            if let Some(change) = NewProductChange::between(&previous, &updated) {
+                diesel::insert_into(product_changes::table)
+                    .values(change)
+                    .execute(conn)?;
+            }
+
+            Ok(updated)
+        })
+    }
+
+    fn list_changes(&self, id: i32) -> Result<Vec<ProductChange>, Error> {
+        let conn = &mut establish_connection();
+
+        product_changes::table
+            .filter(product_changes::product_id.eq(id))
+            .order((product_changes::changed_at.asc(), product_changes::id.asc()))
+            .select(ProductChange::as_select())
+            .load(conn)
     }
 
     fn insert_many(&self, products: Vec<NewProduct>) -> Result<usize, Error> {
@@ -74,4 +143,143 @@ impl ProductRepo for Repo {
             .values(products)
             .execute(conn)
     }
+
+    fn list_by_review_status(&self, review_status: ReviewStatus) -> Result<Vec<Product>, Error> {
+        let conn = &mut establish_connection();
+
+        products_table
+            .filter(products::review_status.eq(review_status))
+            .filter(products::deleted_at.is_null())
+            .order(products::id)
+            .select(Product::as_select())
+            .load(conn)
+    }
+
+    fn merge(&self, survivor_id: i32, duplicate_ids: &[i32]) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            products_table.find(survivor_id)
+                .filter(products::deleted_at.is_null())
+                .select(products::id)
+                .first::<i32>(conn)?;
+
+            diesel::update(wishes::table.filter(wishes::product_id.eq_any(duplicate_ids)))
+                .set(wishes::product_id.eq(survivor_id))
+                .execute(conn)?;
+
+            diesel::update(template_products::table.filter(template_products::product_id.eq_any(duplicate_ids)))
+                .set(template_products::product_id.eq(survivor_id))
+                .execute(conn)?;
+
+            diesel::update(images::table.filter(images::product_id.eq_any(duplicate_ids)))
+                .set(images::product_id.eq(survivor_id))
+                .execute(conn)?;
+
+            let tag_ids: Vec<i32> = product_tags::table
+                .filter(product_tags::product_id.eq_any(duplicate_ids))
+                .select(product_tags::tag_id)
+                .distinct()
+                .load(conn)?;
+
+            let survivor_tags: Vec<_> = tag_ids.into_iter()
+                .map(|tag_id| (product_tags::product_id.eq(survivor_id), product_tags::tag_id.eq(tag_id)))
+                .collect();
+
+            diesel::insert_into(product_tags::table)
+                .values(survivor_tags)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+
+            // Soft deleted like any other product, so their history is kept and they can be restored
+            let total = diesel::update(products_table.filter(products::id.eq_any(duplicate_ids)).filter(products::deleted_at.is_null()))
+                .set(products::deleted_at.eq(now.nullable()))
+                .execute(conn)?;
+
+            // Every duplicate must exist, otherwise nothing is persisted
+            if total != duplicate_ids.len() {
+                return Err(Error::RollbackTransaction);
+            }
+
+            Ok(total)
+        })
+    }
+
+    fn count_wishes(&self, id: i32) -> Result<i64, Error> {
+        let conn = &mut establish_connection();
+
+        wishes::table
+            .filter(wishes::product_id.eq(id))
+            .count()
+            .get_result(conn)
+    }
+}
+
+type Catalog = IntoBoxed<'static, LeftJoin<products_table, categories::table>, Pg>;
+
+/// Approved products matching the filter along with their category, sorted as requested
+fn catalog(filter: &ProductFilter) -> Catalog {
+    let mut query = products_table.left_join(categories::table).into_boxed()
+        .filter(products::review_status.eq(ReviewStatus::Approved));
+
+    if !filter.include_deleted {
+        query = query.filter(products::deleted_at.is_null());
+    }
+
+    if let Some(q) = &filter.q {
+        let pattern = format!("%{}%", escape_like(q));
+
+        query = query.filter(products::name.ilike(pattern.clone()).or(products::description.ilike(pattern)));
+    }
+
+    if let Some(min_price) = filter.min_price {
+        query = query.filter(products::price.ge(min_price));
+    }
+
+    if let Some(max_price) = filter.max_price {
+        query = query.filter(products::price.le(max_price));
+    }
+
+    if let Some(available) = filter.available {
+        query = query.filter(products::available.eq(available));
+    }
+
+    if let Some(category_ids) = &filter.category_ids {
+        query = query.filter(products::category_id.eq_any(category_ids.clone()));
+    }
+
+    if let Some(tag) = &filter.tag {
+        let tagged = product_tags::table
+            .inner_join(tags::table)
+            .filter(tags::name.eq(tag.clone()))
+            .select(product_tags::product_id);
+
+        query = query.filter(products::id.eq_any(tagged));
+    }
+
+    if let Some(updated_since) = filter.updated_since {
+        query = query.filter(products::updated_at.ge(updated_since));
+    }
+
+    query = match (filter.sort_by, filter.direction) {
+        (ProductSortField::Id, SortDirection::Asc) => query.order(products::id.asc()),
+        (ProductSortField::Id, SortDirection::Desc) => query.order(products::id.desc()),
+        (ProductSortField::Name, SortDirection::Asc) => query.order(products::name.asc()),
+        (ProductSortField::Name, SortDirection::Desc) => query.order(products::name.desc()),
+        (ProductSortField::Price, SortDirection::Asc) => query.order(products::price.asc()),
+        (ProductSortField::Price, SortDirection::Desc) => query.order(products::price.desc()),
+        (ProductSortField::CreatedAt, SortDirection::Asc) => query.order(products::created_at.asc()),
+        (ProductSortField::CreatedAt, SortDirection::Desc) => query.order(products::created_at.desc()),
+        (ProductSortField::UpdatedAt, SortDirection::Asc) => query.order(products::updated_at.asc()),
+        (ProductSortField::UpdatedAt, SortDirection::Desc) => query.order(products::updated_at.desc()),
+    };
+
+    // Keeps pages stable when the sorted values repeat
+    query.then_order_by(products::id.asc())
+}
+
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
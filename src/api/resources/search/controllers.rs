@@ -0,0 +1,35 @@
+use std::str::FromStr;
+use salvo::prelude::*;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::responses as api_responses;
+use crate::api::utils::get_db;
+use crate::api::utils::pagination::{Pagination, DEFAULT_PER_PAGE};
+use crate::api::validations::{QueryValidator, Validator};
+use super::models::SearchLanguage;
+
+#[handler]
+pub fn search(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.search_repo();
+
+    let validator = QueryValidator(req);
+
+    let q = validator.string("q")?;
+
+    if q.trim().is_empty() {
+        return Err(ApiError::BadRequestError("`q` cannot be empty".to_string()));
+    }
+
+    let language = match validator.optional_string("lang")? {
+        None => SearchLanguage::default(),
+        Some(lang) => SearchLanguage::from_str(&lang).map_err(ApiError::BadRequestError)?,
+    };
+
+    let page = req.query::<i64>("page").unwrap_or(1).max(1);
+    let per_page = req.query::<i64>("per_page").unwrap_or(DEFAULT_PER_PAGE).max(1);
+
+    let (entries, hits) = repo.search(language, &q, page, per_page)?;
+
+    api_responses::render_collection_paginated(res, hits, Pagination::new(page, per_page, entries));
+
+    Ok(())
+}
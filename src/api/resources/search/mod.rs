@@ -0,0 +1,11 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use salvo::prelude::*;
+use self::controllers::search;
+
+pub fn get_router() -> Router {
+    Router::with_path("search")
+        .get(search)
+}
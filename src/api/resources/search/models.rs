@@ -0,0 +1,86 @@
+use std::str::FromStr;
+use diesel::prelude::*;
+use diesel::sql_types::{Float4, Int4, Text};
+use serde::{Deserialize, Serialize};
+use crate::api::utils::escape_html;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum SearchLanguage {
+    /// Most of the catalog is written in spanish
+    #[default]
+    Spanish,
+    English,
+}
+
+impl SearchLanguage {
+    /// Text search configuration used by postgres
+    pub fn config(&self) -> &'static str {
+        match self {
+            SearchLanguage::Spanish => "spanish",
+            SearchLanguage::English => "english",
+        }
+    }
+
+    /// Generated `tsvector` column holding the documents for the language
+    pub fn column(&self) -> &'static str {
+        match self {
+            SearchLanguage::Spanish => "search_es",
+            SearchLanguage::English => "search_en",
+        }
+    }
+}
+
+impl FromStr for SearchLanguage {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "es" | "spanish" => Ok(SearchLanguage::Spanish),
+            "en" | "english" => Ok(SearchLanguage::English),
+            other => Err(format!("`{other}` is not a supported language, use `es` or `en`")),
+        }
+    }
+}
+
+/// A product or a public wishlist matching the search
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(QueryableByName)]
+pub struct SearchHit {
+    /// `product` or `wishlist`
+    #[diesel(sql_type = Text)]
+    pub kind: String,
+    #[diesel(sql_type = Int4)]
+    pub id: i32,
+    #[diesel(sql_type = Text)]
+    pub title: String,
+    /// HTML escaped fragments of the text with the matches wrapped in `<mark>`
+    #[diesel(sql_type = Text)]
+    pub headline: String,
+    #[diesel(sql_type = Float4)]
+    pub rank: f32,
+}
+
+/// Delimit the matches in the headlines postgres builds, they are stripped
+/// from the documents so only real matches carry them
+pub const START_MATCH: char = '\u{2}';
+pub const STOP_MATCH: char = '\u{3}';
+
+/// Escapes the text users wrote and only then marks the matches, so the
+/// headline is safe to render as HTML
+pub fn highlight(headline: &str) -> String {
+    escape_html(headline)
+        .replace(START_MATCH, "<mark>")
+        .replace(STOP_MATCH, "</mark>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_headlines() {
+        let headline = format!("<img src=x onerror=alert(1)> {START_MATCH}Kite{STOP_MATCH} & more");
+
+        assert_eq!(highlight(&headline), "&lt;img src=x onerror=alert(1)&gt; <mark>Kite</mark> &amp; more");
+    }
+}
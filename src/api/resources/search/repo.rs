@@ -0,0 +1,59 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{BigInt, Text};
+use crate::services::database::{contracts::SearchRepo, establish_connection};
+use super::models::{highlight, SearchHit, SearchLanguage, START_MATCH, STOP_MATCH};
+
+#[derive(QueryableByName)]
+struct Total {
+    #[diesel(sql_type = BigInt)]
+    entries: i64,
+}
+
+pub struct Repo;
+
+impl SearchRepo for Repo {
+    fn search(&self, language: SearchLanguage, q: &str, page: i64, per_page: i64) -> Result<(i64, Vec<SearchHit>), Error> {
+        let conn = &mut establish_connection();
+
+        // Both the configuration and the column come from `SearchLanguage`,
+        // never from the request, so formatting them in is safe
+        let config = language.config();
+        let column = language.column();
+
+        let headline_options = format!("StartSel={START_MATCH}, StopSel={STOP_MATCH}, MaxFragments=2, MaxWords=20, MinWords=5");
+        let markers = format!("{START_MATCH}{STOP_MATCH}");
+
+        let hits = format!(r#"
+            WITH search AS (SELECT websearch_to_tsquery('{config}', $1) AS query)
+            SELECT 'product' AS kind, p.id, p.name::text AS title,
+                ts_headline('{config}', translate(coalesce(p.description, p.name), '{markers}', ''), search.query, '{headline_options}') AS headline,
+                ts_rank(p.{column}, search.query) AS rank
+            FROM products p, search
+            WHERE p.{column} @@ search.query AND p.review_status = 'APPROVED' AND p.deleted_at IS NULL
+            UNION ALL
+            SELECT 'wishlist' AS kind, w.id, w.title::text AS title,
+                ts_headline('{config}', translate(coalesce(w.description, w.title), '{markers}', ''), search.query, '{headline_options}') AS headline,
+                ts_rank(w.{column}, search.query) AS rank
+            FROM wishlists w, search
+            WHERE w.{column} @@ search.query AND w.visibility = 'PUBLIC' AND w.deleted_at IS NULL
+        "#);
+
+        // Counted apart, a page past the end still knows the total
+        let Total { entries } = diesel::sql_query(format!("SELECT COUNT(*) AS entries FROM ({hits}) hits"))
+            .bind::<Text, _>(q)
+            .get_result(conn)?;
+
+        let hits: Vec<SearchHit> = diesel::sql_query(format!("SELECT * FROM ({hits}) hits ORDER BY rank DESC, kind, id LIMIT $2 OFFSET $3"))
+            .bind::<Text, _>(q)
+            .bind::<BigInt, _>(per_page)
+            .bind::<BigInt, _>((page - 1) * per_page)
+            .load(conn)?;
+
+        let hits = hits.into_iter()
+            .map(|hit| SearchHit { headline: highlight(&hit.headline), ..hit })
+            .collect();
+
+        Ok((entries, hits))
+    }
+}
@@ -0,0 +1,121 @@
+use salvo::prelude::*;
+use salvo::http::form::FormData;
+use serde_json::json;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::policy::{authorize_wishlist, Action, Subject};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::resources::wishlists::models::DetailedWishlist;
+use crate::api::utils::{get_db, random_token};
+use crate::api::validations::{FormValidator, Validator};
+use crate::api::responses as api_responses;
+use crate::models::Composable;
+use super::models::NewShareLink;
+
+const TOKEN_LENGTH: usize = 32;
+
+#[handler]
+pub fn list_share_links(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Edit, &wishlist)?;
+
+    let share_links = db.share_link_repo().list_by_wishlist(wishlist_id)?;
+
+    api_responses::render_collection(res, share_links);
+
+    Ok(())
+}
+
+#[handler]
+pub async fn create_share_link(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let form_data = req.form_data().await?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Manage, &wishlist)?;
+
+    if !wishlist.visibility.shareable() {
+        return Err(ApiError::Conflict(format!("A {} wishlist can't be shared, make it link only first", wishlist.visibility.as_str())));
+    }
+
+    let new_share_link = cast_form_data_to_new_share_link(form_data, wishlist_id)?;
+
+    let share_link = db.share_link_repo().insert(new_share_link)?;
+
+    // The token itself is a secret, only the link is referenced
+    audit::record(req, depot, NewAuditLog::new("share", "wishlist", Some(wishlist_id))
+        .details(json!({ "share_link_id": share_link.id })));
+
+    api_responses::render_resource_created(res, share_link);
+
+    Ok(())
+}
+
+#[handler]
+pub fn revoke_share_link(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Manage, &wishlist)?;
+
+    let total = db.share_link_repo().revoke(wishlist_id, id)?;
+
+    if total == 0 {
+        return Err(ApiError::Diesel(diesel::result::Error::NotFound));
+    }
+
+    audit::record(req, depot, NewAuditLog::new("revoke_share", "wishlist", Some(wishlist_id))
+        .details(json!({ "share_link_id": id })));
+
+    api_responses::render_db_execution(res, total);
+
+    Ok(())
+}
+
+/// Public, read-only access to a wishlist through its secret token
+#[handler]
+pub fn show_shared_wishlist(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let token = req.param::<String>("token").ok_or(ApiError::FieldNotFound("token".to_string()))?;
+
+    let share_link = db.share_link_repo().find_active(&token)?;
+
+    let wishlist = db.wishlist_repo().find_one(share_link.wishlist_id)?;
+
+    // Links outlive visibility changes, making the wishlist private closes them
+    if !wishlist.visibility.shareable() {
+        return Err(ApiError::Diesel(diesel::result::Error::NotFound));
+    }
+
+    let wishes = db.wish_repo().list_by_wishlist(wishlist.id)?;
+
+    api_responses::render_resource(res, DetailedWishlist::compose(wishlist, wishes));
+
+    Ok(())
+}
+
+fn cast_form_data_to_new_share_link(form_data: &FormData, wishlist_id: i32) -> ApiResult<NewShareLink> {
+    let validator = FormValidator(form_data);
+
+    let new_share_link = NewShareLink {
+        wishlist_id,
+        token: random_token(TOKEN_LENGTH),
+        expires_at: validator.optional_date("expires_at")?,
+    };
+
+    Ok(new_share_link)
+}
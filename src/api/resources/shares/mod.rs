@@ -0,0 +1,23 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use salvo::prelude::*;
+use crate::api::auth;
+use auth::controllers::handle_auth;
+use self::controllers::{create_share_link, list_share_links, revoke_share_link, show_shared_wishlist};
+
+pub fn get_router_for_wishlists() -> Router {
+    Router::with_path("wishlists/<wishlist_id>/shares")
+        .hoop(handle_auth)
+        .get(list_share_links)
+        .post(create_share_link)
+        .push(Router::with_path("<id>")
+            .delete(revoke_share_link)
+        )
+}
+
+pub fn get_public_router() -> Router {
+    Router::with_path("shared/<token>")
+        .get(show_shared_wishlist)
+}
@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::api::resources::wishlists::models::Wishlist;
+use crate::api::utils::formatters::optional_date;
+use crate::schema;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable, Identifiable, Associations)]
+#[diesel(belongs_to(Wishlist))]
+#[diesel(table_name = schema::share_links)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ShareLink {
+    pub id: i32,
+    pub wishlist_id: i32,
+    pub token: String,
+    #[serde(with = "optional_date")]
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked: bool,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = schema::share_links)]
+pub struct NewShareLink {
+    pub wishlist_id: i32,
+    pub token: String,
+    pub expires_at: Option<NaiveDateTime>,
+}
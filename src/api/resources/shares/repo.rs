@@ -0,0 +1,50 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+use crate::schema::share_links::{self, table as share_links_table};
+use crate::services::database::{contracts::ShareLinkRepo, establish_connection};
+use super::models::{NewShareLink, ShareLink};
+
+pub struct Repo;
+
+impl ShareLinkRepo for Repo {
+    fn list_by_wishlist(&self, wishlist_id: i32) -> Result<Vec<ShareLink>, Error> {
+        let conn = &mut establish_connection();
+
+        share_links_table
+            .filter(share_links::wishlist_id.eq(wishlist_id))
+            .select(ShareLink::as_select())
+            .load(conn)
+    }
+
+    fn find_active(&self, token: &str) -> Result<ShareLink, Error> {
+        let conn = &mut establish_connection();
+
+        share_links_table
+            .filter(share_links::token.eq(token))
+            .filter(share_links::revoked.eq(false))
+            .filter(share_links::expires_at.is_null().or(share_links::expires_at.gt(diesel::dsl::now)))
+            .select(ShareLink::as_select())
+            .first(conn)
+    }
+
+    fn insert(&self, new_share_link: NewShareLink) -> Result<ShareLink, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::insert_into(share_links_table)
+            .values(&new_share_link)
+            .returning(ShareLink::as_returning())
+            .get_result(conn)
+    }
+
+    fn revoke(&self, wishlist_id: i32, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(
+            share_links_table
+                .filter(share_links::wishlist_id.eq(wishlist_id))
+                .find(id),
+        )
+        .set(share_links::revoked.eq(true))
+        .execute(conn)
+    }
+}
@@ -1,19 +1,31 @@
 use std::sync::Arc;
 
-use salvo::{prelude::*, http::form::FormData};
-use crate::api::validations::{Validator, FormValidator};
+use diesel::result::Error;
+use salvo::prelude::*;
+use crate::api::validations::{BodyValidator, Constraint, FieldValidator, Validator};
 use crate::api::{responses as api_responses, utils};
+use crate::api::utils::sorting::SyncFilter;
 use crate::api::errors::{ApiResult, ApiError};
+use crate::api::policy::{authorize_wishlist, Action, Subject};
+use crate::api::resources::audit::{self, models::NewAuditLog};
 use crate::services::database::contracts::DatabaseService;
-use super::models::NewSponsor;
+use crate::api::resources::inbox;
+use crate::api::resources::wishes::models::Wish;
+use crate::api::resources::wishlists::models::Wishlist;
+use crate::services::notifications::messages::Message;
+use super::models::{NewSponsor, Sponsor};
 
 #[handler]
 pub async fn list_sponsors_wish(req: &mut Request, depot: &mut Depot, res: &mut Response) -> ApiResult<()> {
-    let repo = get_db(depot)?.sponsor_repo();
+    let db = get_db(depot)?;
 
     let wish_id = req.param("wish_id").ok_or(ApiError::FieldNotFound("wish_id".to_string()))?;
 
-    let sponsors = repo.list_by_wish(wish_id)?;
+    authorize_wish(db, depot, wish_id)?;
+
+    let filter = SyncFilter::from_query(req)?;
+
+    let sponsors = db.sponsor_repo().list_by_wish(wish_id, &filter)?;
 
     api_responses::render_collection(res, sponsors);
     
@@ -22,34 +34,80 @@ pub async fn list_sponsors_wish(req: &mut Request, depot: &mut Depot, res: &mut
 
 #[handler]
 pub async fn add_sponsor(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
-    let repo = get_db(depot)?.sponsor_repo();
+    let db = get_db(depot)?;
 
-    let form_data = req.form_data().await?;
+    let body = BodyValidator::from_request(req).await?;
 
     let user_id = utils::get_user_id(depot).unwrap_or_default();
 
-    let new_product = cast_form_data_to_new_sponsor(form_data, user_id)?;
+    let new_product = cast_form_data_to_new_sponsor(&body, user_id)?;
+
+    let (wish, wishlist) = authorize_wish(db, depot, new_product.wish_id)?;
+
+    let (product, funded) = db.sponsor_repo().insert(new_product)?;
+
+    audit::record(req, depot, NewAuditLog::new("create", "sponsor", Some(product.id)).changes(None, Some(&product)));
 
-    let product = repo.insert(new_product)?;
+    if let Err(error) = notify_owner(db, depot, &wish, &wishlist, &product, funded) {
+        tracing::error!("Notifying the owner of wishlist {} failed: {error}", wishlist.id);
+    }
 
     api_responses::render_resource(res, product);
 
     Ok(())
 }
 
-fn cast_form_data_to_new_sponsor(form_data: &FormData, user_id: i32) -> ApiResult<NewSponsor> {
-    let validator = FormValidator(form_data);
+fn cast_form_data_to_new_sponsor(validator: &dyn Validator, user_id: i32) -> ApiResult<NewSponsor> {
+    let mut validator = FieldValidator::new(validator);
 
     let new_sponsor = NewSponsor {
         user_id,
-        wish_id: validator.integer("wish_id")?,
-        amount: validator.float("amount")?,
-        leader: validator.optional_boolean("leader")?,
+        wish_id: validator.integer("wish_id", &[Constraint::Min(1.0)]),
+        amount: validator.float("amount", &[Constraint::Min(0.01)]),
+        leader: validator.optional_boolean("leader"),
     };
 
+    validator.finish()?;
+
     Ok(new_sponsor)
 }
 
+/// Sponsors belong to whoever can see the wish's wishlist
+fn authorize_wish(db: &Arc<dyn DatabaseService>, depot: &Depot, wish_id: i32) -> ApiResult<(Wish, Wishlist)> {
+    let wish = db.wish_repo().find_one(wish_id)?;
+
+    let wishlist = db.wishlist_repo().find_one(wish.wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::View, &wishlist)?;
+
+    Ok((wish, wishlist))
+}
+
+/// Tells the wishlist owner about the new sponsor, and that the wish is
+/// funded when this sponsor is the one reaching the product's price
+fn notify_owner(db: &Arc<dyn DatabaseService>, depot: &Depot, wish: &Wish, wishlist: &Wishlist, sponsor: &Sponsor, funded: bool) -> Result<(), Error> {
+    let product = db.product_repo().find_one(wish.product_id)?;
+
+    if sponsor.user_id != wishlist.user_id {
+        let sponsor_user = db.user_repo().find_user(sponsor.user_id)?;
+
+        inbox::notify(depot, wishlist.user_id, &Message::WishSponsored {
+            sponsor: sponsor_user.name,
+            product: product.name.clone(),
+            wishlist: wishlist.title.clone(),
+        });
+    }
+
+    if funded {
+        inbox::notify(depot, wishlist.user_id, &Message::WishFunded {
+            product: product.name,
+            wishlist: wishlist.title.clone(),
+        });
+    }
+
+    Ok(())
+}
+
 fn get_db(depot: &Depot) -> ApiResult<&Arc<dyn DatabaseService>> {
     use crate::api::errors::InjectionError;
 
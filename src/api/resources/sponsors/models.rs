@@ -1,8 +1,10 @@
 use diesel::prelude::*;
 use serde::Serialize;
+use chrono::NaiveDateTime;
 use crate::api::resources::users::models::User;
 use crate::api::resources::wishes::models::Wish;
 use crate::schema;
+use crate::api::utils::formatters::date;
 
 #[derive(Serialize, Debug, Clone)]
 #[derive(Queryable, Selectable, Identifiable, Associations, PartialEq, AsChangeset)]
@@ -16,6 +18,10 @@ pub struct Sponsor {
     pub amount: f32,
     pub user_id: i32,
     pub wish_id: i32,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -1,7 +1,7 @@
-use diesel::query_dsl::methods::{FilterDsl, SelectDsl};
-use diesel::{ExpressionMethods, RunQueryDsl, SelectableHelper};
+use diesel::prelude::*;
 
-use crate::schema;
+use crate::schema::{products, sponsors, wishes};
+use crate::api::utils::sorting::{SortDirection, SortField, SyncFilter};
 use crate::services::database::{contracts::SponsorRepo, establish_connection};
 use crate::schema::sponsors::table as sponsors_table;
 use super::models::{NewSponsor, Sponsor};
@@ -10,21 +10,55 @@ use diesel::result::Error;
 pub struct Repo;
 
 impl SponsorRepo for Repo {
-    fn insert(&self, new_sponsor: NewSponsor) -> Result<Sponsor, Error> {
+    fn insert(&self, new_sponsor: NewSponsor) -> Result<(Sponsor, bool), Error> {
         let conn = &mut establish_connection();
 
-        diesel::insert_into(sponsors_table)
-            .values(&new_sponsor)
-            .returning(Sponsor::as_returning())
-            .get_result(conn)
+        conn.transaction(|conn| {
+            // Sponsors of the same wish wait for each other here
+            let price: f32 = wishes::table
+                .inner_join(products::table)
+                .filter(wishes::id.eq(new_sponsor.wish_id))
+                .select(products::price)
+                .for_update()
+                .first(conn)?;
 
+            let previous: f32 = sponsors_table
+                .filter(sponsors::wish_id.eq(new_sponsor.wish_id))
+                .select(diesel::dsl::sum(sponsors::amount))
+                .first::<Option<f32>>(conn)?
+                .unwrap_or_default();
+
+            let sponsor = diesel::insert_into(sponsors_table)
+                .values(&new_sponsor)
+                .returning(Sponsor::as_returning())
+                .get_result(conn)?;
+
+            Ok((sponsor, previous < price && previous + new_sponsor.amount >= price))
+        })
     }
     
-    fn list_by_wish(&self, wish_id: i32) -> Result<Vec<Sponsor>, Error> {
+    fn list_by_wish(&self, wish_id: i32, filter: &SyncFilter) -> Result<Vec<Sponsor>, Error> {
         let conn = &mut establish_connection();
 
-        sponsors_table
-            .filter(schema::sponsors::wish_id.eq(wish_id))
+        let mut query = sponsors_table
+            .filter(sponsors::wish_id.eq(wish_id))
+            .into_boxed();
+
+        if let Some(updated_since) = filter.updated_since {
+            query = query.filter(sponsors::updated_at.ge(updated_since));
+        }
+
+        query = match (filter.sort_by.unwrap_or_default(), filter.direction) {
+            (SortField::Id, SortDirection::Asc) => query.order(sponsors::id.asc()),
+            (SortField::Id, SortDirection::Desc) => query.order(sponsors::id.desc()),
+            (SortField::CreatedAt, SortDirection::Asc) => query.order(sponsors::created_at.asc()),
+            (SortField::CreatedAt, SortDirection::Desc) => query.order(sponsors::created_at.desc()),
+            (SortField::UpdatedAt, SortDirection::Asc) => query.order(sponsors::updated_at.asc()),
+            (SortField::UpdatedAt, SortDirection::Desc) => query.order(sponsors::updated_at.desc()),
+        };
+
+        query
+            .then_order_by(sponsors::id.asc())
             .select(Sponsor::as_select())
             .load(conn)
     }
@@ -0,0 +1,66 @@
+use salvo::prelude::*;
+use serde_json::json;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::utils::get_db;
+use crate::api::validations::{FormValidator, Validator};
+use crate::api::responses as api_responses;
+use super::models::normalize_tags;
+
+#[handler]
+pub fn list_tags(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.tag_repo();
+
+    let tags = repo.list()?;
+
+    api_responses::render_collection(res, tags);
+
+    Ok(())
+}
+
+/// Replaces the tags of a product, creating the missing ones
+#[handler]
+pub async fn set_product_tags(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let product_id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let form_data = req.form_data().await?;
+
+    let names = normalize_tags(&FormValidator(form_data).string_list("tags")?);
+
+    db.product_repo().find_one(product_id)?;
+
+    let previous_names: Vec<String> = db.tag_repo().list_by_product(product_id)?
+        .into_iter()
+        .map(|tag| tag.name)
+        .collect();
+
+    let tags = db.tag_repo().set_product_tags(product_id, &names)?;
+
+    audit::record(req, depot, NewAuditLog::new("tag", "product", Some(product_id))
+        .details(json!({ "before": { "tags": previous_names }, "after": { "tags": names } })));
+
+    api_responses::render_resource_updated(res, tags);
+
+    Ok(())
+}
+
+#[handler]
+pub fn delete_tag(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.tag_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let total = repo.delete(id)?;
+
+    if total == 0 {
+        return Err(ApiError::Diesel(diesel::result::Error::NotFound));
+    }
+
+    audit::record(req, depot, NewAuditLog::new("delete", "tag", Some(id)));
+
+    api_responses::render_db_execution(res, total);
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use salvo::prelude::*;
+use self::controllers::{delete_tag, list_tags, set_product_tags};
+
+pub fn get_router() -> Router {
+    Router::with_path("tags")
+        .get(list_tags)
+}
+
+pub fn get_admin_router() -> Router {
+    Router::new()
+        .push(Router::with_path("tags/<id>")
+            .delete(delete_tag)
+        )
+        .push(Router::with_path("products/<id>/tags")
+            .put(set_product_tags)
+        )
+}
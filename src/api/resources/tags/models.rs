@@ -0,0 +1,40 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::schema;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = schema::tags)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Insertable)]
+#[diesel(table_name = schema::tags)]
+pub struct NewTag {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable, Insertable)]
+#[diesel(table_name = schema::product_tags)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductTag {
+    pub product_id: i32,
+    pub tag_id: i32,
+}
+
+/// Tags are free-form but compared trimmed and lowercased, without repetitions
+pub fn normalize_tags(names: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = names.iter()
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    tags.sort();
+    tags.dedup();
+
+    tags
+}
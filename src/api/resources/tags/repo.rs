@@ -0,0 +1,72 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+use crate::schema::{product_tags, tags};
+use crate::schema::tags::table as tags_table;
+use crate::services::database::{contracts::TagRepo, establish_connection};
+use super::models::{NewTag, ProductTag, Tag};
+
+pub struct Repo;
+
+impl TagRepo for Repo {
+    fn list(&self) -> Result<Vec<Tag>, Error> {
+        let conn = &mut establish_connection();
+
+        tags_table
+            .order(tags::name.asc())
+            .select(Tag::as_select())
+            .load(conn)
+    }
+
+    fn list_by_product(&self, product_id: i32) -> Result<Vec<Tag>, Error> {
+        let conn = &mut establish_connection();
+
+        product_tags::table
+            .inner_join(tags_table)
+            .filter(product_tags::product_id.eq(product_id))
+            .order(tags::name.asc())
+            .select(Tag::as_select())
+            .load(conn)
+    }
+
+    fn set_product_tags(&self, product_id: i32, names: &[String]) -> Result<Vec<Tag>, Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let new_tags: Vec<NewTag> = names.iter()
+                .map(|name| NewTag { name: name.clone() })
+                .collect();
+
+            diesel::insert_into(tags_table)
+                .values(&new_tags)
+                .on_conflict(tags::name)
+                .do_nothing()
+                .execute(conn)?;
+
+            let tags: Vec<Tag> = tags_table
+                .filter(tags::name.eq_any(names))
+                .order(tags::name.asc())
+                .select(Tag::as_select())
+                .load(conn)?;
+
+            diesel::delete(product_tags::table.filter(product_tags::product_id.eq(product_id)))
+                .execute(conn)?;
+
+            let product_tags: Vec<ProductTag> = tags.iter()
+                .map(|tag| ProductTag { product_id, tag_id: tag.id })
+                .collect();
+
+            diesel::insert_into(product_tags::table)
+                .values(&product_tags)
+                .execute(conn)?;
+
+            Ok(tags)
+        })
+    }
+
+    fn delete(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::delete(tags_table.find(id))
+            .execute(conn)
+    }
+}
@@ -0,0 +1,150 @@
+use salvo::prelude::*;
+use salvo::http::form::FormData;
+use serde_json::json;
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::resources::wishlists::models::{NewWishlist, Visibility};
+use crate::api::utils::{get_db, get_user_id};
+use crate::api::validations::{FormValidator, Validator};
+use crate::api::responses as api_responses;
+use crate::models::Mergeable;
+use super::models::{DetailedTemplate, NewTemplate, Template};
+
+#[handler]
+pub fn list_templates(depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.template_repo();
+
+    let templates = repo.list()?;
+
+    api_responses::render_collection(res, templates);
+
+    Ok(())
+}
+
+#[handler]
+pub fn show_template(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.template_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let template = repo.find_one(id)?;
+
+    api_responses::render_resource(res, template);
+
+    Ok(())
+}
+
+#[handler]
+pub async fn instantiate_template(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let user_id = get_user_id(depot).ok_or(ApiError::NotAllowed("User not found".to_string()))?;
+
+    let form_data = req.form_data().await?;
+
+    let template = db.template_repo().find_one(id)?;
+
+    let new_wishlist = cast_form_data_to_instance(form_data, &template, user_id)?;
+
+    let wishlist = db.wishlist_repo().insert_with_products(new_wishlist, template.product_ids())?;
+
+    audit::record(req, depot, NewAuditLog::new("create", "wishlist", Some(wishlist.id))
+        .details(json!({ "template_id": id })));
+
+    api_responses::render_resource_created(res, wishlist);
+
+    Ok(())
+}
+
+#[handler]
+pub async fn create_template(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.template_repo();
+
+    let form_data = req.form_data().await?;
+
+    let (new_template, product_ids) = cast_form_data_to_new_template(form_data)?;
+
+    let template = repo.insert(new_template, product_ids.unwrap_or_default())?;
+
+    audit::record(req, depot, NewAuditLog::new("create", "template", Some(template.id)).changes(None, Some(&template)));
+
+    api_responses::render_resource_created(res, template);
+
+    Ok(())
+}
+
+#[handler]
+pub async fn update_template(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.template_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let form_data = req.form_data().await?;
+
+    let current_template = repo.find_one(id)?;
+
+    let template = Template::from(current_template.clone()).merge(&FormValidator(form_data))?;
+
+    let product_ids = optional_product_ids(&FormValidator(form_data))?;
+
+    let updated_template = repo.update(&template, product_ids)?;
+
+    audit::record(req, depot, NewAuditLog::new("update", "template", Some(id)).changes(Some(&current_template), repo.find_one(id).ok().as_ref()));
+
+    api_responses::render_resource_updated(res, updated_template);
+
+    Ok(())
+}
+
+#[handler]
+pub fn delete_template(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.template_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let total = repo.delete(id)?;
+
+    if total > 0 {
+        audit::record(req, depot, NewAuditLog::new("delete", "template", Some(id)));
+    }
+
+    api_responses::render_db_execution(res, total);
+
+    Ok(())
+}
+
+fn optional_product_ids(validator: &FormValidator) -> ApiResult<Option<Vec<i32>>> {
+    match validator.get("product_ids") {
+        None => Ok(None),
+        Some(_) => Ok(Some(validator.integer_list("product_ids")?)),
+    }
+}
+
+fn cast_form_data_to_new_template(form_data: &FormData) -> ApiResult<(NewTemplate, Option<Vec<i32>>)> {
+    let validator = FormValidator(form_data);
+
+    let new_template = NewTemplate {
+        title: validator.string("title")?,
+        description: validator.optional_string("description")?,
+    };
+
+    let product_ids = optional_product_ids(&validator)?;
+
+    Ok((new_template, product_ids))
+}
+
+fn cast_form_data_to_instance(form_data: &FormData, template: &DetailedTemplate, user_id: i32) -> ApiResult<NewWishlist> {
+    let validator = FormValidator(form_data);
+
+    let new_wishlist = NewWishlist {
+        title: validator.optional_string("title")?.unwrap_or(template.title.clone()),
+        description: template.description.clone(),
+        date: validator.optional_date("date")?,
+        user_id,
+        visibility: Visibility::Private,
+    };
+
+    Ok(new_wishlist)
+}
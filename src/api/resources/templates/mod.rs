@@ -0,0 +1,36 @@
+pub mod models;
+mod controllers;
+pub mod repo;
+
+use salvo::prelude::*;
+use crate::api::auth;
+use auth::controllers::handle_auth;
+use self::controllers::{
+    create_template,
+    delete_template,
+    instantiate_template,
+    list_templates,
+    show_template,
+    update_template,
+};
+
+pub fn get_router() -> Router {
+    Router::with_path("templates")
+        .hoop(handle_auth)
+        .get(list_templates)
+        .push(Router::with_path("<id>")
+            .get(show_template)
+            .push(Router::with_path("instantiate")
+                .post(instantiate_template)
+            )
+        )
+}
+
+pub fn get_admin_router() -> Router {
+    Router::with_path("templates")
+        .post(create_template)
+        .push(Router::with_path("<id>")
+            .put(update_template)
+            .delete(delete_template)
+        )
+}
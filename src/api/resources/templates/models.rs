@@ -0,0 +1,85 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::api::errors::{ApiError, ApiResult};
+use crate::api::validations::Validator;
+use crate::api::resources::products::models::Product;
+use crate::models::{Composable, Mergeable};
+use crate::schema;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Queryable, Selectable, Identifiable, AsChangeset)]
+#[diesel(table_name = schema::templates)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Template {
+    pub id: i32,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = schema::templates)]
+pub struct NewTemplate {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = schema::template_products)]
+pub struct NewTemplateProduct {
+    pub template_id: i32,
+    pub product_id: i32,
+    pub position: i32,
+}
+
+impl Mergeable for Template {
+    fn merge(self, validator: &dyn Validator) -> ApiResult<Self> {
+        let mut updated = self.clone();
+
+        if validator.contains("title") {
+            let title = validator.optional_string("title")?.unwrap_or_default();
+
+            if title.is_empty() {
+                return Err(ApiError::BadRequestError("`title` cannot be empty".to_string()));
+            }
+
+            updated.title = title;
+        }
+
+        if validator.contains("description") {
+            updated.description = validator.optional_string("description")?;
+        }
+
+        Ok(updated)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetailedTemplate {
+    pub id: i32,
+    pub title: String,
+    pub description: Option<String>,
+    pub products: Vec<Product>,
+}
+
+impl DetailedTemplate {
+    pub fn product_ids(&self) -> Vec<i32> {
+        self.products.iter().map(|p| p.id).collect()
+    }
+}
+
+impl From<DetailedTemplate> for Template {
+    fn from(value: DetailedTemplate) -> Self {
+        let DetailedTemplate { id, title, description, .. } = value;
+
+        Self { id, title, description }
+    }
+}
+
+impl Composable<Template, Vec<Product>> for DetailedTemplate {
+    fn compose(template: Template, products: Vec<Product>) -> Self {
+        let Template { id, title, description } = template;
+
+        Self { id, title, description, products }
+    }
+}
@@ -0,0 +1,92 @@
+use diesel::prelude::*;
+use diesel::result::Error;
+use crate::api::resources::products::models::Product;
+use crate::models::Composable;
+use crate::schema::templates::table as templates_table;
+use crate::schema::template_products::table as template_products_table;
+use crate::schema::{products, template_products};
+use crate::services::database::{contracts::TemplateRepo, establish_connection};
+use super::models::{DetailedTemplate, NewTemplate, NewTemplateProduct, Template};
+
+pub struct Repo;
+
+impl TemplateRepo for Repo {
+    fn list(&self) -> Result<Vec<Template>, Error> {
+        let conn = &mut establish_connection();
+
+        templates_table
+            .select(Template::as_select())
+            .load(conn)
+    }
+
+    fn find_one(&self, id: i32) -> Result<DetailedTemplate, Error> {
+        let conn = &mut establish_connection();
+
+        let template = templates_table
+            .find(id)
+            .select(Template::as_select())
+            .first(conn)?;
+
+        let products = template_products_table
+            .inner_join(products::table)
+            .filter(template_products::template_id.eq(id))
+            .filter(products::deleted_at.is_null())
+            .order((template_products::position.asc(), template_products::id.asc()))
+            .select(Product::as_select())
+            .load(conn)?;
+
+        Ok(DetailedTemplate::compose(template, products))
+    }
+
+    fn insert(&self, new_template: NewTemplate, product_ids: Vec<i32>) -> Result<Template, Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let template = diesel::insert_into(templates_table)
+                .values(&new_template)
+                .returning(Template::as_returning())
+                .get_result(conn)?;
+
+            insert_template_products(conn, template.id, &product_ids)?;
+
+            Ok(template)
+        })
+    }
+
+    fn update(&self, template: &Template, product_ids: Option<Vec<i32>>) -> Result<Template, Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let updated = diesel::update(templates_table.find(template.id))
+                .set(template)
+                .get_result(conn)?;
+
+            if let Some(product_ids) = product_ids {
+                diesel::delete(template_products_table.filter(template_products::template_id.eq(template.id)))
+                    .execute(conn)?;
+
+                insert_template_products(conn, template.id, &product_ids)?;
+            }
+
+            Ok(updated)
+        })
+    }
+
+    fn delete(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::delete(templates_table.find(id))
+            .execute(conn)
+    }
+}
+
+fn insert_template_products(conn: &mut PgConnection, template_id: i32, product_ids: &[i32]) -> Result<usize, Error> {
+    let rows: Vec<NewTemplateProduct> = product_ids.iter()
+        .enumerate()
+        .map(|(position, product_id)| NewTemplateProduct { template_id, product_id: *product_id, position: position as i32 })
+        .collect();
+
+    diesel::insert_into(template_products_table)
+        .values(rows)
+        .execute(conn)
+}
@@ -1,7 +1,9 @@
 use diesel::prelude::*;
 use serde::{Serialize, Deserialize};
+use chrono::NaiveDateTime;
 use crate::schema;
 use crate::api::auth::models::User as AuthUser;
+use crate::api::utils::formatters::date;
 
 #[derive(Serialize, Deserialize, Clone)]
 #[derive(Queryable, Identifiable, Selectable, Debug, PartialEq)]
@@ -13,16 +15,34 @@ pub struct User {
     pub role: String,
     pub email: String,
     pub active: bool,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
+    /// Language of the notifications, see `Locale`
+    pub locale: String,
 }
 
 impl From<AuthUser> for User {
     fn from(value: AuthUser) -> Self {
-        let AuthUser { id, name, email, role, active, .. } = value;
+        let AuthUser { id, name, email, role, active, created_at, updated_at, locale, .. } = value;
 
-        Self { id, name, role, email, active }
+        Self { id, name, role, email, active, created_at, updated_at, locale }
     }
 }
 
+/// Only the columns users had before their emails were unique, so the clashes can be
+/// listed while the unique email migration, and every one after it, are still pending
+#[derive(Queryable, Selectable, Debug, Clone, PartialEq)]
+#[diesel(table_name = schema::users)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct EmailOwner {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+    pub active: bool,
+}
+
 #[derive(Debug)]
 #[derive(Insertable)]
 #[diesel(table_name = schema::users)]
@@ -31,5 +51,49 @@ pub struct NewUser {
     pub email: String,
     pub password: Vec<u8>,
     pub active: bool,
+    pub locale: String,
+}
+
+/// Emails are stored trimmed and lowercased, they are unique regardless of case
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Users sharing an email regardless of case, they block the unique email index until resolved
+pub fn email_clashes(users: Vec<EmailOwner>) -> Vec<Vec<EmailOwner>> {
+    let mut groups: Vec<(String, Vec<EmailOwner>)> = vec![];
+
+    for user in users {
+        let email = normalize_email(&user.email);
+
+        match groups.iter_mut().find(|(group_email, _)| *group_email == email) {
+            Some((_, group)) => group.push(user),
+            None => groups.push((email, vec![user])),
+        }
+    }
+
+    groups.into_iter()
+        .map(|(_, group)| group)
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{email_clashes, normalize_email, EmailOwner};
+
+    fn user(id: i32, email: &str) -> EmailOwner {
+        EmailOwner { id, name: format!("User {id}"), email: email.to_string(), active: true }
+    }
+
+    #[test]
+    fn group_email_clashes() {
+        let users = vec![user(1, "ana@dummy.test"), user(2, "bob@dummy.test"), user(3, " Ana@Dummy.test")];
+
+        let clashes = email_clashes(users);
+
+        assert_eq!(normalize_email(" Ana@Dummy.test "), "ana@dummy.test");
+        assert_eq!(clashes.len(), 1, "only ana's email is shared");
+        assert_eq!(clashes[0].iter().map(|user| user.id).collect::<Vec<i32>>(), vec![1, 3]);
+    }
 }
-    
\ No newline at end of file
@@ -1,20 +1,49 @@
 use diesel::prelude::*;
+use diesel::dsl::{AsSelect, Select};
+use diesel::pg::Pg;
 use diesel::{result::Error, SelectableHelper};
 use crate::services::database::{contracts::UserRepo, establish_connection};
+use crate::schema::users;
 use crate::schema::users::table as users_table;
-use super::models::{User, NewUser};
+use crate::api::utils::sorting::{SortDirection, SortField, SyncFilter};
+use crate::schema::notification_outbox::table as outbox_table;
+use crate::api::resources::outbox::models::NewOutboxEntry;
+use crate::services::notifications::messages::Message;
+use super::models::{EmailOwner, User, NewUser};
 
 pub struct Repo;
 
 impl UserRepo for Repo {
-    fn list(&self) -> Result<Vec<User>, Error> {
+    fn list(&self, filter: &SyncFilter) -> Result<Vec<User>, Error> {
         let conn = &mut establish_connection();
 
-        users_table
+        let mut query = users_table.into_boxed();
+
+        if let Some(updated_since) = filter.updated_since {
+            query = query.filter(users::updated_at.ge(updated_since));
+        }
+
+        query = match (filter.sort_by.unwrap_or_default(), filter.direction) {
+            (SortField::Id, SortDirection::Asc) => query.order(users::id.asc()),
+            (SortField::Id, SortDirection::Desc) => query.order(users::id.desc()),
+            (SortField::CreatedAt, SortDirection::Asc) => query.order(users::created_at.asc()),
+            (SortField::CreatedAt, SortDirection::Desc) => query.order(users::created_at.desc()),
+            (SortField::UpdatedAt, SortDirection::Asc) => query.order(users::updated_at.asc()),
+            (SortField::UpdatedAt, SortDirection::Desc) => query.order(users::updated_at.desc()),
+        };
+
+        query
+            .then_order_by(users::id.asc())
             .select(User::as_select())
             .load(conn)
     }
 
+    fn list_email_owners(&self) -> Result<Vec<EmailOwner>, Error> {
+        let conn = &mut establish_connection();
+
+        email_owners().load(conn)
+    }
+
     fn find_user(&self, id: i32) -> Result<User, Error> {
         let conn = &mut establish_connection();
 
@@ -29,6 +58,7 @@ impl UserRepo for Repo {
 
         diesel::insert_into(users_table)
             .values(users)
+            .on_conflict_do_nothing()
             .execute(conn)
     }
 
@@ -40,4 +70,26 @@ impl UserRepo for Repo {
             .returning(User::as_returning())
             .get_result(conn)
     }
+
+    fn insert_notified(&self, new_user: NewUser, message: &dyn Fn(&User) -> Message) -> Result<User, Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let user = diesel::insert_into(users_table)
+                .values(&new_user)
+                .returning(User::as_returning())
+                .get_result(conn)?;
+
+            diesel::insert_into(outbox_table)
+                .values(&NewOutboxEntry::new(user.id, &message(&user)))
+                .execute(conn)?;
+
+            Ok(user)
+        })
+    }
+}
+
+/// Every user with the columns that exist before the unique email migration
+pub fn email_owners() -> Select<users_table, AsSelect<EmailOwner, Pg>> {
+    users_table.select(EmailOwner::as_select())
 }
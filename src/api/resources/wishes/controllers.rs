@@ -1,31 +1,42 @@
 
 use salvo::prelude::*;
-use salvo::http::form::FormData;
+use serde_json::json;
 use crate::api::errors::{ApiError, ApiResult};
-use crate::api::utils::{get_db, get_user_id};
-use crate::api::validations::{FormValidator, Validator};
-use crate::api::{utils, errors as api_errors, responses as api_responses};
-use super::models::NewWish;
+use crate::api::resources::audit::{self, models::NewAuditLog};
+use crate::api::utils::get_db;
+use crate::api::utils::sorting::SyncFilter;
+use crate::api::validations::{BodyValidator, Constraint, FieldValidator, FormValidator, Validator};
+use crate::api::{errors as api_errors, responses as api_responses};
+use super::models::{NewWish, WishProduct};
 use super::repo;
-use crate::api::resources::wishlists::repo::find_wishlist;
+use crate::api::policy::{authorize, authorize_wishlist, Action, Resource, Subject};
 
 #[handler]
-pub fn list_wishes(req: &mut Request, depot: &mut Depot, res: &mut Response) {
-    match (utils::get_user_id(depot), req.param::<i32>("wishlist_id")) {
-        (None, _) => api_errors::render_get_user_id_not_found(res),
+pub fn list_wishes(req: &mut Request, depot: &mut Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
 
-        (_, None) => api_errors::render_resource_not_found(res, "associated wishlist"),
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
 
-        (Some(user_id), Some(wishlist_id)) => match find_wishlist(wishlist_id, user_id) {
-            Err(_) => api_errors::render_db_resource_not_associated(res, "wishlist"),
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
 
-            Ok(_) => match repo::list_wishes_from_wishlist(wishlist_id) {
-                Err(error) => api_errors::render_db_retrieving_error(res, error, "wishes"),
+    let subject = Subject::from_depot(depot);
 
-                Ok(wishes) => api_responses::render_collection(res, wishes)
-            }
-        }
-    }
+    authorize_wishlist(db, &subject, Action::View, &wishlist)?;
+
+    // Deleted wishes are only listed to whoever manages the wishlist
+    let manager = match authorize_wishlist(db, &subject, Action::Manage, &wishlist) {
+        Ok(()) => true,
+        Err(ApiError::NotAllowed(_)) => false,
+        Err(error) => return Err(error),
+    };
+
+    let filter = SyncFilter::from_query(req)?.with_deleted_for(manager);
+
+    let wishes = repo::list_wishes_from_wishlist(wishlist_id, &filter)?;
+
+    api_responses::render_collection(res, wishes);
+
+    Ok(())
 }
 
 #[handler]
@@ -34,22 +45,25 @@ pub fn show_wish(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<
 
     let detailed = req.query::<bool>("detailed").unwrap_or_default();
 
-    if detailed {
-        let user_id = utils::get_user_id(depot).ok_or(ApiError::NotAllowed("User not found".to_string()))?;
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
 
-        let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
 
-        let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+    let permission = match detailed {
+        true => Action::Edit,
+        false => Action::View,
+    };
 
-        if wishlist.user_id != user_id {
-            return Err(ApiError::NotAllowed("User is not allowed".to_string()));
-        }
-    }
+    authorize_wishlist(db, &Subject::from_depot(depot), permission, &wishlist)?;
 
     let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
 
     let wish = db.wish_repo().find_one_expanded(id)?;
 
+    if wish.wishlist_id != wishlist_id {
+        return Err(ApiError::Diesel(diesel::result::Error::NotFound));
+    }
+
     api_responses::render_resource(res, wish);
 
     Ok(())
@@ -59,20 +73,25 @@ pub fn show_wish(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<
 pub async fn create_wish(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
     let db = get_db(depot)?;
 
-    let form_data = req.form_data().await?;
-
-    let user_id = get_user_id(depot).unwrap_or_default();
+    let body = BodyValidator::from_request(req).await?;
 
-    let new_wish = cast_form_data_to_new_wish(form_data)?;
+    let new_wish = cast_form_data_to_new_wish(&body)?;
 
     let wishlist = db.wishlist_repo().find_one(new_wish.wishlist_id)?;
 
-    if wishlist.user_id != user_id {
-        return Err(ApiError::Deserializer("User doesn't own the wishlist".to_string()));
-    }
+    let subject = Subject::from_depot(depot);
+
+    authorize_wishlist(db, &subject, Action::Edit, &wishlist)?;
+
+    // Deleted products and the ones the user can't see aren't wished for
+    let product = db.product_repo().find_one(new_wish.product_id)?;
+
+    authorize(&subject, Action::View, &Resource::Product(&product))?;
 
     let wish = db.wish_repo().insert(new_wish)?;
 
+    audit::record(req, depot, NewAuditLog::new("create", "wish", Some(wish.id)).changes(None, Some(&wish)));
+
     api_responses::render_resource_created(res, wish);
 
     Ok(())
@@ -81,34 +100,102 @@ pub async fn create_wish(req: &mut Request, depot: &Depot, res: &mut Response) -
 #[handler]
 pub fn delete_wish(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
     let db = get_db(depot)?;
-    let wish_id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound(format!("wish")))?;
-    let user_id = utils::get_user_id(depot).ok_or(ApiError::NotAllowed("User not found".to_string()))?;
+    let wish_id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("wish".to_string()))?;
 
     let wish = db.wish_repo().find_one(wish_id)?;
     let wishlist = db.wishlist_repo().find_one(wish.wishlist_id)?;
-    
-    if wishlist.user_id != user_id {
-        return Err(ApiError::NotAllowed(format!("Not owner")));
-    }
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Edit, &wishlist)?;
 
     let total_deleted = db.wish_repo().delete(wish_id)?;
     
     if total_deleted == 0 {
         api_errors::render_resource_not_found(res, "wish");
     } else {
+        audit::record(req, depot, NewAuditLog::new("delete", "wish", Some(wish_id)).changes(Some(&wish), None));
+
         api_responses::render_db_execution(res, total_deleted);
     }
 
     Ok(())
 }
 
-fn cast_form_data_to_new_wish(form_data: &FormData) -> ApiResult<NewWish> {
-    let validator = FormValidator(form_data);
+#[handler]
+pub async fn reorder_wishes(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+    let wishlist_id = req.param::<i32>("wishlist_id").ok_or(ApiError::FieldNotFound("wishlist_id".to_string()))?;
+
+    let form_data = req.form_data().await?;
+
+    let wish_ids = FormValidator(form_data).integer_list("wish_ids")?;
+
+    let wishlist = db.wishlist_repo().find_one(wishlist_id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Edit, &wishlist)?;
+
+    let wish_repo = db.wish_repo();
+
+    let current_wishes = wish_repo.list_by_wishlist(wishlist_id)?;
+
+    check_wish_order(&current_wishes, &wish_ids)?;
+
+    wish_repo.reorder(wishlist_id, &wish_ids)?;
+
+    let previous_ids: Vec<i32> = current_wishes.iter().map(|w| w.id).collect();
+
+    audit::record(req, depot, NewAuditLog::new("reorder", "wishlist", Some(wishlist_id))
+        .details(json!({ "before": { "wish_ids": previous_ids }, "after": { "wish_ids": wish_ids } })));
+
+    let wishes = wish_repo.list_by_wishlist(wishlist_id)?;
+
+    api_responses::render_resource_updated(res, wishes);
+
+    Ok(())
+}
+
+fn check_wish_order(wishes: &[WishProduct], wish_ids: &[i32]) -> ApiResult<()> {
+    let mut expected: Vec<i32> = wishes.iter().map(|w| w.id).collect();
+    let mut candidate = wish_ids.to_vec();
+
+    expected.sort_unstable();
+    candidate.sort_unstable();
+
+    if expected != candidate {
+        return Err(ApiError::BadRequestError("`wish_ids` must list every wish of the wishlist exactly once".to_string()));
+    }
+
+    Ok(())
+}
+
+fn cast_form_data_to_new_wish(validator: &dyn Validator) -> ApiResult<NewWish> {
+    let mut validator = FieldValidator::new(validator);
 
     let new_wish = NewWish { 
-        wishlist_id: validator.integer("wishlist_id")?,
-        product_id: validator.integer("product_id")?,
+        wishlist_id: validator.integer("wishlist_id", &[Constraint::Min(1.0)]),
+        product_id: validator.integer("product_id", &[Constraint::Min(1.0)]),
     };
 
+    validator.finish()?;
+
     Ok(new_wish)
 }
+
+/// Undoes a soft delete, only admins
+#[handler]
+pub fn restore_wish(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.wish_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let total_restored = repo.restore(id)?;
+
+    if total_restored == 0 {
+        api_errors::render_resource_not_found(res, "deleted wish");
+    } else {
+        audit::record(req, depot, NewAuditLog::new("restore", "wish", Some(id)));
+
+        api_responses::render_db_execution(res, total_restored);
+    }
+
+    Ok(())
+}
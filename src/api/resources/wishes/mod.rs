@@ -3,7 +3,7 @@ pub mod models;
 pub mod repo;
 
 use salvo::prelude::*;
-use self::controllers::{create_wish, list_wishes, show_wish, delete_wish};
+use self::controllers::{create_wish, list_wishes, show_wish, delete_wish, reorder_wishes, restore_wish};
 use crate::api::auth;
 use auth::controllers::handle_auth;
 use super::sponsors::controllers::list_sponsors_wish;
@@ -23,8 +23,16 @@ pub fn get_router_for_wishlists() -> Router {
         .hoop(handle_auth)
         .get(list_wishes)
         .post(create_wish)
+        .push(Router::with_path("order")
+            .put(reorder_wishes)
+        )
         .push(Router::with_path("<id>")
             .get(show_wish)
             .delete(delete_wish)
         )
 }
+
+pub fn get_admin_router() -> Router {
+    Router::with_path("wishes/<id>/restore")
+        .put(restore_wish)
+}
@@ -1,8 +1,10 @@
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
 use crate::api::resources::wishlists::models::Wishlist;
 use crate::api::resources::products::models::Product;
 use crate::models::Composable;
+use crate::api::utils::formatters::{date, optional_date};
 
 #[derive(Serialize, Debug, Clone)]
 #[derive(Queryable, Selectable, Identifiable, Associations, PartialEq, AsChangeset)]
@@ -15,6 +17,30 @@ pub struct Wish {
     pub wishlist_id: i32,
     pub product_id: i32,
     pub pending: bool,
+    pub position: i32,
+    pub original_price: f32,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::wishes)]
+pub struct ListedWish {
+    pub id: i32,
+    pub wishlist_id: i32,
+    pub product_id: i32,
+    pub pending: bool,
+    pub position: i32,
+    pub original_price: f32,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
+    /// Only set on the tombstones of synced lists
+    #[serde(with = "optional_date", skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug)]
@@ -31,12 +57,23 @@ pub struct WishProduct {
     pub wishlist_id: i32,
     pub product: Product,
     pub pending: bool,
+    pub position: i32,
+    /// Price of the product when it was wished
+    pub original_price: f32,
+    /// Whether the product price is no longer the original one
+    pub price_changed: bool,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
 }
 
 impl Composable<Wish, Product> for WishProduct {
     fn compose(wish: Wish, product: Product) -> Self {
-        let Wish { id, wishlist_id, pending, .. } = wish;
+        let Wish { id, wishlist_id, pending, position, original_price, created_at, updated_at, .. } = wish;
+
+        let price_changed = product.price != original_price;
 
-        Self { id, wishlist_id, pending, product }
+        Self { id, wishlist_id, pending, position, original_price, price_changed, created_at, updated_at, product }
     }
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 use diesel::prelude::*;
-use super::models::{NewWish, Wish, WishProduct};
+use diesel::dsl::{now, IntervalDsl};
+use chrono::NaiveDateTime;
+use super::models::{ListedWish, NewWish, Wish, WishProduct};
+use crate::api::utils::sorting::{SortDirection, SortField, SyncFilter};
 use crate::api::resources::products::models::Product;
 use crate::services::database::contracts::WishRepo;
 use crate::services::database::establish_connection;
@@ -20,6 +23,9 @@ impl WishRepo for Repo {
         let wish_product_list = wishes_table
             .inner_join(schema::products::table)
             .filter(schema::wishes::wishlist_id.eq(id))
+            .filter(wishes_schema::deleted_at.is_null())
+            .filter(schema::products::deleted_at.is_null())
+            .order((wishes_schema::position.asc(), wishes_schema::id.asc()))
             .select((Wish::as_select(), Product::as_select()))
             .load::<(Wish, Product)>(conn)?;
 
@@ -34,16 +40,43 @@ impl WishRepo for Repo {
     fn insert(&self, new_wish: NewWish) -> Result<Wish, Error> {
         let conn = &mut establish_connection();
 
-        diesel::insert_into(wishes_table)
-            .values(&new_wish)
-            .returning(Wish::as_returning())
-            .get_result(conn)
+        conn.transaction(|conn| {
+            // Concurrent inserts into the same wishlist wait here, so each one sees the last position
+            schema::wishlists::table
+                .find(new_wish.wishlist_id)
+                .select(schema::wishlists::id)
+                .for_update()
+                .first::<i32>(conn)?;
+
+            let last_position: Option<i32> = wishes_table
+                .filter(wishes_schema::wishlist_id.eq(new_wish.wishlist_id))
+                .select(diesel::dsl::max(wishes_schema::position))
+                .first(conn)?;
+
+            let position = last_position.map_or(0, |p| p + 1);
+
+            let original_price: f32 = products_table
+                .find(new_wish.product_id)
+                .filter(schema::products::deleted_at.is_null())
+                .select(schema::products::price)
+                .first(conn)?;
+
+            diesel::insert_into(wishes_table)
+                .values((
+                    &new_wish,
+                    wishes_schema::position.eq(position),
+                    wishes_schema::original_price.eq(original_price),
+                ))
+                .returning(Wish::as_returning())
+                .get_result(conn)
+        })
     }
 
     fn find_one(&self, id: i32) -> Result<Wish, Error> {
         let conn = &mut establish_connection();
 
         wishes_table.find(id)
+            .filter(wishes_schema::deleted_at.is_null())
             .select(Wish::as_select())
             .get_result(conn)
     }
@@ -53,11 +86,13 @@ impl WishRepo for Repo {
 
         let wish = wishes_table
             .find(id)
+            .filter(wishes_schema::deleted_at.is_null())
             .select(Wish::as_select())
             .get_result(conn)?;
 
         let product = products_table
             .find(wish.product_id)
+            .filter(schema::products::deleted_at.is_null())
             .select(Product::as_select())
             .get_result(conn)?;
 
@@ -67,17 +102,92 @@ impl WishRepo for Repo {
     fn delete(&self, id: i32) -> Result<usize, Error> {
         let conn = &mut establish_connection();
         
-        diesel::delete(schema::wishes::table.find(id))
+        diesel::update(wishes_table.find(id).filter(wishes_schema::deleted_at.is_null()))
+            .set(wishes_schema::deleted_at.eq(now.nullable()))
+            .execute(conn)
+    }
+
+    fn restore(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(wishes_table.find(id).filter(wishes_schema::deleted_at.is_not_null()))
+            .set(wishes_schema::deleted_at.eq(None::<NaiveDateTime>))
             .execute(conn)
     }
+
+    fn purge(&self, retention_days: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let purged = wishes_table
+                .filter(wishes_schema::deleted_at.lt((now - retention_days.days()).nullable()))
+                .select(wishes_schema::id);
+
+            diesel::delete(schema::sponsors::table.filter(schema::sponsors::wish_id.eq_any(purged)))
+                .execute(conn)?;
+
+            diesel::delete(wishes_table.filter(wishes_schema::deleted_at.lt((now - retention_days.days()).nullable())))
+                .execute(conn)
+        })
+    }
+
+    fn reorder(&self, wishlist_id: i32, wish_ids: &[i32]) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let mut total = 0;
+
+            for (position, wish_id) in wish_ids.iter().enumerate() {
+                total += diesel::update(
+                    wishes_table
+                        .filter(wishes_schema::wishlist_id.eq(wishlist_id))
+                        .filter(wishes_schema::deleted_at.is_null())
+                        .find(wish_id),
+                )
+                .set(wishes_schema::position.eq(position as i32))
+                .execute(conn)?;
+            }
+
+            // Every id must belong to the wishlist, otherwise nothing is persisted
+            if total != wish_ids.len() {
+                return Err(Error::RollbackTransaction);
+            }
+
+            Ok(total)
+        })
+    }
 }
 
-pub fn list_wishes_from_wishlist(wishlist_id: i32) -> Result<Vec<Wish>, Error> {
+/// The wishes of the wishlist by position, unless the filter sorts them otherwise
+pub fn list_wishes_from_wishlist(wishlist_id: i32, filter: &SyncFilter) -> Result<Vec<ListedWish>, Error> {
     let conn = &mut db::establish_connection();
 
-    wishes_table
+    let mut query = wishes_table
         .filter(wishes_schema::wishlist_id.eq(wishlist_id))
-        .select(Wish::as_select())
+        .into_boxed();
+
+    if !filter.include_deleted {
+        query = query.filter(wishes_schema::deleted_at.is_null());
+    }
+
+    if let Some(updated_since) = filter.updated_since {
+        query = query.filter(wishes_schema::updated_at.ge(updated_since));
+    }
+
+    query = match (filter.sort_by, filter.direction) {
+        (None, SortDirection::Asc) => query.order(wishes_schema::position.asc()),
+        (None, SortDirection::Desc) => query.order(wishes_schema::position.desc()),
+        (Some(SortField::Id), SortDirection::Asc) => query.order(wishes_schema::id.asc()),
+        (Some(SortField::Id), SortDirection::Desc) => query.order(wishes_schema::id.desc()),
+        (Some(SortField::CreatedAt), SortDirection::Asc) => query.order(wishes_schema::created_at.asc()),
+        (Some(SortField::CreatedAt), SortDirection::Desc) => query.order(wishes_schema::created_at.desc()),
+        (Some(SortField::UpdatedAt), SortDirection::Asc) => query.order(wishes_schema::updated_at.asc()),
+        (Some(SortField::UpdatedAt), SortDirection::Desc) => query.order(wishes_schema::updated_at.desc()),
+    };
+
+    query
+        .then_order_by(wishes_schema::id.asc())
+        .select(ListedWish::as_select())
         .load(conn)
 }
 
@@ -87,6 +197,9 @@ pub fn list_detailed_wishes(id: i32) -> Result<Vec<WishProduct>, Error> {
     let wish_product_list = schema::wishes::table
         .inner_join(schema::products::table)
         .filter(schema::wishes::wishlist_id.eq(id))
+        .filter(wishes_schema::deleted_at.is_null())
+        .filter(schema::products::deleted_at.is_null())
+        .order((wishes_schema::position.asc(), wishes_schema::id.asc()))
         .select((Wish::as_select(), Product::as_select()))
         .load::<(Wish, Product)>(conn)?;
 
@@ -1,19 +1,27 @@
 use salvo::prelude::*;
-use salvo::http::form::FormData;
+use serde_json::json;
 use crate::api::errors::{ApiError, ApiResult};
+use crate::api::resources::audit::{self, models::NewAuditLog};
 use crate::api::resources::wishlists::models::DetailedWishlist;
 use crate::api::utils::{get_db, get_user_id};
 use crate::api::utils::pagination::Pagination;
-use crate::api::validations::{FormValidator, Validator};
+use crate::api::utils::sorting::SyncFilter;
+use crate::api::validations::{BodyValidator, FieldValidator, Validator};
 use crate::api::{errors as api_errors, responses as api_responses, utils};
 use crate::models::{Composable, Mergeable};
-use super::models::NewWishlist;
+use crate::api::policy::{authorize_wishlist, Action, Subject};
+use super::models::{NewWishlist, Visibility, DESCRIPTION_CONSTRAINTS, TITLE_CONSTRAINTS};
 use super::repo;
 
 #[handler]
-pub fn list_wishlists(req: &mut Request, _depot: &mut Depot, res: &mut Response) {
+pub fn list_wishlists(req: &mut Request, depot: &mut Depot, res: &mut Response) -> ApiResult<()> {
+    let viewer_id = utils::get_user_id(depot).unwrap_or_default();
+
+    // Other people's wishlists, their deletions are for admins only
+    let filter = SyncFilter::from_query(req)?.with_deleted_for(Subject::from_depot(depot).admin);
+
     match req.query::<i64>("per_page") {
-        None => match repo::list_wishlists() {
+        None => match repo::list_wishlists(viewer_id, &filter) {
             Err(_) => api_errors::render_resource_not_found(res, "wishlist"),
 
             Ok(wishlists) => api_responses::render_collection(res, wishlists),
@@ -21,7 +29,7 @@ pub fn list_wishlists(req: &mut Request, _depot: &mut Depot, res: &mut Response)
         Some(per_page) => {
             let page = req.query::<i64>("page").unwrap_or(1);
 
-            match repo::list_wishlists_paginate(page, per_page) {
+            match repo::list_wishlists_paginate(viewer_id, &filter, page, per_page) {
                 Err(error) => api_errors::render_db_retrieving_error(res, error, "wishlists"),
 
                 Ok((entries, wishlists)) =>
@@ -29,19 +37,26 @@ pub fn list_wishlists(req: &mut Request, _depot: &mut Depot, res: &mut Response)
             }
         }
     }
+
+    Ok(())
 }
 
 #[handler]
-pub fn list_user_wishlists(_req: &mut Request, depot: &mut Depot, res: &mut Response) {
+pub fn list_user_wishlists(req: &mut Request, depot: &mut Depot, res: &mut Response) -> ApiResult<()> {
+    // Only the wishlists the user owns are listed, so their deletions can be synced
+    let filter = SyncFilter::from_query(req)?;
+
     match utils::get_user_id(depot) {
         None => api_errors::render_get_user_id_not_found(res),
 
-        Some(user_id) => match repo::list_user_wishlists(user_id) {
+        Some(user_id) => match repo::list_user_wishlists(user_id, &filter) {
             Err(error) => api_errors::render_db_retrieving_error(res, error, "wishlists"),
 
             Ok(wishlists) => api_responses::render_collection(res, wishlists),
         },
     }
+
+    Ok(())
 }
 
 #[handler]
@@ -52,6 +67,8 @@ pub fn show_wishlist(req: &Request, depot: &Depot, res: &mut Response) -> ApiRes
 
     let wishlist = repo.find_one(id)?;
 
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::View, &wishlist)?;
+
     match req.query::<String>("detailed") {
         None => {
             api_responses::render_resource(res, wishlist);
@@ -76,14 +93,16 @@ pub fn show_wishlist(req: &Request, depot: &Depot, res: &mut Response) -> ApiRes
 pub async fn create_wishlist(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
     let repo = get_db(depot)?.wishlist_repo();
 
-    let form_data = req.form_data().await?;
+    let body = BodyValidator::from_request(req).await?;
 
     let user_id = get_user_id(depot).unwrap_or_default();
 
-    let new_wishlist = cast_form_data_to_new_wishlist(form_data, user_id)?;
+    let new_wishlist = cast_form_data_to_new_wishlist(&body, user_id)?;
 
     let wishlist = repo.insert(new_wishlist)?;
 
+    audit::record(req, depot, NewAuditLog::new("create", "wishlist", Some(wishlist.id)).changes(None, Some(&wishlist)));
+
     api_responses::render_resource_created(res, wishlist);
 
     Ok(())
@@ -91,24 +110,23 @@ pub async fn create_wishlist(req: &mut Request, depot: &Depot, res: &mut Respons
  
 #[handler]
 pub async fn update_wishlist(req: &mut Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
-    let repo = get_db(depot)?.wishlist_repo();
-
-    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound(format!("id")))?;
+    let db = get_db(depot)?;
+    let repo = db.wishlist_repo();
 
-    let form_data = req.form_data().await?;
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
 
-    let user_id = utils::get_user_id(depot).ok_or(ApiError::FieldNotFound(format!("user_id")))?;
+    let body = BodyValidator::from_request(req).await?;
 
     let wishlist = repo.find_one(id)?;
 
-    if wishlist.user_id != user_id {
-        return Err(ApiError::NotAllowed(format!("Wishlist doesn't belong to the user")));
-    }
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Edit, &wishlist)?;
 
-    let updatable_wishlist = wishlist.merge(form_data)?;
+    let updatable_wishlist = wishlist.clone().merge(&body)?;
 
     let updated_wishlist = repo.update(&updatable_wishlist)?;
 
+    audit::record(req, depot, NewAuditLog::new("update", "wishlist", Some(id)).changes(Some(&wishlist), Some(&updated_wishlist)));
+
     api_responses::render_resource_updated(res, updated_wishlist);
 
     Ok(())
@@ -116,39 +134,96 @@ pub async fn update_wishlist(req: &mut Request, depot: &Depot, res: &mut Respons
 
 #[handler]
 pub fn delete_wishlist(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
-    let repo = get_db(depot)?.wishlist_repo();
+    let db = get_db(depot)?;
+    let repo = db.wishlist_repo();
 
     let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
 
-    let user_id =  utils::get_user_id(depot).ok_or(ApiError::FieldNotFound("user_id".to_string()))?;
-
     let wishlist = repo.find_one(id)?;
 
-    if wishlist.user_id != user_id && !utils::admin(depot) {
-        return Err(ApiError::NotAllowed(format!("Wishlist doesn't belong to the user")));
-    }
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::Manage, &wishlist)?;
 
+    // The cover is kept along with the wishlist until it's purged
     let total = repo.delete(id)?;
 
     if total == 0 {
-        return Err(ApiError::FieldNotFound(format!("Nothing was deleted")));
+        return Err(ApiError::FieldNotFound("Nothing was deleted".to_string()));
     }
 
+    audit::record(req, depot, NewAuditLog::new("delete", "wishlist", Some(id)).changes(Some(&wishlist), None));
+
     api_responses::render_db_execution(res, total);
 
     Ok(())
 }
 
-fn cast_form_data_to_new_wishlist(form_data: &FormData, user_id: i32) -> ApiResult<NewWishlist> {
-    let validator = FormValidator(form_data);
+#[handler]
+pub fn clone_wishlist(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let db = get_db(depot)?;
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let user_id = utils::get_user_id(depot).ok_or(ApiError::FieldNotFound("user_id".to_string()))?;
+
+    let wishlist = db.wishlist_repo().find_one(id)?;
+
+    authorize_wishlist(db, &Subject::from_depot(depot), Action::View, &wishlist)?;
+
+    let product_ids = db.wish_repo()
+        .list_by_wishlist(id)?
+        .into_iter()
+        .map(|wish| wish.product.id)
+        .collect();
+
+    let new_wishlist = NewWishlist {
+        title: wishlist.title,
+        description: wishlist.description,
+        date: None,
+        user_id,
+        visibility: Visibility::Private,
+    };
+
+    let cloned_wishlist = db.wishlist_repo().insert_with_products(new_wishlist, product_ids)?;
+
+    audit::record(req, depot, NewAuditLog::new("clone", "wishlist", Some(cloned_wishlist.id)).details(json!({ "source_id": id })));
+
+    api_responses::render_resource_created(res, cloned_wishlist);
+
+    Ok(())
+}
+
+fn cast_form_data_to_new_wishlist(validator: &dyn Validator, user_id: i32) -> ApiResult<NewWishlist> {
+    let mut validator = FieldValidator::new(validator);
 
     let new_wishlist = NewWishlist {
-        title: validator.string("title")?,
-        description: validator.optional_string("description")?,
-        date: validator.optional_date("date")?,
+        title: validator.string("title", TITLE_CONSTRAINTS),
+        description: validator.optional_string("description", DESCRIPTION_CONSTRAINTS),
+        date: validator.optional_date("date"),
         user_id,
-        published: false,
+        visibility: validator.parsed("visibility", false).unwrap_or(Visibility::Private),
     };
 
+    validator.finish()?;
+
     Ok(new_wishlist)
 }
+
+/// Undoes a soft delete, only admins
+#[handler]
+pub fn restore_wishlist(req: &Request, depot: &Depot, res: &mut Response) -> ApiResult<()> {
+    let repo = get_db(depot)?.wishlist_repo();
+
+    let id = req.param::<i32>("id").ok_or(ApiError::FieldNotFound("id".to_string()))?;
+
+    let total_restored = repo.restore(id)?;
+
+    if total_restored == 0 {
+        api_errors::render_resource_not_found(res, "deleted wishlist");
+    } else {
+        audit::record(req, depot, NewAuditLog::new("restore", "wishlist", Some(id)));
+
+        api_responses::render_db_execution(res, total_restored);
+    }
+
+    Ok(())
+}
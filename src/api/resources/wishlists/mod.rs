@@ -8,8 +8,10 @@ use auth::controllers::handle_auth;
 use self::controllers::{
     list_user_wishlists, 
     list_wishlists,
+    clone_wishlist,
     create_wishlist,
     delete_wishlist,
+    restore_wishlist,
     show_wishlist, 
     update_wishlist, 
 };
@@ -26,5 +28,13 @@ pub fn get_router() -> Router {
             .get(show_wishlist)
             .delete(delete_wishlist)
             .put(update_wishlist)
+            .push(Router::with_path("clone")
+                .post(clone_wishlist)
+            )
         )
+}
+
+pub fn get_admin_router() -> Router {
+    Router::with_path("wishlists/<id>/restore")
+        .put(restore_wishlist)
 }
\ No newline at end of file
@@ -1,14 +1,87 @@
+use std::str::FromStr;
 use diesel::prelude::*;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Varchar;
 use serde::{Deserialize, Serialize};
-use salvo::http::form::FormData;
 use chrono::NaiveDateTime;
 use crate::api::errors::{ApiError, ApiResult};
-use crate::api::validations::{FormValidator, Validator};
+use crate::api::validations::{Constraint, FieldValidator, Validator};
 use crate::schema::wishlists;
 use crate::api::resources::wishes::models::WishProduct;
 use crate::api::resources::users::models::User;
 use crate::models::{Composable, Mergeable};
-use crate::api::utils::formatters::optional_date;
+use crate::api::utils::formatters::{date, optional_date};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(AsExpression, FromSqlRow)]
+#[diesel(sql_type = Varchar)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Only owners and collaborators
+    Private,
+    /// Owners, collaborators and anyone holding a share link
+    LinkOnly,
+    /// Owners, collaborators and active followers
+    FollowersOnly,
+    /// Everyone, listed in `GET /wishlists`
+    Public,
+}
+
+impl Visibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Private => "PRIVATE",
+            Visibility::LinkOnly => "LINK_ONLY",
+            Visibility::FollowersOnly => "FOLLOWERS_ONLY",
+            Visibility::Public => "PUBLIC",
+        }
+    }
+
+    /// Share links only open link-only and public wishlists
+    pub fn shareable(&self) -> bool {
+        matches!(self, Visibility::LinkOnly | Visibility::Public)
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_uppercase().replace('-', "_").as_str() {
+            "PRIVATE" => Ok(Visibility::Private),
+            "LINK_ONLY" => Ok(Visibility::LinkOnly),
+            "FOLLOWERS_ONLY" => Ok(Visibility::FollowersOnly),
+            "PUBLIC" => Ok(Visibility::Public),
+            other => Err(format!("`{other}` is not a valid visibility")),
+        }
+    }
+}
+
+impl From<bool> for Visibility {
+    fn from(published: bool) -> Self {
+        match published {
+            true => Visibility::Public,
+            false => Visibility::Private,
+        }
+    }
+}
+
+impl ToSql<Varchar, Pg> for Visibility {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <str as ToSql<Varchar, Pg>>::to_sql(self.as_str(), out)
+    }
+}
+
+impl FromSql<Varchar, Pg> for Visibility {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let value = <String as FromSql<Varchar, Pg>>::from_sql(bytes)?;
+
+        Visibility::from_str(&value).map_err(|error| error.into())
+    }
+}
 
 #[derive(
     Serialize,
@@ -24,6 +97,7 @@ use crate::api::utils::formatters::optional_date;
 )]
 #[diesel(belongs_to(User))]
 #[diesel(table_name = crate::schema::wishlists)]
+#[diesel(treat_none_as_null = true)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Wishlist {
     pub id: i32,
@@ -32,7 +106,11 @@ pub struct Wishlist {
     #[serde(with = "optional_date")]
     pub date: Option<NaiveDateTime>,
     pub user_id: i32,
-    pub published: bool,
+    pub visibility: Visibility,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
 }
 
 #[derive(Debug, Insertable, Deserialize)]
@@ -44,37 +122,41 @@ pub struct NewWishlist {
     #[serde(with = "optional_date")]
     pub date: Option<NaiveDateTime>,
     pub user_id: i32,
-    pub published: bool,
+    pub visibility: Visibility,
 }
 
+/// Checks shared by new wishlists and partial updates
+pub const TITLE_CONSTRAINTS: &[Constraint] = &[Constraint::MinLength(1), Constraint::MaxLength(120)];
+pub const DESCRIPTION_CONSTRAINTS: &[Constraint] = &[Constraint::MaxLength(2000)];
+
 impl Mergeable for Wishlist {
-    fn merge(self, form_data: &FormData) -> ApiResult<Self> {
-        let validator = FormValidator(&form_data);
+    fn merge(self, validator: &dyn Validator) -> ApiResult<Self> {
+        if validator.contains("user_id") {
+            return Err(ApiError::NotAllowed("Changing `user_id` not allowed".to_string()));
+        }
+
+        let mut validator = FieldValidator::new(validator);
         let mut updated = self.clone();
 
-        if let Some(title) = validator.get("title") {
-            if title.is_empty() {
-                return Err(ApiError::BadRequestError(format!("`title` cannot be empty")));
-            }
-            
-            updated.title = validator.string("title")?;
+        if validator.contains("title") {
+            updated.title = validator.string("title", TITLE_CONSTRAINTS);
         }
 
-        if let Some(_) = validator.get("description") {
-            updated.description = validator.optional_string("description")?;
+        if validator.contains("description") {
+            updated.description = validator.optional_string("description", DESCRIPTION_CONSTRAINTS);
         }
 
-        if let Some(_) = validator.get("date") {
-            updated.date = validator.optional_date("date")?;
+        if validator.contains("date") {
+            updated.date = validator.optional_date("date");
         }
 
-        if let Some(_) = validator.get("user_id") {
-            return Err(ApiError::NotAllowed(format!("Changing `user_id` not allowed")));
+        if validator.contains("visibility") {
+            updated.visibility = validator.parsed("visibility", true).unwrap_or(self.visibility);
+        } else if validator.contains("published") {
+            updated.visibility = Visibility::from(validator.boolean("published"));
         }
 
-        if let Some(_) = validator.get("published") {
-            updated.published = validator.boolean("published")?;
-        }
+        validator.finish()?;
 
         Ok(updated)
     }
@@ -87,14 +169,13 @@ pub struct ListedWishlist {
     pub title: String,
     #[serde(with = "optional_date")]
     pub date: Option<NaiveDateTime>,
-}
-
-impl From<Wishlist> for ListedWishlist {
-    fn from(wishlist: Wishlist) -> Self {
-        let Wishlist { id, title, date, .. } = wishlist;
-
-        Self { id, title, date }
-    }
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
+    /// Only set on the tombstones of synced lists
+    #[serde(with = "optional_date", skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 #[derive(Serialize)]
@@ -105,14 +186,18 @@ pub struct DetailedWishlist {
     #[serde(with = "optional_date")]
     pub date: Option<NaiveDateTime>,
     pub user_id: i32,
-    pub published: bool,
+    pub visibility: Visibility,
+    #[serde(with = "date")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "date")]
+    pub updated_at: NaiveDateTime,
     pub wishes: Vec<WishProduct>,
 }
 
 impl Composable<Wishlist, Vec<WishProduct>> for DetailedWishlist {
     fn compose(wishlist: Wishlist, wishes: Vec<WishProduct>) -> Self {
-        let Wishlist { id, title, description, date,user_id, published } = wishlist;
+        let Wishlist { id, title, description, date, user_id, visibility, created_at, updated_at } = wishlist;
 
-        Self { id, title, description, date, user_id, published, wishes }
+        Self { id, title, description, date, user_id, visibility, created_at, updated_at, wishes }
     }
 }
@@ -1,13 +1,17 @@
 use diesel::prelude::*;
+use diesel::dsl::{now, IntervalDsl};
+use chrono::NaiveDateTime;
 use crate::api::utils::pagination::Paginate;
 use crate::services::database::{contracts::WishlistRepo, establish_connection};
 use crate::db;
 use crate::schema::wishlists::table as wishlists_table;
 use super::models::DetailedWishlist;
-use super::models::{ListedWishlist, NewWishlist, Wishlist};
+use super::models::{ListedWishlist, NewWishlist, Visibility, Wishlist};
+use crate::api::utils::sorting::{SortDirection, SortField, SyncFilter};
 use diesel::result::Error;
 use crate::schema::wishlists as wishlist_schema;
 use crate::models::Composable;
+use crate::api::resources::images::models::Image;
 
 pub struct Repo;
 
@@ -17,6 +21,7 @@ impl WishlistRepo for Repo {
 
         wishlists_table
         .find(id)
+        .filter(wishlist_schema::deleted_at.is_null())
         .select(Wishlist::as_select())
         .first(conn)
     }
@@ -38,20 +43,98 @@ impl WishlistRepo for Repo {
             .execute(conn)
     }
 
+    fn insert_with_products(&self, new_wishlist: NewWishlist, product_ids: Vec<i32>) -> Result<Wishlist, Error> {
+        use std::collections::HashMap;
+        use crate::schema::{products, wishes};
+
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let wishlist = diesel::insert_into(wishlists_table)
+                .values(&new_wishlist)
+                .returning(Wishlist::as_returning())
+                .get_result(conn)?;
+
+            let prices: HashMap<i32, f32> = products::table
+                .filter(products::id.eq_any(&product_ids))
+                .select((products::id, products::price))
+                .load(conn)?
+                .into_iter()
+                .collect();
+
+            let wishes = product_ids.into_iter()
+                .enumerate()
+                .map(|(position, product_id)| Ok((
+                    wishes::wishlist_id.eq(wishlist.id),
+                    wishes::product_id.eq(product_id),
+                    wishes::position.eq(position as i32),
+                    wishes::original_price.eq(prices.get(&product_id).copied().ok_or(Error::NotFound)?),
+                )))
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            diesel::insert_into(wishes::table)
+                .values(wishes)
+                .execute(conn)?;
+
+            Ok(wishlist)
+        })
+    }
+
     fn update(&self, wishlist: &Wishlist) -> Result<Wishlist, Error> {
         let conn = &mut establish_connection();
 
-        diesel::update(wishlists_table.find(wishlist.id))
+        diesel::update(wishlists_table.find(wishlist.id).filter(wishlist_schema::deleted_at.is_null()))
             .set(wishlist)
+            .returning(Wishlist::as_returning())
             .get_result(conn)
     }
 
     fn delete(&self, id: i32) -> Result<usize, Error> {
         let conn = &mut establish_connection();
 
-        diesel::delete(wishlists_table.find(id))
+        diesel::update(wishlists_table.find(id).filter(wishlist_schema::deleted_at.is_null()))
+            .set(wishlist_schema::deleted_at.eq(now.nullable()))
             .execute(conn)
     }
+
+    fn restore(&self, id: i32) -> Result<usize, Error> {
+        let conn = &mut establish_connection();
+
+        diesel::update(wishlists_table.find(id).filter(wishlist_schema::deleted_at.is_not_null()))
+            .set(wishlist_schema::deleted_at.eq(None::<NaiveDateTime>))
+            .execute(conn)
+    }
+
+    fn purge(&self, retention_days: i32) -> Result<(usize, Vec<Image>), Error> {
+        use crate::schema::{images, sponsors, wishes};
+
+        let conn = &mut establish_connection();
+
+        conn.transaction(|conn| {
+            let ids: Vec<i32> = wishlists_table
+                .filter(wishlist_schema::deleted_at.lt((now - retention_days.days()).nullable()))
+                .select(wishlist_schema::id)
+                .load(conn)?;
+
+            // Wishes go away with their wishlist, sponsors have to be removed first
+            let wish_ids = wishes::table
+                .filter(wishes::wishlist_id.eq_any(&ids))
+                .select(wishes::id);
+
+            diesel::delete(sponsors::table.filter(sponsors::wish_id.eq_any(wish_ids)))
+                .execute(conn)?;
+
+            let purged_images = images::table
+                .filter(images::wishlist_id.eq_any(&ids))
+                .select(Image::as_select())
+                .load(conn)?;
+
+            let total = diesel::delete(wishlists_table.filter(wishlist_schema::id.eq_any(&ids)))
+                .execute(conn)?;
+
+            Ok((total, purged_images))
+        })
+    }
 }
 
 pub fn find_wishlist(id: i32, user_id: i32) -> Result<Wishlist, Error> {
@@ -59,6 +142,7 @@ pub fn find_wishlist(id: i32, user_id: i32) -> Result<Wishlist, Error> {
 
     wishlists_table
         .filter(wishlist_schema::user_id.eq(user_id))
+        .filter(wishlist_schema::deleted_at.is_null())
         .find(id)
         .select(Wishlist::as_select())
         .first(conn)
@@ -74,20 +158,64 @@ pub fn find_detailed_wishlist(id: i32, user_id: i32) -> Result<DetailedWishlist,
     Ok(DetailedWishlist::compose(wishlist, wishes))
 }
 
-pub fn list_wishlists() -> Result<Vec<ListedWishlist>, Error> {
-    let conn = &mut db::establish_connection();
+/// Public wishlists plus the followers-only ones the viewer actively follows
+fn visible_wishlists(viewer_id: i32) -> wishlist_schema::BoxedQuery<'static, diesel::pg::Pg> {
+    use crate::schema::followers;
+
+    let followed = followers::table
+        .filter(followers::user_id.eq(viewer_id))
+        .filter(followers::active.eq(true))
+        .select(followers::wishlist_id);
 
     wishlists_table
-        .filter(wishlist_schema::published.eq(true))
+        .filter(
+            wishlist_schema::visibility.eq(Visibility::Public).or(
+                wishlist_schema::visibility.eq(Visibility::FollowersOnly)
+                    .and(wishlist_schema::id.eq_any(followed)),
+            ),
+        )
+        .into_boxed()
+}
+
+/// Narrows and sorts the wishlists as the filter requests
+fn filtered<'a>(
+    mut query: wishlist_schema::BoxedQuery<'a, diesel::pg::Pg>,
+    filter: &SyncFilter,
+) -> wishlist_schema::BoxedQuery<'a, diesel::pg::Pg> {
+    if !filter.include_deleted {
+        query = query.filter(wishlist_schema::deleted_at.is_null());
+    }
+
+    if let Some(updated_since) = filter.updated_since {
+        query = query.filter(wishlist_schema::updated_at.ge(updated_since));
+    }
+
+    query = match (filter.sort_by.unwrap_or_default(), filter.direction) {
+        (SortField::Id, SortDirection::Asc) => query.order(wishlist_schema::id.asc()),
+        (SortField::Id, SortDirection::Desc) => query.order(wishlist_schema::id.desc()),
+        (SortField::CreatedAt, SortDirection::Asc) => query.order(wishlist_schema::created_at.asc()),
+        (SortField::CreatedAt, SortDirection::Desc) => query.order(wishlist_schema::created_at.desc()),
+        (SortField::UpdatedAt, SortDirection::Asc) => query.order(wishlist_schema::updated_at.asc()),
+        (SortField::UpdatedAt, SortDirection::Desc) => query.order(wishlist_schema::updated_at.desc()),
+    };
+
+    // Keeps pages stable when the sorted values repeat
+    query.then_order_by(wishlist_schema::id.asc())
+}
+
+pub fn list_wishlists(viewer_id: i32, filter: &SyncFilter) -> Result<Vec<ListedWishlist>, Error> {
+    let conn = &mut db::establish_connection();
+
+    filtered(visible_wishlists(viewer_id), filter)
         .select(ListedWishlist::as_select())
         .load(conn)
 }
 
-pub fn list_wishlists_paginate(page: i64, per_page: i64) -> Result<(i64, Vec<ListedWishlist>), Error> {
+pub fn list_wishlists_paginate(viewer_id: i32, filter: &SyncFilter, page: i64, per_page: i64) -> Result<(i64, Vec<ListedWishlist>), Error> {
     let conn = &mut db::establish_connection();
 
-    let results: Vec<(Wishlist, i64)> = wishlists_table
-        .filter(wishlist_schema::published.eq(true))
+    let results: Vec<(ListedWishlist, i64)> = filtered(visible_wishlists(viewer_id), filter)
+        .select(ListedWishlist::as_select())
         .paginate(page)
         .per_page(per_page)
         .get_results(conn)?;
@@ -97,17 +225,20 @@ pub fn list_wishlists_paginate(page: i64, per_page: i64) -> Result<(i64, Vec<Lis
             Some((_, entries)) => Ok((
                 *entries,
                 results.into_iter()
-                    .map(|(w, _)| ListedWishlist::from(w))
+                    .map(|(w, _)| w)
                     .collect())
                 )
     }
 }
 
-pub fn list_user_wishlists(user_id: i32) -> Result<Vec<ListedWishlist>, Error> {
+pub fn list_user_wishlists(user_id: i32, filter: &SyncFilter) -> Result<Vec<ListedWishlist>, Error> {
     let conn = &mut db::establish_connection();
 
-    wishlists_table
+    let owned = wishlists_table
         .filter(wishlist_schema::user_id.eq(user_id))
+        .into_boxed();
+
+    filtered(owned, filter)
         .select(ListedWishlist::as_select())
         .load(conn)
 }
@@ -115,10 +246,12 @@ pub fn list_user_wishlists(user_id: i32) -> Result<Vec<ListedWishlist>, Error> {
 pub fn delete_wishlist(id: i32, user_id: i32) -> Result<usize, Error> {
     let conn = &mut db::establish_connection();
 
-    diesel::delete(
+    diesel::update(
         wishlists_table
             .filter(wishlist_schema::user_id.eq(user_id))
+            .filter(wishlist_schema::deleted_at.is_null())
             .find(id),
     )
+    .set(wishlist_schema::deleted_at.eq(now.nullable()))
     .execute(conn)
 }
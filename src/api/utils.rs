@@ -1,10 +1,12 @@
-use std::{env, error::Error, sync::Arc};
+use std::{env, error::Error, net::IpAddr, sync::{Arc, RwLock}};
 use salvo::prelude::*;
+use futures_util::StreamExt;
 use serde::de::DeserializeOwned;
 use sha2::{Sha256, Digest};
 use crate::{api::errors::{ApiResult, ApiError}, models::Role};
 use crate::services::notifications::contracts::Notifier;
 use crate::services::database::contracts::DatabaseService;
+use crate::services::storage::contracts::Storage;
 use super::auth::JwtBearerClaims;
 
 pub fn get_user_id(depot: &Depot) -> Option<i32> {
@@ -12,6 +14,62 @@ pub fn get_user_id(depot: &Depot) -> Option<i32> {
         .map(|data| data.claims.id)
 }
 
+static TRUSTED_PROXIES: RwLock<Vec<IpAddr>> = RwLock::new(Vec::new());
+
+/// Only requests coming from these proxies can tell the client address with `X-Forwarded-For`,
+/// set from the `TRUSTED_PROXIES` config
+pub fn trust_proxies(proxies: Vec<IpAddr>) {
+    *TRUSTED_PROXIES.write().expect("Locking trusted proxies failed") = proxies;
+}
+
+/// The peer address, or the one a trusted proxy forwarded
+pub fn client_ip(req: &Request) -> Option<String> {
+    let remote_addr = req.remote_addr();
+
+    let peer = remote_addr.as_ipv4().map(|addr| IpAddr::V4(*addr.ip()))
+        .or_else(|| remote_addr.as_ipv6().map(|addr| IpAddr::V6(*addr.ip())))?;
+
+    let trusted = TRUSTED_PROXIES.read().expect("Locking trusted proxies failed");
+
+    let forwarded = req.header::<String>("x-forwarded-for");
+
+    Some(forwarded_client(peer, forwarded.as_deref(), &trusted).to_string())
+}
+
+/// Walks `X-Forwarded-For` back from the peer while the hops are trusted proxies,
+/// the entries before the first untrusted one could have been written by anyone
+fn forwarded_client(peer: IpAddr, forwarded: Option<&str>, trusted: &[IpAddr]) -> IpAddr {
+    let mut client = peer;
+
+    let hops = forwarded.unwrap_or_default()
+        .rsplit(',')
+        .map(|address| address.trim().parse::<IpAddr>());
+
+    for hop in hops {
+        match hop {
+            Ok(address) if trusted.contains(&client) => client = address,
+            _ => break,
+        }
+    }
+
+    client
+}
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Tags the response with the caller's `X-Request-Id`, or a new one, so errors can be traced back
+#[handler]
+pub fn assign_request_id(req: &Request, res: &mut Response) {
+    let request_id = req.header::<String>(REQUEST_ID_HEADER)
+        .filter(|id| !id.is_empty() && id.len() <= 64)
+        .filter(|id| id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or_else(|| random_token(16));
+
+    if let Err(error) = res.add_header(REQUEST_ID_HEADER, request_id, true) {
+        tracing::error!("Setting the request id failed: {error}");
+    }
+}
+
 pub fn admin(depot: &Depot) -> bool {
     match depot.jwt_auth_data::<JwtBearerClaims>() {
         None => false,
@@ -25,7 +83,7 @@ pub mod pagination {
     use diesel::prelude::*;
     use serde::{Serialize, Deserialize};
 
-    const DEFAULT_PER_PAGE: i64 = 10;
+    pub const DEFAULT_PER_PAGE: i64 = 10;
 
     #[derive(Serialize, Deserialize)]
     pub struct Pagination {
@@ -101,6 +159,99 @@ pub mod pagination {
     }
 }
 
+pub mod sorting {
+    use std::str::FromStr;
+    use chrono::NaiveDateTime;
+    use salvo::Request;
+    use crate::api::errors::{ApiError, ApiResult};
+    use crate::api::validations::{QueryValidator, Validator};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+    pub enum SortDirection {
+        #[default]
+        Asc,
+        Desc,
+    }
+
+    impl FromStr for SortDirection {
+        type Err = String;
+
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            match value.to_lowercase().as_str() {
+                "asc" => Ok(SortDirection::Asc),
+                "desc" => Ok(SortDirection::Desc),
+                other => Err(format!("`{other}` is not a valid sort direction, use `asc` or `desc`")),
+            }
+        }
+    }
+
+    /// Columns every synced resource can be sorted by
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+    pub enum SortField {
+        #[default]
+        Id,
+        CreatedAt,
+        UpdatedAt,
+    }
+
+    impl FromStr for SortField {
+        type Err = String;
+
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            match value.to_lowercase().as_str() {
+                "id" => Ok(SortField::Id),
+                "created_at" => Ok(SortField::CreatedAt),
+                "updated_at" => Ok(SortField::UpdatedAt),
+                other => Err(format!("`{other}` is not a sortable field, use `id`, `created_at` or `updated_at`")),
+            }
+        }
+    }
+
+    /// Criteria for incremental syncs, by default every live entry in the natural order of its list
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct SyncFilter {
+        /// Entries created or modified from then on
+        pub updated_since: Option<NaiveDateTime>,
+        /// Adds the soft deleted entries, with their `deleted_at`, so deletions can be synced too
+        pub include_deleted: bool,
+        pub sort_by: Option<SortField>,
+        pub direction: SortDirection,
+    }
+
+    impl SyncFilter {
+        /// Reads `updated_since`, `include_deleted`, `sort` and `order` from the query
+        pub fn from_query(req: &Request) -> ApiResult<Self> {
+            let validator = QueryValidator(req);
+
+            let sort_by = match validator.optional_string("sort")? {
+                None => None,
+                Some(field) => Some(SortField::from_str(&field).map_err(ApiError::BadRequestError)?),
+            };
+
+            let direction = match validator.optional_string("order")? {
+                None => SortDirection::default(),
+                Some(direction) => SortDirection::from_str(&direction).map_err(ApiError::BadRequestError)?,
+            };
+
+            let filter = SyncFilter {
+                updated_since: validator.optional_date("updated_since")?,
+                include_deleted: validator.optional_boolean("include_deleted")?.unwrap_or_default(),
+                sort_by,
+                direction,
+            };
+
+            Ok(filter)
+        }
+
+        /// Drops the soft deleted entries unless the caller may see them
+        pub fn with_deleted_for(mut self, allowed: bool) -> Self {
+            self.include_deleted &= allowed;
+
+            self
+        }
+    }
+}
+
 pub fn compare_passwords(pwd: &[u8], candidate: &str) -> bool {
     let mut hasher = Sha256::new();
 
@@ -121,6 +272,24 @@ pub fn hash_password(pwd: &str) -> Vec<u8> {
     Vec::from(&result[..])
 }
 
+pub fn random_token(length: usize) -> String {
+    use rand::{distributions::Alphanumeric, Rng};
+
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub fn parse_csv<F: Into<T> + DeserializeOwned, T>(filename: &str) -> Result<Vec<T>, Box<dyn Error>> {
     let current_dir = env::current_dir()?;
     let mut rdr = csv::Reader::from_path(current_dir.join(filename))?;
@@ -153,7 +322,73 @@ pub fn get_notifier(depot: &Depot) -> ApiResult<&Arc<dyn Notifier>> {
     Ok(service)
 }
 
+pub fn get_storage(depot: &Depot) -> ApiResult<&Arc<dyn Storage>> {
+    use crate::api::errors::InjectionError;
+
+    let service = depot.obtain::<Arc<dyn Storage>>()
+        .map_err(|_| ApiError::Injection(InjectionError))?;
+
+    Ok(service)
+}
+
+/// A file sent in a multipart form
+pub struct UploadedFile {
+    pub content_type: Option<String>,
+    pub content: Vec<u8>,
+}
+
+/// Reads the file field straight from the multipart body, up to `max_size` bytes.
+/// The form parser keeps files in temporary files it doesn't flush, so they are skipped.
+pub async fn read_file_field(req: &mut Request, key: &str, max_size: u64) -> ApiResult<UploadedFile> {
+    let boundary = req.header::<String>("content-type")
+        .and_then(|content_type| multer::parse_boundary(content_type).ok())
+        .ok_or(ApiError::BadRequestError("The body must be a multipart form".to_string()))?;
+
+    let body = req.take_body().map(|frame| frame.map(|frame| frame.into_data().unwrap_or_default()));
+
+    let mut multipart = multer::Multipart::new(body, boundary);
+
+    let invalid_body = |error: multer::Error| ApiError::BadRequestError(format!("Error parsing the request body: {error}"));
+
+    while let Some(mut field) = multipart.next_field().await.map_err(invalid_body)? {
+        if field.name() != Some(key) {
+            continue;
+        }
+
+        let content_type = field.content_type().map(|mime| mime.essence_str().to_string());
+        let mut content = vec![];
+
+        while let Some(chunk) = field.chunk().await.map_err(invalid_body)? {
+            if (content.len() + chunk.len()) as u64 > max_size {
+                return Err(ApiError::PayloadTooLarge(format!("`{key}` can't be larger than {max_size} bytes")));
+            }
+
+            content.extend_from_slice(&chunk);
+        }
+
+        return Ok(UploadedFile { content_type, content });
+    }
+
+    Err(ApiError::FieldNotFound(key.to_string()))
+}
+
 pub mod formatters {
+    pub mod date {
+        use chrono::NaiveDateTime;
+        use serde::{self, Deserialize, Serializer, Deserializer};
+        use super::optional_date::FORMAT;
+
+        pub fn serialize<S: Serializer>(date: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&date.format(FORMAT).to_string())
+        }
+
+        pub fn deserialize<'a, D: Deserializer<'a>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+            let s = String::deserialize(deserializer)?;
+
+            NaiveDateTime::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)
+        }
+    }
+
     pub mod optional_date {
         use chrono::NaiveDateTime;
         use serde::{self, Deserialize, Serializer, Deserializer};
@@ -190,3 +425,31 @@ pub mod formatters {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use super::forwarded_client;
+    use super::sorting::SyncFilter;
+
+    #[test]
+    fn forwarded_clients() {
+        let ip = |address: &str| address.parse::<IpAddr>().unwrap();
+        let proxies = [ip("10.0.0.1"), ip("10.0.0.2")];
+
+        assert_eq!(forwarded_client(ip("203.0.113.9"), Some("1.2.3.4"), &proxies), ip("203.0.113.9"), "untrusted peers can't forward");
+        assert_eq!(forwarded_client(ip("10.0.0.1"), None, &proxies), ip("10.0.0.1"));
+        assert_eq!(forwarded_client(ip("10.0.0.1"), Some("198.51.100.7"), &proxies), ip("198.51.100.7"));
+        assert_eq!(forwarded_client(ip("10.0.0.1"), Some("1.2.3.4, 198.51.100.7, 10.0.0.2"), &proxies), ip("198.51.100.7"), "entries before the client are forged");
+        assert_eq!(forwarded_client(ip("10.0.0.1"), Some("unknown"), &proxies), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn deleted_entries_need_permission() {
+        let requested = SyncFilter { include_deleted: true, ..SyncFilter::default() };
+
+        assert!(requested.clone().with_deleted_for(true).include_deleted);
+        assert!(!requested.with_deleted_for(false).include_deleted, "callers who can't see deletions get the live entries");
+        assert!(!SyncFilter::default().with_deleted_for(true).include_deleted, "permission alone doesn't add deletions");
+    }
+}
@@ -1,5 +1,11 @@
+use std::fmt::Display;
+use std::str::FromStr;
 use salvo::http::form::FormData;
+use salvo::http::mime;
+use salvo::Request;
 use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use crate::api::utils::hash_password;
 
 use super::errors::{ApiResult, ApiError};
@@ -10,12 +16,27 @@ pub trait Validator {
 
     fn optional_string(&self, key: &str) -> ApiResult<Option<String>>;
 
+    /// Whether the key was sent at all, even when empty or `null`
+    fn contains(&self, key: &str) -> bool;
+
     fn integer(&self, key: &str) -> ApiResult<i32> {
         self.string(key)?
             .parse()
             .map_err(|error| ApiError::ParseInt(error, key.to_string()))
     }
 
+    fn optional_integer(&self, key: &str) -> ApiResult<Option<i32>> {
+        match self.optional_string(key)? {
+            None => Ok(None),
+            Some(value) => {
+                let integer = value.parse()
+                    .map_err(|error| ApiError::ParseInt(error, key.to_string()))?;
+
+                Ok(Some(integer))
+            },
+        }
+    }
+
     fn float(&self, key: &str) -> ApiResult<f32> {
          self.string(key)?
             .parse::<f32>()
@@ -29,6 +50,18 @@ pub trait Validator {
             .map_err(|e| ApiError::ParseBool(e, key.to_string()))
     }
 
+    fn optional_float(&self, key: &str) -> ApiResult<Option<f32>> {
+        match self.optional_string(key)? {
+            None => Ok(None),
+            Some(value) => {
+                let float = value.parse::<f32>()
+                    .map_err(|error| ApiError::ParseFloat(error, key.to_string()))?;
+
+                Ok(Some(float))
+            },
+        }
+    }
+
     fn optional_boolean(&self, key: &str) -> ApiResult<Option<bool>> {
         match self.optional_string(key)? {
             None => Ok(None),
@@ -51,6 +84,26 @@ pub trait Validator {
         }
     }
 
+    fn integer_list(&self, key: &str) -> ApiResult<Vec<i32>> {
+        self.string(key)?
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().map_err(|error| ApiError::ParseInt(error, key.to_string())))
+            .collect()
+    }
+
+    fn string_list(&self, key: &str) -> ApiResult<Vec<String>> {
+        let list = self.string(key)?
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(list)
+    }
+
     fn password(&self, key: &str) -> ApiResult<Vec<u8>> {
         let pwd = self.string(key)?;
         let hashed = hash_password(&pwd);
@@ -80,13 +133,375 @@ impl Validator for FormValidator<'_> {
 
         Ok(value)
     }
+
+    fn contains(&self, key: &str) -> bool {
+        self.0.fields.contains_key(key)
+    }
+}
+
+/// Validates a JSON object body, an explicit `null` reads as a missing value
+pub struct JsonValidator(pub Map<String, Value>);
+
+impl JsonValidator {
+    fn scalar(key: &str, value: &Value) -> ApiResult<String> {
+        match value {
+            Value::String(s) => Ok(s.to_string()),
+            Value::Number(n) => Ok(n.to_string()),
+            Value::Bool(b) => Ok(b.to_string()),
+            Value::Null => Err(ApiError::BadRequestError(format!("`{key}` cannot be null"))),
+            _ => Err(ApiError::BadRequestError(format!("`{key}` must be a string, number or boolean"))),
+        }
+    }
+
+    /// Lists may be sent as arrays or as comma separated strings, like in forms
+    fn list(&self, key: &str) -> ApiResult<Vec<String>> {
+        match self.0.get(key) {
+            Some(Value::Array(values)) => values.iter()
+                .map(|value| Self::scalar(key, value))
+                .collect(),
+            _ => Ok(self.string(key)?
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()),
+        }
+    }
+}
+
+impl Validator for JsonValidator {
+    fn string(&self, key: &str) -> ApiResult<String> {
+        let value = self.0.get(key)
+            .ok_or(ApiError::FieldNotFound(key.to_string()))?;
+
+        Self::scalar(key, value)
+    }
+
+    fn optional_string(&self, key: &str) -> ApiResult<Option<String>> {
+        match self.0.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => Ok(Some(Self::scalar(key, value)?)),
+        }
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    fn integer_list(&self, key: &str) -> ApiResult<Vec<i32>> {
+        self.list(key)?
+            .iter()
+            .map(|s| s.parse().map_err(|error| ApiError::ParseInt(error, key.to_string())))
+            .collect()
+    }
+
+    fn string_list(&self, key: &str) -> ApiResult<Vec<String>> {
+        Ok(self.list(key)?.into_iter().filter(|s| !s.is_empty()).collect())
+    }
+}
+
+/// Validates a request body sent either as JSON or as a form, following its `Content-Type`
+pub enum BodyValidator<'a> {
+    Form(FormValidator<'a>),
+    Json(JsonValidator),
+}
+
+impl<'a> BodyValidator<'a> {
+    pub async fn from_request(req: &'a mut Request) -> ApiResult<Self> {
+        let is_json = req.content_type()
+            .is_some_and(|content_type| content_type.subtype() == mime::JSON || content_type.suffix() == Some(mime::JSON));
+
+        if !is_json {
+            return Ok(Self::Form(FormValidator(req.form_data().await?)));
+        }
+
+        match req.parse_json::<Value>().await? {
+            Value::Object(map) => Ok(Self::Json(JsonValidator(map))),
+            _ => Err(ApiError::BadRequestError("The JSON body must be an object".to_string())),
+        }
+    }
+
+    fn inner(&self) -> &dyn Validator {
+        match self {
+            Self::Form(validator) => validator,
+            Self::Json(validator) => validator,
+        }
+    }
+}
+
+impl Validator for BodyValidator<'_> {
+    fn string(&self, key: &str) -> ApiResult<String> {
+        self.inner().string(key)
+    }
+
+    fn optional_string(&self, key: &str) -> ApiResult<Option<String>> {
+        self.inner().optional_string(key)
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.inner().contains(key)
+    }
+
+    fn integer_list(&self, key: &str) -> ApiResult<Vec<i32>> {
+        self.inner().integer_list(key)
+    }
+
+    fn string_list(&self, key: &str) -> ApiResult<Vec<String>> {
+        self.inner().string_list(key)
+    }
+}
+
+/// Machine readable reason of a field error
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldErrorCode {
+    Required,
+    Invalid,
+    InvalidNumber,
+    InvalidBoolean,
+    InvalidDate,
+    InvalidEmail,
+    InvalidUrl,
+    TooShort,
+    TooLong,
+    TooSmall,
+    TooLarge,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub code: FieldErrorCode,
+    pub message: String,
+}
+
+/// Declarative checks on a field, lengths apply to text and bounds to numbers
+#[derive(Debug, Clone, Copy)]
+pub enum Constraint {
+    MinLength(usize),
+    MaxLength(usize),
+    Min(f64),
+    Max(f64),
+    Email,
+    Url,
+}
+
+/// Collects the errors of every field of a body instead of stopping at the first one.
+/// Failed fields read as their default value, `finish` must be called before using them.
+pub struct FieldValidator<'a> {
+    validator: &'a dyn Validator,
+    errors: Vec<FieldError>,
+}
+
+impl<'a> FieldValidator<'a> {
+    pub fn new(validator: &'a dyn Validator) -> Self {
+        Self { validator, errors: vec![] }
+    }
+
+    pub fn add_error(&mut self, field: &str, code: FieldErrorCode, message: impl Display) {
+        self.errors.push(FieldError { field: field.to_string(), code, message: message.to_string() });
+    }
+
+    /// Fails with every error found, as a 422
+    pub fn finish(self) -> ApiResult<()> {
+        match self.errors.is_empty() {
+            true => Ok(()),
+            false => Err(ApiError::Validation(self.errors)),
+        }
+    }
+
+    /// Partial updates only validate the fields sent
+    pub fn contains(&self, key: &str) -> bool {
+        self.validator.contains(key)
+    }
+
+    fn raw(&mut self, key: &str, required: bool) -> Option<String> {
+        match self.validator.optional_string(key) {
+            Err(error) => {
+                self.add_error(key, FieldErrorCode::Invalid, error);
+                None
+            },
+            Ok(None) if required => {
+                self.add_error(key, FieldErrorCode::Required, format!("`{key}` is required"));
+                None
+            },
+            Ok(value) => value,
+        }
+    }
+
+    fn parse<T: FromStr>(&mut self, key: &str, required: bool, code: FieldErrorCode) -> Option<T> where T::Err: Display {
+        let value = self.raw(key, required)?;
+
+        match value.trim().parse() {
+            Ok(parsed) => Some(parsed),
+            Err(error) => {
+                self.add_error(key, code, format!("`{key}` is not valid: {error}"));
+                None
+            },
+        }
+    }
+
+    fn check_text(&mut self, key: &str, value: &str, constraints: &[Constraint]) {
+        let length = value.chars().count();
+
+        for constraint in constraints {
+            match *constraint {
+                Constraint::MinLength(1) if length < 1 =>
+                    self.add_error(key, FieldErrorCode::TooShort, format!("`{key}` cannot be empty")),
+                Constraint::MinLength(min) if length < min =>
+                    self.add_error(key, FieldErrorCode::TooShort, format!("`{key}` must have at least {min} characters")),
+                Constraint::MaxLength(max) if length > max =>
+                    self.add_error(key, FieldErrorCode::TooLong, format!("`{key}` must have at most {max} characters")),
+                Constraint::Email if !is_email(value) =>
+                    self.add_error(key, FieldErrorCode::InvalidEmail, format!("`{key}` must be an email address")),
+                Constraint::Url if !is_url(value) =>
+                    self.add_error(key, FieldErrorCode::InvalidUrl, format!("`{key}` must be an http(s) url")),
+                _ => {},
+            }
+        }
+    }
+
+    fn check_number(&mut self, key: &str, value: f64, constraints: &[Constraint]) {
+        for constraint in constraints {
+            match *constraint {
+                Constraint::Min(min) if value < min =>
+                    self.add_error(key, FieldErrorCode::TooSmall, format!("`{key}` must be at least {min}")),
+                Constraint::Max(max) if value > max =>
+                    self.add_error(key, FieldErrorCode::TooLarge, format!("`{key}` must be at most {max}")),
+                _ => {},
+            }
+        }
+    }
+
+    pub fn string(&mut self, key: &str, constraints: &[Constraint]) -> String {
+        self.optional_text(key, true, constraints).unwrap_or_default()
+    }
+
+    pub fn optional_string(&mut self, key: &str, constraints: &[Constraint]) -> Option<String> {
+        self.optional_text(key, false, constraints)
+    }
+
+    fn optional_text(&mut self, key: &str, required: bool, constraints: &[Constraint]) -> Option<String> {
+        let value = self.raw(key, required)?;
+
+        self.check_text(key, &value, constraints);
+
+        Some(value)
+    }
+
+    pub fn integer(&mut self, key: &str, constraints: &[Constraint]) -> i32 {
+        self.optional_number(key, true, constraints).unwrap_or_default()
+    }
+
+    pub fn optional_integer(&mut self, key: &str, constraints: &[Constraint]) -> Option<i32> {
+        self.optional_number(key, false, constraints)
+    }
+
+    pub fn float(&mut self, key: &str, constraints: &[Constraint]) -> f32 {
+        self.optional_number(key, true, constraints).unwrap_or_default()
+    }
+
+    pub fn optional_float(&mut self, key: &str, constraints: &[Constraint]) -> Option<f32> {
+        self.optional_number(key, false, constraints)
+    }
+
+    fn optional_number<T>(&mut self, key: &str, required: bool, constraints: &[Constraint]) -> Option<T>
+    where T: FromStr + Into<f64> + Copy, T::Err: Display {
+        let value = self.parse::<T>(key, required, FieldErrorCode::InvalidNumber)?;
+
+        // `NaN` and `inf` parse as floats, and NaN would slip past every bound
+        if !value.into().is_finite() {
+            self.add_error(key, FieldErrorCode::InvalidNumber, format!("`{key}` must be a finite number"));
+            return None;
+        }
+
+        self.check_number(key, value.into(), constraints);
+
+        Some(value)
+    }
+
+    pub fn boolean(&mut self, key: &str) -> bool {
+        self.parse(key, true, FieldErrorCode::InvalidBoolean).unwrap_or_default()
+    }
+
+    pub fn optional_boolean(&mut self, key: &str) -> Option<bool> {
+        self.parse(key, false, FieldErrorCode::InvalidBoolean)
+    }
+
+    pub fn optional_date(&mut self, key: &str) -> Option<NaiveDateTime> {
+        let value = self.raw(key, false)?;
+
+        match NaiveDateTime::parse_from_str(&value, FORMAT) {
+            Ok(date) => Some(date),
+            Err(_) => {
+                self.add_error(key, FieldErrorCode::InvalidDate, format!("`{key}` must be a date like `2026-01-31 00:00:00`"));
+                None
+            },
+        }
+    }
+
+    /// Any value that parses into `T`, e.g. enums
+    pub fn parsed<T: FromStr>(&mut self, key: &str, required: bool) -> Option<T> where T::Err: Display {
+        self.parse(key, required, FieldErrorCode::Invalid)
+    }
+
+    pub fn password(&mut self, key: &str, constraints: &[Constraint]) -> Vec<u8> {
+        hash_password(&self.string(key, constraints))
+    }
+}
+
+/// One `@` between a local part and a dotted domain, no spaces but the surrounding ones
+fn is_email(value: &str) -> bool {
+    let value = value.trim();
+
+    match value.split_once('@') {
+        None => false,
+        Some((local, domain)) => !local.is_empty()
+            && !domain.contains('@')
+            && domain.split('.').count() > 1
+            && domain.split('.').all(|part| !part.is_empty())
+            && !value.contains(char::is_whitespace),
+    }
+}
+
+fn is_url(value: &str) -> bool {
+    let rest = value.strip_prefix("https://").or_else(|| value.strip_prefix("http://"));
+
+    match rest {
+        None => false,
+        Some(rest) => {
+            let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+            !host.is_empty() && !value.contains(char::is_whitespace)
+        },
+    }
+}
+
+/// Validates the query string of a request, e.g. list filters
+pub struct QueryValidator<'a>(pub &'a Request);
+
+impl Validator for QueryValidator<'_> {
+    fn string(&self, key: &str) -> ApiResult<String> {
+        self.0.query::<String>(key)
+            .ok_or(ApiError::FieldNotFound(key.to_string()))
+    }
+
+    fn optional_string(&self, key: &str) -> ApiResult<Option<String>> {
+        Ok(self.0.query::<String>(key))
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.0.queries().contains_key(key)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use chrono::Datelike;
     use salvo::http::form::FormData;
-    use super::{Validator, FormValidator};
+    use serde_json::json;
+    use super::{Constraint, FieldErrorCode, FieldValidator, Validator, FormValidator, JsonValidator};
+    use crate::api::errors::ApiError;
 
     fn form_data(fields: &[(&str, &str)]) -> FormData {
         let mut form_data = FormData::new();
@@ -109,6 +524,10 @@ mod tests {
             ("active_career", "true"),
             ("married", "false"),
             ("cinema", "false"),
+            ("lucky_numbers", "7, 13,21"),
+            ("height", "1.75"),
+            ("shoe_size", "42"),
+            ("hobbies", "chess, ,cooking"),
         ]);
 
         let validator = FormValidator(&form_data);
@@ -119,10 +538,134 @@ mod tests {
         assert_eq!(validator.float("salary").unwrap(), 2040000.05);
         assert_eq!(validator.optional_date("expiration").unwrap().unwrap().month(), 2);
         assert_eq!(validator.optional_date("expedition").unwrap(), None);
-        assert!(validator.password("password").unwrap().len() > 0);
-        assert_eq!(validator.boolean("active_career").unwrap(), true);
-        assert_eq!(validator.boolean("married").unwrap(), false);
+        assert!(!validator.password("password").unwrap().is_empty());
+        assert!(validator.boolean("active_career").unwrap());
+        assert!(!validator.boolean("married").unwrap());
         assert_eq!(validator.optional_boolean("children").unwrap(), None);
         assert_eq!(validator.optional_boolean("cinema").unwrap(), Some(false));
+        assert_eq!(validator.integer_list("lucky_numbers").unwrap(), vec![7, 13, 21]);
+        assert_eq!(validator.optional_float("height").unwrap(), Some(1.75));
+        assert_eq!(validator.optional_float("weight").unwrap(), None);
+        assert_eq!(validator.optional_integer("shoe_size").unwrap(), Some(42));
+        assert_eq!(validator.optional_integer("children").unwrap(), None);
+        assert_eq!(validator.string_list("hobbies").unwrap(), vec!["chess", "cooking"]);
+    }
+
+    #[test]
+    fn validate_json() {
+        let body = json!({
+            "name": "Britney Swift",
+            "nickname": null,
+            "age": 54,
+            "salary": 2040000.05,
+            "active_career": true,
+            "lucky_numbers": [7, 13, 21],
+            "hobbies": "chess, ,cooking",
+            "address": { "city": "Lima" },
+        });
+
+        let validator = JsonValidator(body.as_object().unwrap().clone());
+
+        assert_eq!(validator.string("name").unwrap(), "Britney Swift");
+        assert!(validator.contains("nickname"), "an explicit null is sent");
+        assert!(!validator.contains("children"), "a missing key is not");
+        assert_eq!(validator.optional_string("nickname").unwrap(), None);
+        assert!(validator.string("nickname").is_err(), "null is not a value");
+        assert_eq!(validator.integer("age").unwrap(), 54);
+        assert_eq!(validator.float("salary").unwrap(), 2040000.05);
+        assert!(validator.boolean("active_career").unwrap());
+        assert_eq!(validator.integer_list("lucky_numbers").unwrap(), vec![7, 13, 21]);
+        assert_eq!(validator.string_list("hobbies").unwrap(), vec!["chess", "cooking"]);
+        assert!(validator.string("address").is_err(), "objects are not scalars");
+    }
+
+    #[test]
+    fn collect_field_errors() {
+        let form_data = form_data(&[
+            ("name", ""),
+            ("email", "britney@swift"),
+            ("website", "ftp://swift.test"),
+            ("age", "fifty"),
+            ("salary", "-1"),
+            ("expiration", "tomorrow"),
+        ]);
+
+        let form_validator = FormValidator(&form_data);
+        let mut validator = FieldValidator::new(&form_validator);
+
+        validator.string("name", &[Constraint::MinLength(1)]);
+        validator.string("email", &[Constraint::Email]);
+        validator.optional_string("website", &[Constraint::Url]);
+        validator.integer("age", &[]);
+        validator.float("salary", &[Constraint::Min(0.0)]);
+        validator.optional_date("expiration");
+        validator.string("password", &[]);
+
+        let Err(ApiError::Validation(errors)) = validator.finish() else {
+            panic!("every field has an error");
+        };
+
+        let codes: Vec<(&str, FieldErrorCode)> = errors.iter()
+            .map(|error| (error.field.as_str(), error.code))
+            .collect();
+
+        assert_eq!(codes, vec![
+            ("name", FieldErrorCode::TooShort),
+            ("email", FieldErrorCode::InvalidEmail),
+            ("website", FieldErrorCode::InvalidUrl),
+            ("age", FieldErrorCode::InvalidNumber),
+            ("salary", FieldErrorCode::TooSmall),
+            ("expiration", FieldErrorCode::InvalidDate),
+            ("password", FieldErrorCode::Required),
+        ]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reject_non_finite_numbers() {
+        let form_data = form_data(&[("price", "NaN"), ("discount", "-inf"), ("weight", "infinity")]);
+
+        let form_validator = FormValidator(&form_data);
+        let mut validator = FieldValidator::new(&form_validator);
+
+        validator.float("price", &[Constraint::Min(0.0), Constraint::Max(1000.0)]);
+        validator.optional_float("discount", &[]);
+        validator.float("weight", &[]);
+
+        let Err(ApiError::Validation(errors)) = validator.finish() else {
+            panic!("non finite numbers are invalid");
+        };
+
+        let codes: Vec<(&str, FieldErrorCode)> = errors.iter()
+            .map(|error| (error.field.as_str(), error.code))
+            .collect();
+
+        assert_eq!(codes, vec![
+            ("price", FieldErrorCode::InvalidNumber),
+            ("discount", FieldErrorCode::InvalidNumber),
+            ("weight", FieldErrorCode::InvalidNumber),
+        ]);
+    }
+
+    #[test]
+    fn pass_field_constraints() {
+        let form_data = form_data(&[
+            ("name", "Britney"),
+            ("email", "britney@swift.test"),
+            ("website", "https://swift.test/tour?year=2026"),
+            ("age", "54"),
+        ]);
+
+        let form_validator = FormValidator(&form_data);
+        let mut validator = FieldValidator::new(&form_validator);
+
+        let name = validator.string("name", &[Constraint::MinLength(1), Constraint::MaxLength(10)]);
+        let email = validator.string("email", &[Constraint::Email]);
+        let website = validator.optional_string("website", &[Constraint::Url]);
+        let age = validator.integer("age", &[Constraint::Min(18.0), Constraint::Max(120.0)]);
+        let nickname = validator.optional_string("nickname", &[Constraint::MinLength(3)]);
+
+        assert!(validator.finish().is_ok(), "all fields are valid");
+        assert_eq!((name.as_str(), email.as_str(), age, nickname), ("Britney", "britney@swift.test", 54, None));
+        assert_eq!(website.as_deref(), Some("https://swift.test/tour?year=2026"));
+    }
+}
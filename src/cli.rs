@@ -1,15 +1,23 @@
 pub mod populate;
 pub mod list;
+pub mod import;
+pub mod duplicates;
+pub mod purge;
 
 use super::{start_server, Config};
 use crate::services;
 use services::database::contracts::DatabaseService;
+use services::storage::contracts::Storage;
 use services::primary::service_injector;
 
 pub enum Command {
     Serve,
     Populate(populate::Target),
     List(list::Target),
+    Import(import::Target),
+    Duplicates(duplicates::Target),
+    /// Retention period in days
+    Purge(i32),
     Hash(String),
     Help,
 }
@@ -34,6 +42,24 @@ impl Command {
                     Some(target) => Ok(Self::List(list::Target::from(target)))
                 },
 
+                "import" => match args.get(2) {
+                    None => Ok(Self::Import(import::Target::Help)),
+                    Some(target) => Ok(Self::Import(import::Target::build(target, args.get(3))))
+                },
+
+                "duplicates" => match args.get(2) {
+                    None => Ok(Self::Duplicates(duplicates::Target::Help)),
+                    Some(target) => Ok(Self::Duplicates(duplicates::Target::build(target, &args[3..])))
+                },
+
+                "purge" => match args.get(2) {
+                    None => Ok(Self::Purge(purge::DEFAULT_RETENTION_DAYS)),
+                    Some(days) => match days.parse() {
+                        Ok(days) if days >= 0 => Ok(Self::Purge(days)),
+                        _ => Err(Error { message: format!("Retention: `{days}` is not a number of days.")}),
+                    }
+                },
+
                 "hash" => match args.get(2) {
                     None => todo!("Create help for hash"),
                     Some(target) => Ok(Self::Hash(target.to_string()))
@@ -52,22 +78,27 @@ pub struct Error {
 
 pub struct CommandProcessor {
     pub database: Box<dyn DatabaseService>,
+    pub storage: Box<dyn Storage>,
     pub config: Config,
 }
 
 impl CommandProcessor {
-    pub fn new(database: Box<dyn DatabaseService>, config: Config) -> Self {
+    pub fn new(database: Box<dyn DatabaseService>, storage: Box<dyn Storage>, config: Config) -> Self {
         Self {
             database,
+            storage,
             config,
         }
     }
 
     pub fn  process(&self, command: Command) {
         match command {
-            Command::Serve => start_server(service_injector(), &self.config),
+            Command::Serve => start_server(service_injector(&self.config), &self.config),
             Command::Populate(target) => populate::execute(target, self),
             Command::List(target) => list::execute(target, self),
+            Command::Import(target) => import::execute(target),
+            Command::Duplicates(target) => duplicates::execute(target, self),
+            Command::Purge(retention_days) => purge::execute(retention_days, self),
             Command::Hash(target) => misc::hash(&target),
             Command::Help => print_help(),
         }
@@ -98,6 +129,13 @@ const HELP_MESSAGE: &str = r#"
 
     list        Run `cargo run -- list help` for more information
 
+    import      Run `cargo run -- import help` for more information
+
+    duplicates  Run `cargo run -- duplicates help` for more information
+
+    purge       Remove for good what was deleted more than 30 days ago, or the given days
+                e.g. `cargo run -- purge 90`
+
     help        Show this screen
 
 "#;
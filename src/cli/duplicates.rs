@@ -0,0 +1,125 @@
+use super::CommandProcessor;
+use crate::api::resources::products::duplicates::{list_duplicates, merge_duplicates};
+use crate::api::resources::users::models::email_clashes;
+
+pub enum Target {
+    List,
+    Merge(i32, Vec<i32>),
+    Emails,
+    Help,
+}
+
+impl Target {
+    /// `merge` takes the surviving product and the comma separated duplicates
+    pub fn build(target: &str, args: &[String]) -> Self {
+        match target {
+            "list" => Self::List,
+            "merge" => {
+                let (Some(survivor_id), Some(duplicate_ids)) = (args.first(), args.get(1)) else {
+                    panic!("Target: `merge` needs the surviving product and its duplicates, e.g. `merge 1 2,3`");
+                };
+
+                let survivor_id = survivor_id.parse().unwrap_or_else(|_| panic!("`{survivor_id}` is not a product id"));
+
+                let duplicate_ids = duplicate_ids.split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(|id| id.parse().unwrap_or_else(|_| panic!("`{id}` is not a product id")))
+                    .collect();
+
+                Self::Merge(survivor_id, duplicate_ids)
+            },
+            "emails" => Self::Emails,
+            "help" => Self::Help,
+            other => panic!("Target: `{other}` not found"),
+        }
+    }
+}
+
+pub fn execute(target: Target, command_processor: &CommandProcessor) {
+    match target {
+        Target::List => list(command_processor),
+        Target::Merge(survivor_id, duplicate_ids) => merge(command_processor, survivor_id, &duplicate_ids),
+        Target::Emails => list_emails(command_processor),
+        Target::Help => print_help(),
+    }
+}
+
+pub fn list(processor: &CommandProcessor) {
+    let repo = processor.database.product_repo();
+
+    match list_duplicates(repo.as_ref()) {
+        Err(error) => {
+            println!("{error}");
+        },
+        Ok(groups) => {
+            groups.iter().enumerate().for_each(|(index, group)| {
+                println!("Group {}", index + 1);
+
+                group.products.iter().for_each(|product| {
+                    let url = product.url.as_deref().unwrap_or_default();
+
+                    println!("{:4}  {:40}  {:>14.2}  {url}", product.id, product.name, product.price);
+                });
+            });
+        }
+    }
+}
+
+pub fn merge(processor: &CommandProcessor, survivor_id: i32, duplicate_ids: &[i32]) {
+    let repo = processor.database.product_repo();
+
+    match merge_duplicates(repo.as_ref(), survivor_id, duplicate_ids) {
+        Err(error) => {
+            println!("{error}");
+        },
+        Ok(total) => {
+            println!("Merged {total} products into {survivor_id}");
+        }
+    }
+}
+
+/// Users sharing an email regardless of case, to be resolved before the unique email index is created
+pub fn list_emails(processor: &CommandProcessor) {
+    let repo = processor.database.user_repo();
+
+    match repo.list_email_owners() {
+        Err(error) => {
+            println!("{error}");
+        },
+        Ok(users) => {
+            let clashes = email_clashes(users);
+
+            if clashes.is_empty() {
+                println!("No users share an email");
+            }
+
+            clashes.iter().enumerate().for_each(|(index, group)| {
+                println!("Group {}", index + 1);
+
+                group.iter().for_each(|user| {
+                    println!("{:4}  {:30}  {:30}  {:6}", user.id, user.name, user.email, user.active);
+                });
+            });
+        }
+    }
+}
+
+pub fn print_help() {
+    println!("{}", DUPLICATES_HELP_MESSAGE);
+}
+
+const DUPLICATES_HELP_MESSAGE: &str = r#"
+    Duplicates command options:
+
+    list        Group the products likely to be the same, by name, url and price
+                e.g. `cargo run -- duplicates list`
+
+    merge       Move the wishes of the duplicates to the surviving product and delete them
+                e.g. `cargo run -- duplicates merge 1 2,3`
+
+    emails      List the users sharing an email regardless of case, run it before migrating
+                e.g. `cargo run -- duplicates emails`
+
+    help        Show this screen
+"#;
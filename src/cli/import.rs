@@ -0,0 +1,59 @@
+use std::fs;
+use crate::api::resources::products::import::parse_product;
+use crate::api::resources::products::models::ReviewStatus;
+
+pub enum Target {
+    Product(String),
+    Help,
+}
+
+impl Target {
+    pub fn build(target: &str, path: Option<&String>) -> Self {
+        match (target, path) {
+            ("product", Some(path)) => Self::Product(path.to_string()),
+            ("help", _) => Self::Help,
+            ("product", None) => panic!("Target: `product` needs the path of the document"),
+            (other, _) => panic!("Target: `{other}` not found"),
+        }
+    }
+}
+
+pub fn execute(target: Target) {
+    match target {
+        Target::Product(path) => import_product(&path),
+        Target::Help => print_help(),
+    }
+}
+
+/// Prints the product found in the saved page, to be reviewed before adding it
+pub fn import_product(path: &str) {
+    let html = match fs::read_to_string(path) {
+        Err(error) => {
+            println!("Error reading `{path}`: {error}");
+            return;
+        },
+        Ok(html) => html,
+    };
+
+    match parse_product(&html).into_imported(None, ReviewStatus::Approved) {
+        Err(error) => {
+            println!("{error}");
+        },
+        Ok(imported) => {
+            println!("{}", serde_json::to_string_pretty(&imported).unwrap_or_default());
+        }
+    }
+}
+
+pub fn print_help() {
+    println!("{}", IMPORT_HELP_MESSAGE);
+}
+
+const IMPORT_HELP_MESSAGE: &str = r#"
+    Import command options:
+
+    product     Read a product from a saved store page (schema.org or OpenGraph)
+                e.g. `cargo run -- import product page.html`
+
+    help        Show this screen
+"#;
@@ -1,8 +1,14 @@
 use super::CommandProcessor;
+use crate::api::resources::audit::models::{AuditFilter, AuditLog};
+use crate::api::resources::outbox::models::{OutboxEntry, OutboxStatus};
 use crate::api::resources::users::models::User;
+use crate::api::utils::formatters::optional_date::FORMAT;
+use crate::api::utils::sorting::SyncFilter;
 
 pub enum Target {
     Users,
+    Audit,
+    Notifications,
     Help,
 }
 
@@ -10,6 +16,8 @@ impl From<&String> for Target {
     fn from(value: &String) -> Self {
         match value.as_str() {
             "users" => Self::Users,
+            "audit" => Self::Audit,
+            "notifications" => Self::Notifications,
             "help" => Self::Help,
             other => panic!("Target: `{other}` not found"),
         }
@@ -19,6 +27,8 @@ impl From<&String> for Target {
 pub fn execute(target: Target, command_processor: &CommandProcessor) {
     match target {
         Target::Users => list_users(command_processor),
+        Target::Audit => list_audit(command_processor),
+        Target::Notifications => list_notifications(command_processor),
         Target::Help => print_list_help(),
     }
 }
@@ -26,7 +36,7 @@ pub fn execute(target: Target, command_processor: &CommandProcessor) {
 pub fn list_users(processor: &CommandProcessor) {
     let repo = processor.database.user_repo();
 
-    match repo.list() {
+    match repo.list(&SyncFilter::default()) {
         Err(error) => {
             println!("{error}");
         },
@@ -40,14 +50,75 @@ pub fn list_users(processor: &CommandProcessor) {
     }
 }
 
+pub fn list_audit(processor: &CommandProcessor) {
+    let repo = processor.database.audit_repo();
+
+    match repo.list(&AuditFilter::default()) {
+        Err(error) => {
+            println!("{error}");
+        },
+        Ok(logs) => {
+            logs.iter().take(AUDIT_LIMIT).for_each(|log| {
+                let AuditLog { id, actor_id, action, resource_type, resource_id, created_at, .. } = log;
+                let actor = actor_id.map_or("-".to_string(), |id| id.to_string());
+                let resource_id = resource_id.map_or("-".to_string(), |id| id.to_string());
+                let created_at = created_at.format(FORMAT);
+
+                println!("{id:6}  {created_at}  {actor:>6}  {action:20}  {resource_type:12}  {resource_id:>6}");
+            });
+        }
+    }
+}
+
+/// Totals of the outbox and the notifications not sent yet
+pub fn list_notifications(processor: &CommandProcessor) {
+    let repo = processor.database.outbox_repo();
+
+    match repo.summary() {
+        Err(error) => {
+            println!("{error}");
+            return;
+        },
+        Ok(summary) => {
+            println!("pending: {}  sent: {}  failed: {}", summary.pending, summary.sent, summary.failed);
+        }
+    }
+
+    for status in [OutboxStatus::Pending, OutboxStatus::Failed] {
+        match repo.list(Some(status)) {
+            Err(error) => {
+                println!("{error}");
+            },
+            Ok(entries) => {
+                entries.iter().for_each(|entry| {
+                    let OutboxEntry { id, user_id, kind, attempts, next_attempt_at, last_error, .. } = entry;
+                    let status = status.as_str();
+                    let next_attempt_at = next_attempt_at.format(FORMAT);
+                    let last_error = last_error.as_deref().unwrap_or("-");
+
+                    println!("{id:6}  {status:8}  {user_id:>6}  {kind:20}  {attempts:>3}  {next_attempt_at}  {last_error}");
+                });
+            }
+        }
+    }
+}
+
 pub fn print_list_help() {
     println!("{}", LIST_HELP_MESSAGE);
 }
 
+const AUDIT_LIMIT: usize = 100;
+
 const LIST_HELP_MESSAGE: &str = r#"
     List command options:
 
     users       e.g. `cargo run -- list users`
 
+    audit       Latest audit log entries, e.g. `cargo run -- list audit`
+
+    notifications
+                Outbox totals and the notifications not sent yet, with their attempts and last error
+                e.g. `cargo run -- list notifications`
+
     help        Show this screen
 "#;
\ No newline at end of file
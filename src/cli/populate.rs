@@ -40,7 +40,7 @@ pub fn execute(target: Target, processor: &CommandProcessor) {
 pub fn populate_products(processor: &CommandProcessor) {
     let repo = processor.database.product_repo();
 
-    match parse_products_csv() {
+    match parse_products_csv(processor.database.category_repo().as_ref()) {
         Err(error) => {
             println!("{error}");
         },
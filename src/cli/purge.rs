@@ -0,0 +1,30 @@
+use super::CommandProcessor;
+use crate::api::resources::images::upload::discard_files;
+
+/// Deleted rows are kept this many days unless told otherwise
+pub const DEFAULT_RETENTION_DAYS: i32 = 30;
+
+/// Permanently removes the wishes, wishlists and products deleted more than `retention_days` ago
+pub fn execute(retention_days: i32, processor: &CommandProcessor) {
+    let database = &processor.database;
+
+    // Wishes first, products are only purged once no wish points to them
+    let purged = database.wish_repo().purge(retention_days)
+        .and_then(|wishes| {
+            let (wishlists, wishlist_images) = database.wishlist_repo().purge(retention_days)?;
+            let (products, product_images) = database.product_repo().purge(retention_days)?;
+
+            Ok((wishes, wishlists, products, [wishlist_images, product_images].concat()))
+        });
+
+    match purged {
+        Err(error) => {
+            println!("{error}");
+        },
+        Ok((wishes, wishlists, products, images)) => {
+            discard_files(processor.storage.as_ref(), &images);
+
+            println!("Purged {wishes} wishes, {wishlists} wishlists and {products} products deleted more than {retention_days} days ago");
+        }
+    }
+}
@@ -6,22 +6,26 @@ pub mod services;
 
 use salvo::prelude::*;
 use api::auth;
+use api::utils::REQUEST_ID_HEADER;
 use home::home_controller;
 use salvo::catcher::Catcher;
 use salvo::cors::Cors;
 use salvo::http::Method;
 use std::collections::HashMap;
 use std::env;
+use std::net::IpAddr;
 use services::ServiceInjector;
+use services::notifications::{MailConfig, MailTransport};
 pub use services::database as db;
 
 pub mod models {
-    use salvo::http::form::FormData;
     use serde::{Deserialize, Serialize};
     use crate::api::errors::ApiResult;
+    use crate::api::validations::Validator;
 
+    /// Applies a partial update, keys missing from the body are left untouched
     pub trait Mergeable {
-        fn merge(self, form_data: &FormData) -> ApiResult<Self> where Self: Sized;
+        fn merge(self, validator: &dyn Validator) -> ApiResult<Self> where Self: Sized;
     }
 
     pub trait Composable<T, G> {
@@ -45,6 +49,8 @@ pub mod models {
     }
 }
 
+static DEFAULT_MAIL_DIR: &str = "mails";
+
 struct ConfigParams(pub HashMap<String, String>);
 
 impl ConfigParams {
@@ -54,6 +60,10 @@ impl ConfigParams {
             None => env::var(key).unwrap_or_else(|_| panic!("{} must be set", key))
         }
     }
+
+    pub fn get_optional(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned().or_else(|| env::var(key).ok())
+    }
 }
 
 #[derive(Default)]
@@ -61,6 +71,11 @@ pub struct Config {
     domain: String,
     port: String,
     client_url: String,
+    /// Shows the cause of internal errors in responses, never set it in production
+    debug: bool,
+    /// Proxies whose `X-Forwarded-For` header is believed
+    trusted_proxies: Vec<IpAddr>,
+    mail: MailConfig,
     // params: ConfigParams,
 }
 
@@ -70,15 +85,51 @@ impl Config {
         let domain = params.get("DOMAIN");
         let port = params.get("PORT");
         let client_url = params.get("CLIENT_URL");
+        let debug = params.get_optional("DEBUG")
+            .is_some_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true"));
+        let trusted_proxies = params.get_optional("TRUSTED_PROXIES")
+            .map(|proxies| Self::trusted_proxies(&proxies))
+            .unwrap_or_default();
 
         Self {
             domain,
             port,
             client_url,
+            debug,
+            trusted_proxies,
+            mail: Self::mail_config(&params),
             // params,
         }
     }
 
+    pub fn mail(&self) -> &MailConfig {
+        &self.mail
+    }
+
+    /// A comma separated list of addresses, e.g. `127.0.0.1, ::1`
+    fn trusted_proxies(proxies: &str) -> Vec<IpAddr> {
+        proxies.split(',')
+            .map(str::trim)
+            .filter(|proxy| !proxy.is_empty())
+            .map(|proxy| proxy.parse().unwrap_or_else(|_| panic!("`{proxy}` in TRUSTED_PROXIES is not an ip address")))
+            .collect()
+    }
+
+    /// `MAIL_TRANSPORT` picks how notifications are sent: `console` (default), `smtp` or `file`
+    fn mail_config(params: &ConfigParams) -> MailConfig {
+        let transport = match params.get_optional("MAIL_TRANSPORT").unwrap_or_default().to_lowercase().as_str() {
+            "smtp" => MailTransport::Smtp(params.get("SMTP_URL")),
+            "file" => MailTransport::File(params.get_optional("MAIL_DIR").unwrap_or_else(|| DEFAULT_MAIL_DIR.to_string()).into()),
+            _ => return MailConfig::default(),
+        };
+
+        MailConfig {
+            transport,
+            from: params.get("MAIL_FROM"),
+            reply_to: params.get_optional("MAIL_REPLY_TO"),
+        }
+    }
+
     fn params() -> ConfigParams {
         let args = env::args();
 
@@ -103,12 +154,18 @@ impl Config {
 
 #[tokio::main]
 pub async fn start_server(service_injector: ServiceInjector, config: &Config) {
-    let Config { domain, port, client_url, .. } = config;
+    let Config { domain, port, client_url, debug, trusted_proxies, .. } = config;
+
+    api::errors::show_internal_details(*debug);
+    api::utils::trust_proxies(trusted_proxies.clone());
+
+    service_injector.outbox_worker().spawn();
 
     let cors_handler = Cors::new()
         .allow_origin(vec![client_url.as_str()])
-        .allow_methods(vec![Method::GET, Method::POST, Method::DELETE])
-        .allow_headers(vec!["content-type", "authorization"])
+        .allow_methods(vec![Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers(vec!["content-type", "authorization", REQUEST_ID_HEADER])
+        .expose_headers(vec![REQUEST_ID_HEADER])
         .into_handler();
 
     let router = Router::new()
@@ -2,6 +2,7 @@ use std::{env, process};
 use dotenvy::dotenv;
 use fishbowl::Config;
 use fishbowl::services::database::MainDatabase;
+use fishbowl::services::storage::LocalStorage;
 use fishbowl::cli::{Command, CommandProcessor};
 
 fn main() {
@@ -16,7 +17,8 @@ fn main() {
 
     let config = Config::build();
     let database = Box::new(MainDatabase);
-    let command_processor = CommandProcessor::new(database, config);
+    let storage = Box::new(LocalStorage::from_env());
+    let command_processor = CommandProcessor::new(database, storage, config);
 
     command_processor.process(command);
 }
@@ -1,11 +1,103 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    audit_logs (id) {
+        id -> Int4,
+        actor_id -> Nullable<Int4>,
+        action -> Varchar,
+        resource_type -> Varchar,
+        resource_id -> Nullable<Int4>,
+        changes -> Nullable<Jsonb>,
+        ip -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    categories (id) {
+        id -> Int4,
+        name -> Varchar,
+        slug -> Varchar,
+        parent_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    collaborators (id) {
+        id -> Int4,
+        wishlist_id -> Int4,
+        user_id -> Int4,
+        role -> Varchar,
+    }
+}
+
 diesel::table! {
     followers (id) {
         id -> Int4,
         wishlist_id -> Int4,
         user_id -> Int4,
         active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        #[max_length = 10]
+        locale -> Varchar,
+    }
+}
+
+diesel::table! {
+    images (id) {
+        id -> Int4,
+        product_id -> Nullable<Int4>,
+        wishlist_id -> Nullable<Int4>,
+        content_type -> Varchar,
+        path -> Varchar,
+        thumbnail_path -> Varchar,
+    }
+}
+
+diesel::table! {
+    notifications (id) {
+        id -> Int4,
+        user_id -> Int4,
+        kind -> Varchar,
+        message -> Jsonb,
+        read_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    notification_outbox (id) {
+        id -> Int4,
+        user_id -> Int4,
+        kind -> Varchar,
+        message -> Jsonb,
+        status -> Varchar,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        next_attempt_at -> Timestamp,
+        sent_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    product_changes (id) {
+        id -> Int4,
+        product_id -> Int4,
+        previous_price -> Float4,
+        price -> Float4,
+        previous_available -> Bool,
+        available -> Bool,
+        changed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    product_tags (product_id, tag_id) {
+        product_id -> Int4,
+        tag_id -> Int4,
     }
 }
 
@@ -17,6 +109,22 @@ diesel::table! {
         url -> Nullable<Varchar>,
         price -> Float4,
         available -> Bool,
+        review_status -> Varchar,
+        submitted_by -> Nullable<Int4>,
+        category_id -> Nullable<Int4>,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    share_links (id) {
+        id -> Int4,
+        wishlist_id -> Int4,
+        token -> Varchar,
+        expires_at -> Nullable<Timestamp>,
+        revoked -> Bool,
     }
 }
 
@@ -27,6 +135,32 @@ diesel::table! {
         leader -> Nullable<Bool>,
         wish_id -> Int4,
         user_id -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    tags (id) {
+        id -> Int4,
+        name -> Varchar,
+    }
+}
+
+diesel::table! {
+    template_products (id) {
+        id -> Int4,
+        template_id -> Int4,
+        product_id -> Int4,
+        position -> Int4,
+    }
+}
+
+diesel::table! {
+    templates (id) {
+        id -> Int4,
+        title -> Varchar,
+        description -> Nullable<Varchar>,
     }
 }
 
@@ -38,6 +172,10 @@ diesel::table! {
         role -> Varchar,
         password -> Bytea,
         active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        #[max_length = 10]
+        locale -> Varchar,
     }
 }
 
@@ -47,6 +185,11 @@ diesel::table! {
         pending -> Bool,
         wishlist_id -> Int4,
         product_id -> Int4,
+        position -> Int4,
+        original_price -> Float4,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -57,22 +200,52 @@ diesel::table! {
         description -> Nullable<Varchar>,
         date -> Nullable<Timestamp>,
         user_id -> Int4,
-        published -> Bool,
+        visibility -> Varchar,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
+diesel::joinable!(audit_logs -> users (actor_id));
+diesel::joinable!(collaborators -> users (user_id));
+diesel::joinable!(collaborators -> wishlists (wishlist_id));
 diesel::joinable!(followers -> users (user_id));
 diesel::joinable!(followers -> wishlists (wishlist_id));
+diesel::joinable!(images -> products (product_id));
+diesel::joinable!(images -> wishlists (wishlist_id));
+diesel::joinable!(notification_outbox -> users (user_id));
+diesel::joinable!(notifications -> users (user_id));
+diesel::joinable!(product_changes -> products (product_id));
+diesel::joinable!(product_tags -> products (product_id));
+diesel::joinable!(product_tags -> tags (tag_id));
+diesel::joinable!(products -> categories (category_id));
+diesel::joinable!(products -> users (submitted_by));
+diesel::joinable!(share_links -> wishlists (wishlist_id));
 diesel::joinable!(sponsors -> users (user_id));
 diesel::joinable!(sponsors -> wishes (wish_id));
+diesel::joinable!(template_products -> products (product_id));
+diesel::joinable!(template_products -> templates (template_id));
 diesel::joinable!(wishes -> products (product_id));
 diesel::joinable!(wishes -> wishlists (wishlist_id));
 diesel::joinable!(wishlists -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    audit_logs,
+    categories,
+    collaborators,
     followers,
+    images,
+    notification_outbox,
+    notifications,
+    product_changes,
+    product_tags,
     products,
+    share_links,
     sponsors,
+    tags,
+    template_products,
+    templates,
     users,
     wishes,
     wishlists,
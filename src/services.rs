@@ -1,30 +1,42 @@
 pub mod primary;
 pub mod database;
 pub mod notifications;
+pub mod storage;
+pub mod outbox;
 
 use salvo::prelude::*;
 use std::sync::Arc;
 use database::contracts::DatabaseService;
 use notifications::contracts::Notifier;
+use storage::contracts::Storage;
+use outbox::OutboxWorker;
 
-pub struct InjectableServices<D: DatabaseService, N: Notifier> {
+pub struct InjectableServices<D: DatabaseService, N: Notifier, S: Storage> {
     pub database: D,
     pub notifier: N,
+    pub storage: S,
 }
 
 #[derive(Clone)]
 pub struct ServiceInjector {
     database: Arc<dyn DatabaseService>,
     notifier: Arc<dyn Notifier>,
+    storage: Arc<dyn Storage>,
 }
 
 impl ServiceInjector {
-    pub fn new<D: DatabaseService + 'static, N: Notifier + 'static>(services: InjectableServices<D, N>) -> Self {
+    pub fn new<D: DatabaseService + 'static, N: Notifier + 'static, S: Storage + 'static>(services: InjectableServices<D, N, S>) -> Self {
         Self {
             database: Arc::new(services.database),
             notifier: Arc::new(services.notifier),
+            storage: Arc::new(services.storage),
         }
     }
+
+    /// Delivers the notifications queued by the requests with the injected notifier
+    pub fn outbox_worker(&self) -> OutboxWorker {
+        OutboxWorker::new(self.database.clone(), self.notifier.clone())
+    }
 }
 
 #[async_trait]
@@ -32,6 +44,7 @@ impl Handler for ServiceInjector {
     async fn handle(&self, _req: &mut Request, depot: &mut Depot, _res: &mut Response, _ctrl: &mut FlowCtrl) {
         depot.inject::<Arc<dyn DatabaseService>>(self.database.clone());
         depot.inject::<Arc<dyn Notifier>>(self.notifier.clone());
+        depot.inject::<Arc<dyn Storage>>(self.storage.clone());
     }
 }
 
@@ -10,6 +10,16 @@ use crate::api::resources::wishlists;
 use crate::api::resources::wishes;
 use crate::api::resources::sponsors;
 use crate::api::resources::followers;
+use crate::api::resources::templates;
+use crate::api::resources::collaborators;
+use crate::api::resources::shares;
+use crate::api::resources::search;
+use crate::api::resources::categories;
+use crate::api::resources::tags;
+use crate::api::resources::images;
+use crate::api::resources::audit;
+use crate::api::resources::outbox;
+use crate::api::resources::inbox;
 use crate::api::auth;
 
 pub fn establish_connection() -> PgConnection {
@@ -50,4 +60,44 @@ impl DatabaseService for MainDatabase {
     fn follower_repo(&self) -> Box<dyn contracts::FollowerRepo> {
         Box::new(followers::repo::Repo)
     }
+
+    fn template_repo(&self) -> Box<dyn contracts::TemplateRepo> {
+        Box::new(templates::repo::Repo)
+    }
+
+    fn collaborator_repo(&self) -> Box<dyn contracts::CollaboratorRepo> {
+        Box::new(collaborators::repo::Repo)
+    }
+
+    fn share_link_repo(&self) -> Box<dyn contracts::ShareLinkRepo> {
+        Box::new(shares::repo::Repo)
+    }
+
+    fn search_repo(&self) -> Box<dyn contracts::SearchRepo> {
+        Box::new(search::repo::Repo)
+    }
+
+    fn category_repo(&self) -> Box<dyn contracts::CategoryRepo> {
+        Box::new(categories::repo::Repo)
+    }
+
+    fn tag_repo(&self) -> Box<dyn contracts::TagRepo> {
+        Box::new(tags::repo::Repo)
+    }
+
+    fn image_repo(&self) -> Box<dyn contracts::ImageRepo> {
+        Box::new(images::repo::Repo)
+    }
+
+    fn audit_repo(&self) -> Box<dyn contracts::AuditRepo> {
+        Box::new(audit::repo::Repo)
+    }
+
+    fn outbox_repo(&self) -> Box<dyn contracts::OutboxRepo> {
+        Box::new(outbox::repo::Repo)
+    }
+
+    fn inbox_repo(&self) -> Box<dyn contracts::InboxRepo> {
+        Box::new(inbox::repo::Repo)
+    }
 }
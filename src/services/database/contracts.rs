@@ -1,11 +1,24 @@
+use crate::api::resources::audit::models::{AuditFilter, AuditLog, NewAuditLog};
+use crate::api::resources::categories::models::{Category, NewCategory};
+use crate::api::resources::collaborators::models::{Collaborator, NewCollaborator};
 use crate::api::resources::followers::models::{Follower, NewFollower};
-use crate::api::resources::products::models::{Product, ListedProduct, NewProduct};
+use crate::api::resources::images::models::{Image, NewImage};
+use crate::api::resources::inbox::models::{NewNotification, Notification};
+use crate::api::resources::outbox::models::{NewOutboxEntry, OutboxEntry, OutboxStatus, OutboxSummary};
+use crate::api::resources::products::models::{Product, ListedProduct, NewProduct, ProductChange, ProductFilter, ReviewStatus};
+use crate::api::resources::search::models::{SearchHit, SearchLanguage};
+use crate::api::resources::shares::models::{NewShareLink, ShareLink};
 use crate::api::resources::sponsors::models::{NewSponsor, Sponsor};
-use crate::api::resources::users::models::{User, NewUser};
+use crate::api::resources::tags::models::Tag;
+use crate::api::resources::templates::models::{DetailedTemplate, NewTemplate, Template};
+use crate::api::resources::users::models::{EmailOwner, User, NewUser};
 use crate::api::resources::wishes::models::{NewWish, Wish, WishProduct};
 use crate::api::resources::wishlists::models::{Wishlist, NewWishlist};
 use crate::api::auth;
+use crate::api::utils::sorting::SyncFilter;
+use crate::services::notifications::messages::Message;
 use diesel::result::Error;
+use chrono::Duration;
 
 pub trait DatabaseService: Send + Sync {
     fn user_repo(&self) -> Box<dyn UserRepo>;
@@ -21,32 +34,78 @@ pub trait DatabaseService: Send + Sync {
     fn auth_repo(&self) -> Box<dyn AuthRepo>;
 
     fn follower_repo(&self) -> Box<dyn FollowerRepo>;
+
+    fn template_repo(&self) -> Box<dyn TemplateRepo>;
+
+    fn collaborator_repo(&self) -> Box<dyn CollaboratorRepo>;
+
+    fn share_link_repo(&self) -> Box<dyn ShareLinkRepo>;
+
+    fn search_repo(&self) -> Box<dyn SearchRepo>;
+
+    fn category_repo(&self) -> Box<dyn CategoryRepo>;
+
+    fn tag_repo(&self) -> Box<dyn TagRepo>;
+
+    fn image_repo(&self) -> Box<dyn ImageRepo>;
+
+    fn audit_repo(&self) -> Box<dyn AuditRepo>;
+
+    fn outbox_repo(&self) -> Box<dyn OutboxRepo>;
+
+    fn inbox_repo(&self) -> Box<dyn InboxRepo>;
 }
 
 pub trait UserRepo: Send + Sync {
-    fn list(&self) -> Result<Vec<User>, Error>;
+    /// Users are deleted for good, so `include_deleted` has nothing to add
+    fn list(&self, filter: &SyncFilter) -> Result<Vec<User>, Error>;
+
+    /// Reads no column added after the unique email migration, so it works while that one fails
+    fn list_email_owners(&self) -> Result<Vec<EmailOwner>, Error>;
 
     fn find_user(&self, id: i32) -> Result<User, Error>;
 
     fn insert(&self, new_user: NewUser) -> Result<User, Error>;
 
+    /// Inserts the user along with the message queued for them, both or neither
+    fn insert_notified(&self, new_user: NewUser, message: &dyn Fn(&User) -> Message) -> Result<User, Error>;
+
     fn insert_many(&self, users: Vec<NewUser>) ->Result<usize, Error>;
 }
 
 pub trait ProductRepo: Send + Sync {
     fn find_one(&self, id: i32) -> Result<Product, Error>;
 
-    fn list(&self) -> Result<Vec<ListedProduct>, Error>;
+    fn list(&self, filter: &ProductFilter) -> Result<Vec<ListedProduct>, Error>;
 
-    fn list_paginated(&self, page: i64, per_page: i64) -> Result<(i64, Vec<ListedProduct>), Error>;
+    fn list_paginated(&self, filter: &ProductFilter, page: i64, per_page: i64) -> Result<(i64, Vec<ListedProduct>), Error>;
 
     fn insert(&self, new_product: NewProduct) -> Result<Product, Error>;
 
+    /// Soft delete, the row is kept until purged
     fn delete(&self, id: i32) -> Result<usize, Error>;
 
+    /// Undoes a soft delete
+    fn restore(&self, id: i32) -> Result<usize, Error>;
+
+    /// Permanently removes the unreferenced products deleted more than `retention_days` ago, returns the images to discard
+    fn purge(&self, retention_days: i32) -> Result<(usize, Vec<Image>), Error>;
+
+    /// Records the change when the price or the availability differ
     fn update(&self, product: &Product) -> Result<Product, Error>;
 
+    /// Price and availability changes, oldest first
+    fn list_changes(&self, id: i32) -> Result<Vec<ProductChange>, Error>;
+
     fn insert_many(&self, products: Vec<NewProduct>) -> Result<usize, Error>;
+
+    fn list_by_review_status(&self, review_status: ReviewStatus) -> Result<Vec<Product>, Error>;
+
+    /// Points the wishes, templates, images and tags of the duplicates to the survivor and soft deletes them
+    fn merge(&self, survivor_id: i32, duplicate_ids: &[i32]) -> Result<usize, Error>;
+
+    /// Number of wishes pointing to the product, soft deleted ones too since they can be restored
+    fn count_wishes(&self, id: i32) -> Result<i64, Error>;
 }
 
 pub trait WishlistRepo: Send + Sync {
@@ -56,9 +115,18 @@ pub trait WishlistRepo: Send + Sync {
 
     fn insert_many(&self, wishlists: Vec<NewWishlist>) -> Result<usize, Error>;
 
+    fn insert_with_products(&self, new_wishlist: NewWishlist, product_ids: Vec<i32>) -> Result<Wishlist, Error>;
+
     fn update(&self, wishlist: &Wishlist) -> Result<Wishlist, Error>;
 
+    /// Soft delete, the row is kept until purged
     fn delete(&self, id: i32) -> Result<usize, Error>;
+
+    /// Undoes a soft delete
+    fn restore(&self, id: i32) -> Result<usize, Error>;
+
+    /// Permanently removes the wishlists deleted more than `retention_days` ago, returns the images to discard
+    fn purge(&self, retention_days: i32) -> Result<(usize, Vec<Image>), Error>;
 }
 
 pub trait WishRepo: Send + Sync {
@@ -70,13 +138,25 @@ pub trait WishRepo: Send + Sync {
 
     fn find_one_expanded(&self, id: i32) -> Result<WishProduct, Error>;
 
+    /// Soft delete, the row is kept until purged
     fn delete(&self, id: i32) -> Result<usize, Error>;
+
+    /// Undoes a soft delete
+    fn restore(&self, id: i32) -> Result<usize, Error>;
+
+    /// Permanently removes the wishes deleted more than `retention_days` ago, along with their sponsors
+    fn purge(&self, retention_days: i32) -> Result<usize, Error>;
+
+    fn reorder(&self, wishlist_id: i32, wish_ids: &[i32]) -> Result<usize, Error>;
 }
 
 pub trait SponsorRepo: Send + Sync {
-    fn insert(&self, new_sponsor: NewSponsor) -> Result<Sponsor, Error>;
+    /// Also tells whether this sponsor is the one reaching the price of the wished product,
+    /// decided while the wish is locked so concurrent sponsors can't both fund it
+    fn insert(&self, new_sponsor: NewSponsor) -> Result<(Sponsor, bool), Error>;
 
-    fn list_by_wish(&self, wish_id: i32) -> Result<Vec<Sponsor>, Error>;
+    /// Sponsors are deleted for good, so `include_deleted` has nothing to add
+    fn list_by_wish(&self, wish_id: i32, filter: &SyncFilter) -> Result<Vec<Sponsor>, Error>;
 }
 
 pub trait AuthRepo: Send {
@@ -91,4 +171,136 @@ pub trait FollowerRepo: Send + Sync {
     fn update(&self, follower: &Follower) -> Result<Follower, Error>;
 
     fn delete(&self, id: i32) -> Result<usize, Error>;
+
+    fn is_following(&self, wishlist_id: i32, user_id: i32) -> Result<bool, Error>;
+}
+
+pub trait TemplateRepo: Send + Sync {
+    fn list(&self) -> Result<Vec<Template>, Error>;
+
+    fn find_one(&self, id: i32) -> Result<DetailedTemplate, Error>;
+
+    fn insert(&self, new_template: NewTemplate, product_ids: Vec<i32>) -> Result<Template, Error>;
+
+    fn update(&self, template: &Template, product_ids: Option<Vec<i32>>) -> Result<Template, Error>;
+
+    fn delete(&self, id: i32) -> Result<usize, Error>;
+}
+
+pub trait CollaboratorRepo: Send + Sync {
+    fn list_by_wishlist(&self, wishlist_id: i32) -> Result<Vec<Collaborator>, Error>;
+
+    fn find_one(&self, wishlist_id: i32, user_id: i32) -> Result<Collaborator, Error>;
+
+    fn insert(&self, new_collaborator: NewCollaborator) -> Result<Collaborator, Error>;
+
+    fn delete(&self, wishlist_id: i32, user_id: i32) -> Result<usize, Error>;
+}
+
+pub trait ShareLinkRepo: Send + Sync {
+    fn list_by_wishlist(&self, wishlist_id: i32) -> Result<Vec<ShareLink>, Error>;
+
+    /// Finds a link by token, ignoring revoked and expired ones
+    fn find_active(&self, token: &str) -> Result<ShareLink, Error>;
+
+    fn insert(&self, new_share_link: NewShareLink) -> Result<ShareLink, Error>;
+
+    fn revoke(&self, wishlist_id: i32, id: i32) -> Result<usize, Error>;
+}
+
+pub trait SearchRepo: Send + Sync {
+    /// Ranked hits across the catalog and the public wishlists, with the total of entries
+    fn search(&self, language: SearchLanguage, q: &str, page: i64, per_page: i64) -> Result<(i64, Vec<SearchHit>), Error>;
+}
+
+pub trait CategoryRepo: Send + Sync {
+    fn list(&self) -> Result<Vec<Category>, Error>;
+
+    fn find_one(&self, id: i32) -> Result<Category, Error>;
+
+    fn find_by_slug(&self, slug: &str) -> Result<Category, Error>;
+
+    /// Returns the category with the same slug, inserting it when missing
+    fn find_or_insert(&self, new_category: NewCategory) -> Result<Category, Error>;
+
+    fn insert(&self, new_category: NewCategory) -> Result<Category, Error>;
+
+    fn update(&self, category: &Category) -> Result<Category, Error>;
+
+    fn delete(&self, id: i32) -> Result<usize, Error>;
+
+    /// The category along with all its subcategories, at any depth
+    fn descendant_ids(&self, id: i32) -> Result<Vec<i32>, Error>;
+}
+
+pub trait TagRepo: Send + Sync {
+    fn list(&self) -> Result<Vec<Tag>, Error>;
+
+    fn list_by_product(&self, product_id: i32) -> Result<Vec<Tag>, Error>;
+
+    /// Replaces the tags of the product, creating the ones that don't exist yet
+    fn set_product_tags(&self, product_id: i32, names: &[String]) -> Result<Vec<Tag>, Error>;
+
+    fn delete(&self, id: i32) -> Result<usize, Error>;
+}
+
+pub trait ImageRepo: Send + Sync {
+    fn find_one(&self, id: i32) -> Result<Image, Error>;
+
+    fn list_by_product(&self, product_id: i32) -> Result<Vec<Image>, Error>;
+
+    fn find_wishlist_cover(&self, wishlist_id: i32) -> Result<Image, Error>;
+
+    fn insert(&self, new_image: NewImage) -> Result<Image, Error>;
+
+    /// Sets the cover of the wishlist, returning the previous one if any
+    fn replace_wishlist_cover(&self, new_image: NewImage) -> Result<(Image, Option<Image>), Error>;
+
+    fn delete(&self, id: i32) -> Result<usize, Error>;
+}
+
+pub trait AuditRepo: Send + Sync {
+    fn insert(&self, new_log: NewAuditLog) -> Result<AuditLog, Error>;
+
+    fn list(&self, filter: &AuditFilter) -> Result<Vec<AuditLog>, Error>;
+
+    fn list_paginated(&self, filter: &AuditFilter, page: i64, per_page: i64) -> Result<(i64, Vec<AuditLog>), Error>;
+}
+
+pub trait OutboxRepo: Send + Sync {
+    fn insert(&self, new_entry: NewOutboxEntry) -> Result<OutboxEntry, Error>;
+
+    fn list(&self, status: Option<OutboxStatus>) -> Result<Vec<OutboxEntry>, Error>;
+
+    fn list_paginated(&self, status: Option<OutboxStatus>, page: i64, per_page: i64) -> Result<(i64, Vec<OutboxEntry>), Error>;
+
+    fn summary(&self) -> Result<OutboxSummary, Error>;
+
+    /// Pending entries due by now, kept from other workers for the lease
+    fn claim_due(&self, limit: i64, lease: Duration) -> Result<Vec<OutboxEntry>, Error>;
+
+    fn mark_sent(&self, id: i32) -> Result<usize, Error>;
+
+    /// Records the failed attempt, trying again after the delay
+    fn reschedule(&self, id: i32, error: &str, delay: Duration) -> Result<usize, Error>;
+
+    /// Records the last failed attempt, it isn't retried anymore
+    fn mark_failed(&self, id: i32, error: &str) -> Result<usize, Error>;
+
+    /// Queues a failed entry again from scratch
+    fn retry(&self, id: i32) -> Result<usize, Error>;
+}
+
+pub trait InboxRepo: Send + Sync {
+    fn insert(&self, new_notification: NewNotification) -> Result<Notification, Error>;
+
+    /// The user's notifications, the latest first
+    fn list_paginated(&self, user_id: i32, unread: bool, page: i64, per_page: i64) -> Result<(i64, Vec<Notification>), Error>;
+
+    fn count_unread(&self, user_id: i32) -> Result<i64, Error>;
+
+    /// Only the user's own notification, zero when it isn't theirs
+    fn mark_read(&self, user_id: i32, id: i32) -> Result<usize, Error>;
+
+    fn mark_all_read(&self, user_id: i32) -> Result<usize, Error>;
 }
@@ -0,0 +1,252 @@
+use std::fmt;
+use std::str::FromStr;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use crate::api::utils::escape_html;
+use crate::api::utils::formatters::date;
+
+/// Languages the notifications are written in
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Es];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+        }
+    }
+
+    /// First supported language of an `Accept-Language` header, ignoring weights
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        header.split(',')
+            .filter_map(|language| language.split(';').next())
+            .find_map(|language| language.trim().parse().ok())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Takes the language of tags like `es-AR`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let language = value.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+
+        Self::ALL.into_iter()
+            .find(|locale| locale.code() == language)
+            .ok_or_else(|| format!("Unsupported locale `{value}`"))
+    }
+}
+
+impl<'a> From<&'a str> for Locale {
+    fn from(value: &'a str) -> Self {
+        value.parse().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Catalog of the notifications sent to users
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Message {
+    AccountActivation { token: String },
+    PasswordReset { token: String },
+    NewFollower { follower: String, wishlist: String },
+    WishSponsored { sponsor: String, product: String, wishlist: String },
+    WishFunded { product: String, wishlist: String },
+    EventReminder {
+        wishlist: String,
+        #[serde(with = "date")]
+        date: NaiveDateTime,
+    },
+}
+
+/// A message written for a recipient, ready for any transport
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedMessage {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// `{name}` placeholders are replaced by the parameters of the message and the recipient's name
+struct Template {
+    subject: &'static str,
+    body: &'static str,
+}
+
+impl Message {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Message::AccountActivation { .. } => "account_activation",
+            Message::PasswordReset { .. } => "password_reset",
+            Message::NewFollower { .. } => "new_follower",
+            Message::WishSponsored { .. } => "wish_sponsored",
+            Message::WishFunded { .. } => "wish_funded",
+            Message::EventReminder { .. } => "event_reminder",
+        }
+    }
+
+    /// Shown in the app inbox too, account messages are only useful before logging in
+    pub fn in_app(&self) -> bool {
+        !matches!(self, Message::AccountActivation { .. } | Message::PasswordReset { .. })
+    }
+
+    pub fn render(&self, recipient: &str, locale: Locale) -> RenderedMessage {
+        let Template { subject, body } = self.template(locale);
+
+        let mut params = self.params(locale);
+        params.push(("name", recipient.to_string()));
+
+        let paragraphs: Vec<String> = body.split("\n\n")
+            .map(|paragraph| format!("<p>{}</p>", fill(paragraph, &params, escape_html).replace('\n', "<br>\n")))
+            .collect();
+
+        RenderedMessage {
+            subject: fill(subject, &params, str::to_string),
+            text: fill(body, &params, str::to_string),
+            html: format!("<!DOCTYPE html>\n<html lang=\"{locale}\">\n<body>\n{}\n</body>\n</html>\n", paragraphs.join("\n")),
+        }
+    }
+
+    fn params(&self, locale: Locale) -> Vec<(&'static str, String)> {
+        match self {
+            Message::AccountActivation { token } | Message::PasswordReset { token } => vec![("token", token.clone())],
+            Message::NewFollower { follower, wishlist } => vec![("follower", follower.clone()), ("wishlist", wishlist.clone())],
+            Message::WishSponsored { sponsor, product, wishlist } => vec![("sponsor", sponsor.clone()), ("product", product.clone()), ("wishlist", wishlist.clone())],
+            Message::WishFunded { product, wishlist } => vec![("product", product.clone()), ("wishlist", wishlist.clone())],
+            Message::EventReminder { wishlist, date } => {
+                let format = match locale {
+                    Locale::En => "%m/%d/%Y",
+                    Locale::Es => "%d/%m/%Y",
+                };
+
+                vec![("wishlist", wishlist.clone()), ("date", date.format(format).to_string())]
+            },
+        }
+    }
+
+    fn template(&self, locale: Locale) -> Template {
+        match (self, locale) {
+            (Message::AccountActivation { .. }, Locale::En) => Template {
+                subject: "Activate your Fishbowl account",
+                body: "Hi {name},\n\nWelcome to Fishbowl! Use this code to activate your account:\n{token}",
+            },
+            (Message::AccountActivation { .. }, Locale::Es) => Template {
+                subject: "Activa tu cuenta de Fishbowl",
+                body: "Hola {name},\n\n¡Te damos la bienvenida a Fishbowl! Usa este código para activar tu cuenta:\n{token}",
+            },
+            (Message::PasswordReset { .. }, Locale::En) => Template {
+                subject: "Reset your Fishbowl password",
+                body: "Hi {name},\n\nUse this code to choose a new password:\n{token}\n\nIf you didn't ask for it, you can ignore this email.",
+            },
+            (Message::PasswordReset { .. }, Locale::Es) => Template {
+                subject: "Restablece tu contraseña de Fishbowl",
+                body: "Hola {name},\n\nUsa este código para elegir una nueva contraseña:\n{token}\n\nSi no lo pediste, puedes ignorar este correo.",
+            },
+            (Message::NewFollower { .. }, Locale::En) => Template {
+                subject: "{follower} follows {wishlist}",
+                body: "Hi {name},\n\n{follower} started following your wishlist {wishlist}.",
+            },
+            (Message::NewFollower { .. }, Locale::Es) => Template {
+                subject: "{follower} sigue {wishlist}",
+                body: "Hola {name},\n\n{follower} empezó a seguir tu lista {wishlist}.",
+            },
+            (Message::WishSponsored { .. }, Locale::En) => Template {
+                subject: "{sponsor} sponsors {product}",
+                body: "Hi {name},\n\n{sponsor} is chipping in for {product} from your wishlist {wishlist}.",
+            },
+            (Message::WishSponsored { .. }, Locale::Es) => Template {
+                subject: "{sponsor} apadrina {product}",
+                body: "Hola {name},\n\n{sponsor} está colaborando con {product} de tu lista {wishlist}.",
+            },
+            (Message::WishFunded { .. }, Locale::En) => Template {
+                subject: "{product} is fully funded",
+                body: "Hi {name},\n\nThe sponsors of {wishlist} have covered the whole price of {product}.",
+            },
+            (Message::WishFunded { .. }, Locale::Es) => Template {
+                subject: "{product} está totalmente financiado",
+                body: "Hola {name},\n\nLos padrinos de {wishlist} cubrieron el precio completo de {product}.",
+            },
+            (Message::EventReminder { .. }, Locale::En) => Template {
+                subject: "{wishlist} is coming up on {date}",
+                body: "Hi {name},\n\nThis is a reminder that {wishlist} takes place on {date}.",
+            },
+            (Message::EventReminder { .. }, Locale::Es) => Template {
+                subject: "{wishlist} se acerca, es el {date}",
+                body: "Hola {name},\n\nTe recordamos que {wishlist} es el {date}.",
+            },
+        }
+    }
+}
+
+/// Formats the template and each parameter alike, so HTML escaping covers both
+fn fill(template: &str, params: &[(&str, String)], format: fn(&str) -> String) -> String {
+    params.iter().fold(format(template), |text, (key, value)| text.replace(&format!("{{{key}}}"), &format(value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_locales() {
+        assert_eq!("es-AR".parse::<Locale>(), Ok(Locale::Es));
+        assert_eq!("EN_us".parse::<Locale>(), Ok(Locale::En));
+        assert!("fr".parse::<Locale>().is_err());
+        assert_eq!(Locale::from("fr"), Locale::En, "unsupported locales fall back to english");
+        assert_eq!(Locale::from_accept_language("fr-CH, fr;q=0.9, es;q=0.8, en;q=0.7"), Some(Locale::Es));
+        assert_eq!(Locale::from_accept_language("fr"), None);
+    }
+
+    #[test]
+    fn render_messages() {
+        let message = Message::NewFollower { follower: "Ana <3".to_string(), wishlist: "Birthday".to_string() };
+
+        let english = message.render("Jane", Locale::En);
+        assert_eq!(english.subject, "Ana <3 follows Birthday");
+        assert_eq!(english.text, "Hi Jane,\n\nAna <3 started following your wishlist Birthday.");
+        assert!(english.html.contains("<p>Ana &lt;3 started following your wishlist Birthday.</p>"), "parameters are escaped");
+
+        let spanish = message.render("Jane", Locale::Es);
+        assert_eq!(spanish.subject, "Ana <3 sigue Birthday");
+        assert!(spanish.html.contains("<html lang=\"es\">"));
+
+        let reminder = Message::EventReminder { wishlist: "Birthday".to_string(), date: NaiveDateTime::parse_from_str("2026-12-31 20:00:00", "%Y-%m-%d %H:%M:%S").unwrap() };
+        assert_eq!(reminder.render("Jane", Locale::En).subject, "Birthday is coming up on 12/31/2026");
+        assert_eq!(reminder.render("Jane", Locale::Es).subject, "Birthday se acerca, es el 31/12/2026");
+
+        for locale in Locale::ALL {
+            let rendered = Message::AccountActivation { token: "abc".to_string() }.render("Jane", locale);
+            assert!(rendered.text.contains("abc") && rendered.html.contains("abc") && !rendered.text.contains('{'));
+        }
+    }
+
+    #[test]
+    fn serialize_messages() {
+        let message = Message::WishFunded { product: "Lamp".to_string(), wishlist: "Home".to_string() };
+
+        let json = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(json, serde_json::json!({ "kind": "wish_funded", "product": "Lamp", "wishlist": "Home" }));
+        assert_eq!(serde_json::from_value::<Message>(json).unwrap(), message);
+    }
+
+    #[test]
+    fn in_app_messages() {
+        assert!(!Message::PasswordReset { token: "abc".to_string() }.in_app());
+        assert!(Message::WishFunded { product: "Lamp".to_string(), wishlist: "Home".to_string() }.in_app());
+    }
+}
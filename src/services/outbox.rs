@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration as StdDuration;
+use chrono::Duration;
+use diesel::result::Error;
+use crate::api::resources::outbox::models::{backoff, OutboxEntry};
+use super::database::contracts::DatabaseService;
+use super::notifications::contracts::Notifier;
+
+/// Attempts per notification before it's marked as failed
+pub const MAX_ATTEMPTS: i32 = 8;
+
+const BATCH_SIZE: i64 = 50;
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(5);
+
+/// Delivers the queued notifications with the configured notifier, retrying them with exponential backoff
+pub struct OutboxWorker {
+    database: Arc<dyn DatabaseService>,
+    notifier: Arc<dyn Notifier>,
+}
+
+/// What a batch did with its entries
+#[derive(Debug, Default, PartialEq)]
+pub struct OutboxRun {
+    pub sent: usize,
+    pub retried: usize,
+    pub failed: usize,
+}
+
+enum DeliveryFailure {
+    /// Worth another attempt, e.g. the mail server is down
+    Transient(String),
+    /// Will never go through, e.g. the recipient is gone
+    Permanent(String),
+}
+
+impl OutboxWorker {
+    pub fn new(database: Arc<dyn DatabaseService>, notifier: Arc<dyn Notifier>) -> Self {
+        Self { database, notifier }
+    }
+
+    /// Processes the due entries in the background, for as long as the process lives
+    pub fn spawn(self) -> thread::JoinHandle<()> {
+        thread::spawn(move || loop {
+            if let Err(error) = self.run_once() {
+                tracing::error!("Processing the notification outbox failed: {error}");
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        })
+    }
+
+    /// Delivers one batch of due entries
+    pub fn run_once(&self) -> Result<OutboxRun, Error> {
+        let repo = self.database.outbox_repo();
+
+        // claimed entries are skipped by other workers until the lease is over, in case this one dies
+        let entries = repo.claim_due(BATCH_SIZE, Duration::minutes(5))?;
+
+        let mut run = OutboxRun::default();
+
+        for entry in entries {
+            let attempts = entry.attempts + 1;
+
+            match self.deliver(&entry) {
+                Ok(()) => {
+                    repo.mark_sent(entry.id)?;
+                    run.sent += 1;
+                },
+                Err(DeliveryFailure::Transient(error)) if attempts < MAX_ATTEMPTS => {
+                    tracing::warn!("Notification {} failed, attempt {attempts}: {error}", entry.id);
+
+                    repo.reschedule(entry.id, &error, backoff(attempts))?;
+                    run.retried += 1;
+                },
+                Err(DeliveryFailure::Transient(error) | DeliveryFailure::Permanent(error)) => {
+                    tracing::error!("Notification {} failed for good: {error}", entry.id);
+
+                    repo.mark_failed(entry.id, &error)?;
+                    run.failed += 1;
+                },
+            }
+        }
+
+        Ok(run)
+    }
+
+    fn deliver(&self, entry: &OutboxEntry) -> Result<(), DeliveryFailure> {
+        let message = entry.message()
+            .map_err(|error| DeliveryFailure::Permanent(format!("Invalid message: {error}")))?;
+
+        let recipient = self.database.user_repo().find_user(entry.user_id).map_err(|error| match error {
+            Error::NotFound => DeliveryFailure::Permanent(format!("User {} not found", entry.user_id)),
+            other => DeliveryFailure::Transient(other.to_string()),
+        })?;
+
+        self.notifier.send(&recipient, &message)
+            .map_err(|error| DeliveryFailure::Transient(error.to_string()))
+    }
+}
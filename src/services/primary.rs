@@ -1,12 +1,28 @@
-use super::notifications::ConsoleNotifier;
+use crate::Config;
+use super::notifications::{ConsoleNotifier, EmailNotifier, MailTransport};
 use super::database::MainDatabase;
+use super::storage::LocalStorage;
 use super::{InjectableServices, ServiceInjector};
 
-pub fn service_injector() -> ServiceInjector {
+pub fn service_injector(config: &Config) -> ServiceInjector {
+    let mail = config.mail();
+
+    if let MailTransport::Console = mail.transport {
+        return ServiceInjector::new(InjectableServices {
+            database: MainDatabase,
+            notifier: ConsoleNotifier,
+            storage: LocalStorage::from_env(),
+        });
+    }
+
+    let notifier = EmailNotifier::new(mail)
+        .unwrap_or_else(|error| panic!("Invalid mail configuration: {error}"));
+
     let injectable_services = InjectableServices {
         database: MainDatabase,
-        notifier: ConsoleNotifier,
+        notifier,
+        storage: LocalStorage::from_env(),
     };
 
     ServiceInjector::new(injectable_services)
-}
\ No newline at end of file
+}
@@ -0,0 +1,104 @@
+pub mod contracts {
+    use std::io;
+
+    pub trait Storage: Send + Sync {
+        /// Saves the content under the key, replacing any previous one
+        fn put(&self, key: &str, content: &[u8]) -> io::Result<()>;
+
+        fn get(&self, key: &str) -> io::Result<Vec<u8>>;
+
+        fn delete(&self, key: &str) -> io::Result<()>;
+
+        /// Public address where the content is served
+        fn url(&self, key: &str) -> String;
+    }
+}
+
+use std::{env, fs, io};
+use std::path::{Component, Path, PathBuf};
+use contracts::*;
+
+static DEFAULT_STORAGE_DIR: &str = "uploads";
+
+/// Keeps the files in a directory of the local filesystem, served from `/api/v1/uploads`
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Uses the directory in `STORAGE_DIR`, relative to the current one when not absolute
+    pub fn from_env() -> Self {
+        let dir = env::var("STORAGE_DIR").unwrap_or_else(|_| DEFAULT_STORAGE_DIR.to_string());
+
+        Self::new(env::current_dir().unwrap_or_default().join(dir))
+    }
+
+    /// Keys can't escape the root directory
+    fn path(&self, key: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(key);
+
+        let valid = relative.components().count() > 0
+            && relative.components().all(|c| matches!(c, Component::Normal(_)));
+
+        match valid {
+            true => Ok(self.root.join(relative)),
+            false => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid storage key `{key}`"))),
+        }
+    }
+}
+
+impl Storage for LocalStorage {
+    fn put(&self, key: &str, content: &[u8]) -> io::Result<()> {
+        let path = self.path(key)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, content)
+    }
+
+    fn get(&self, key: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.path(key)?)
+    }
+
+    fn delete(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(key)?) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("/api/v1/uploads/{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_storage() {
+        let root = env::temp_dir().join(format!("fishbowl-storage-{}", std::process::id()));
+        let storage = LocalStorage::new(&root);
+
+        storage.put("products/1/image.png", b"content").unwrap();
+
+        assert_eq!(storage.get("products/1/image.png").unwrap(), b"content");
+        assert_eq!(storage.url("products/1/image.png"), "/api/v1/uploads/products/1/image.png");
+        assert!(storage.get("../image.png").is_err(), "keys can't leave the root");
+        assert!(storage.put("/etc/image.png", b"content").is_err(), "keys can't be absolute");
+
+        storage.delete("products/1/image.png").unwrap();
+
+        assert!(storage.get("products/1/image.png").is_err());
+        assert!(storage.delete("products/1/image.png").is_ok(), "deleting twice is fine");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,8 +1,13 @@
 use std::{collections::HashMap, sync::{Arc, Mutex}};
+use chrono::NaiveDate;
 use salvo::{test::{ResponseExt, TestClient}, hyper::StatusCode};
 use fishbowl::api::responses::{CollectionResponse, ExecutionResponse};
+use fishbowl::api::resources::audit::models::AuditLog;
 use fishbowl::api::resources::users::models::User;
-use crate::utils::{get_user_and_token, prepare_api_service, Reporter};
+use fishbowl::api::resources::products::models::{Product, ReviewStatus};
+use fishbowl::api::resources::wishes::models::Wish;
+use fishbowl::api::resources::wishlists::models::{Visibility, Wishlist};
+use crate::utils::{timestamp, get_user_and_token, prepare_api_service, Reporter};
 
 use super::utils::{prepare_target, ServiceData,  BASE_URL, get_admin_and_token};
 
@@ -15,6 +20,9 @@ fn test_users() -> HashMap<String, User> {
         email: "admin@dummy.test".to_string(),
         role: "ADMIN".to_string(),
         active: true,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        locale: "en".to_string(),
     });
 
     map
@@ -80,6 +88,47 @@ async fn list_users() {
 
 }
 
+#[tokio::test]
+async fn list_users_changed_since() {
+    // -- setup
+
+    let admin_user = test_users().get("admin_user").unwrap().clone();
+    let at = |day: u32| NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(12, 0, 0).unwrap();
+
+    let user1 = User { created_at: at(1), updated_at: at(20), ..admin_user.clone() };
+    let user2 = User { id: 2, email: "user2@dummy.test".to_string(), created_at: at(3), updated_at: at(3), ..admin_user.clone() };
+    let user3 = User { id: 3, email: "user3@dummy.test".to_string(), created_at: at(2), updated_at: at(10), ..admin_user };
+
+    let target = prepare_target(ServiceData::with_users(vec![user1, user2, user3]));
+
+    let (_, auth_token) = get_admin_and_token();
+
+    let list = |query: &'static str| {
+        let (target, auth_token) = (&target, &auth_token);
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/admin/users?{query}"))
+                .add_header("authorization", format!("Bearer {auth_token}"), true)
+                .send(target)
+                .await
+                .take_json::<CollectionResponse<User>>()
+                .await
+                .unwrap()
+                .data
+        }
+    };
+
+    // -- run
+
+    let by_creation = list("sort=created_at&order=desc").await;
+    let changed = list("updated_since=2026-03-10%2012:00:00&sort=updated_at").await;
+
+    // -- assert
+
+    assert_eq!(by_creation.iter().map(|u| u.id).collect::<Vec<i32>>(), vec![2, 3, 1], "sort by creation, newest first");
+    assert_eq!(changed.iter().map(|u| u.id).collect::<Vec<i32>>(), vec![3, 1], "only the users updated since then");
+}
+
 #[tokio::test]
 async fn populate_wishlists() {
     // -- setup
@@ -112,3 +161,175 @@ async fn populate_wishlists() {
     assert_eq!(calls, 1, "wishlist_repo.insert_many() should be called once");
     assert_eq!(response_text.message, "Total row affected: 10", "total of insertions must match");
 }
+
+#[tokio::test]
+async fn restore_deleted_rows() {
+    // -- setup
+
+    let product = Product {
+        id: 1,
+        name: "Kite".to_string(),
+        description: None,
+        url: None,
+        price: 1500.0,
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let wishlist = Wishlist {
+        id: 1,
+        title: "Birthday".to_string(),
+        description: None,
+        date: None,
+        user_id: 2,
+        visibility: Visibility::Private,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let wish = Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 1500.0, created_at: timestamp(), updated_at: timestamp() };
+
+    let service_data = ServiceData::with_products(vec![product])
+        .wishlists(vec![wishlist])
+        .wishes(vec![wish]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, admin_token) = get_admin_and_token();
+
+    let restore = |path: &'static str, token: &str| {
+        let target = &target;
+        let token = token.to_string();
+
+        async move {
+            TestClient::put(format!("{BASE_URL}/admin/{path}/restore"))
+                .add_header("authorization", format!("Bearer {token}"), true)
+                .send(target)
+                .await
+                .status_code
+                .unwrap()
+        }
+    };
+
+    // -- run 1
+
+    let user_status = restore("wishlists/1", &user_token).await;
+    let product_status = restore("products/1", &admin_token).await;
+    let missing_status = restore("products/99", &admin_token).await;
+    let wishlist_status = restore("wishlists/1", &admin_token).await;
+    let wish_status = restore("wishes/1", &admin_token).await;
+
+    // -- assert 1
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(user_status, StatusCode::FORBIDDEN, "only admins restore");
+    assert_eq!(product_status, StatusCode::ACCEPTED);
+    assert_eq!(missing_status, StatusCode::NOT_FOUND);
+    assert_eq!(wishlist_status, StatusCode::ACCEPTED);
+    assert_eq!(wish_status, StatusCode::ACCEPTED);
+    assert_eq!(locked_reporter.get_fn_calls("product_repo.restore"), 2, "product_repo.restore() should be called twice");
+    assert_eq!(locked_reporter.get_fn_calls("wishlist_repo.restore"), 1, "wishlist_repo.restore() should be called once");
+    assert_eq!(locked_reporter.get_fn_calls("wish_repo.restore"), 1, "wish_repo.restore() should be called once");
+    assert_eq!(locked_reporter.get_fn_calls("audit_repo.insert.product.restore"), 1, "only the restored product gets audited");
+    assert_eq!(locked_reporter.get_fn_calls("audit_repo.insert"), 3, "every restore gets audited");
+}
+
+#[tokio::test]
+async fn list_audit_logs() {
+    // -- setup
+
+    let log = |id: i32, actor_id: i32, action: &str, resource_type: &str, created_at: &str| AuditLog {
+        id,
+        actor_id: Some(actor_id),
+        action: action.to_string(),
+        resource_type: resource_type.to_string(),
+        resource_id: Some(1),
+        changes: None,
+        ip: Some("127.0.0.1".to_string()),
+        created_at: chrono::NaiveDateTime::parse_from_str(created_at, "%Y-%m-%d %H:%M:%S").unwrap(),
+    };
+
+    let audit_logs = vec![
+        log(1, 2, "login", "user", "2026-01-01 10:00:00"),
+        log(2, 1, "update", "product", "2026-01-02 10:00:00"),
+        log(3, 2, "delete", "wishlist", "2026-01-03 10:00:00"),
+    ];
+
+    let service_data = ServiceData::default().audit_logs(audit_logs);
+
+    let target = prepare_target(service_data);
+
+    let (_, admin_token) = get_admin_and_token();
+
+    let (_, user_token) = get_user_and_token();
+
+    let list = |query: &'static str| {
+        let target = &target;
+        let bearer = format!("Bearer {admin_token}");
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/admin/audit{query}"))
+                .add_header("authorization", bearer, true)
+                .send(target)
+                .await
+                .take_json::<CollectionResponse<AuditLog>>()
+                .await
+                .unwrap()
+                .data
+                .into_iter()
+                .map(|log| log.id)
+                .collect::<Vec<i32>>()
+        }
+    };
+
+    // -- run 1
+
+    let all = list("").await;
+    let by_actor = list("?actor=2").await;
+    let by_resource = list("?resource=product&resource_id=1").await;
+    let by_range = list("?from=2026-01-02%2000:00:00&to=2026-01-02%2023:59:59").await;
+
+    // -- assert 1
+
+    assert_eq!(all, vec![3, 2, 1], "latest entries come first");
+    assert_eq!(by_actor, vec![3, 1], "filtered by actor");
+    assert_eq!(by_resource, vec![2], "filtered by resource");
+    assert_eq!(by_range, vec![2], "filtered by time range");
+
+    // -- run 2
+
+    let user_status = TestClient::get(format!("{BASE_URL}/admin/audit"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let anonymous_status = TestClient::get(format!("{BASE_URL}/admin/audit"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let invalid_status = TestClient::get(format!("{BASE_URL}/admin/audit?from=yesterday"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(user_status, StatusCode::FORBIDDEN, "A not admin user gets forbidden");
+    assert_eq!(anonymous_status, StatusCode::UNAUTHORIZED, "An unknown user get unauthorized");
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST, "dates must be valid");
+}
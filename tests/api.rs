@@ -4,3 +4,11 @@ mod wishlists;
 mod sponsors;
 mod admin;
 mod api_auth;
+mod templates;
+mod collaborators;
+mod shares;
+mod search;
+mod categories;
+mod images;
+mod outbox;
+mod inbox;
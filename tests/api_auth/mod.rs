@@ -1,9 +1,11 @@
 
 use std::sync::{Arc, Mutex};
 use salvo::test::{ResponseExt, TestClient};
+use serde_json::json;
+use fishbowl::api::errors::{ErrorCode, ErrorResponse};
 use fishbowl::api::resources::users::models::User;
 use fishbowl::api::responses::ResourceResponse;
-use super::utils::{prepare_api_service, ServiceData, BASE_URL, Reporter};
+use super::utils::{timestamp, prepare_api_service, ServiceData, BASE_URL, Reporter};
 
 #[tokio::test]
 async fn sighup() {
@@ -41,4 +43,149 @@ async fn sighup() {
     assert_eq!(status_code, 202, "status code should be 200");
     assert_eq!(calls, 1, "user_repo.insert() should be called once");
     assert_eq!(parsed_response.data.email, "axel@dummy.test", "email should match");
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn signup_with_json() {
+    // -- setup
+
+    let service_data = ServiceData::default();
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let body = json!({ "name": "Axel Rose", "email": "axel@dummy.test", "password": "patience" });
+
+    // -- run 1
+
+    let response = &mut TestClient::post(format!("{}/auth/signup", BASE_URL))
+        .json(&body)
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let parsed_response = response.take_json::<ResourceResponse<User>>()
+        .await
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(status_code, 202, "status code should be 202");
+    assert_eq!(parsed_response.data.email, "axel@dummy.test", "email should match");
+
+    // -- run 2
+
+    let status_code = TestClient::post(format!("{}/auth/signup", BASE_URL))
+        .json(&json!({ "name": "Axel Rose", "email": "axel@dummy.test", "password": null }))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(status_code, 422, "a null password is rejected");
+}
+
+#[tokio::test]
+async fn signup_with_taken_email() {
+    // -- setup
+
+    let user = User {
+        id: 1,
+        name: "Axel Rose".to_string(),
+        role: "USER".to_string(),
+        email: "axel@dummy.test".to_string(),
+        active: true,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        locale: "en".to_string(),
+    };
+
+    let service_data = ServiceData::with_users(vec![user]);
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let fields = [
+        ("name", "Axl"),
+        ("email", " Axel@Dummy.TEST "),
+        ("password", "patience"),
+    ];
+
+    // -- run 1
+
+    let response = &mut TestClient::post(format!("{}/auth/signup", BASE_URL))
+        .form(&fields)
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let parsed_response = response.take_json::<ErrorResponse>()
+        .await
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(status_code, 409, "the email is taken regardless of case");
+    assert_eq!(parsed_response.error.code, ErrorCode::EmailTaken);
+}
+
+#[tokio::test]
+async fn signup_in_user_locale() {
+    // -- setup
+
+    let service_data = ServiceData::default();
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    // -- run 1
+
+    let response = &mut TestClient::post(format!("{}/auth/signup", BASE_URL))
+        .add_header("accept-language", "fr-CH, es-AR;q=0.8, en;q=0.5", true)
+        .json(&json!({ "name": "Axel Rose", "email": "axel@dummy.test", "password": "patience" }))
+        .send(&target)
+        .await;
+
+    let parsed_response = response.take_json::<ResourceResponse<User>>()
+        .await
+        .unwrap();
+
+    let calls = reporter.lock()
+        .unwrap()
+        .get_fn_calls("outbox_repo.insert.account_activation");
+
+    // -- assert 1
+
+    assert_eq!(parsed_response.data.locale, "es", "the locale comes from the accept-language header");
+    assert_eq!(calls, 1, "the activation is queued, to be sent in the user's locale");
+
+    // -- run 2
+
+    let response = &mut TestClient::post(format!("{}/auth/signup", BASE_URL))
+        .add_header("accept-language", "es", true)
+        .json(&json!({ "name": "Axel Rose", "email": "axel@dummy.test", "password": "patience", "locale": "en-US" }))
+        .send(&target)
+        .await;
+
+    let parsed_response = response.take_json::<ResourceResponse<User>>()
+        .await
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(parsed_response.data.locale, "en", "the locale field wins over the header");
+
+    // -- run 3
+
+    let status_code = TestClient::post(format!("{}/auth/signup", BASE_URL))
+        .json(&json!({ "name": "Axel Rose", "email": "axel@dummy.test", "password": "patience", "locale": "fr" }))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 3
+
+    assert_eq!(status_code, 422, "unsupported locales are rejected");
+}
@@ -0,0 +1,295 @@
+use salvo::prelude::*;
+use salvo::test::{ResponseExt, TestClient};
+use std::sync::{Arc, Mutex};
+use fishbowl::api::resources::categories::models::{Category, ListedCategory};
+use fishbowl::api::resources::products::models::{DetailedProduct, ListedProduct, Product, ReviewStatus};
+use fishbowl::api::resources::tags::models::{ProductTag, Tag};
+use fishbowl::api::responses::{CollectionResponse, ResourceResponse};
+use super::utils::{timestamp, get_admin_and_token, get_user_and_token, prepare_api_service, prepare_target, ServiceData, BASE_URL, Reporter};
+
+fn test_categories() -> Vec<Category> {
+    vec![
+        Category { id: 1, name: "Games".to_string(), slug: "games".to_string(), parent_id: None },
+        Category { id: 2, name: "Consoles".to_string(), slug: "consoles".to_string(), parent_id: Some(1) },
+        Category { id: 3, name: "Handhelds".to_string(), slug: "handhelds".to_string(), parent_id: Some(2) },
+        Category { id: 4, name: "Books".to_string(), slug: "books".to_string(), parent_id: None },
+    ]
+}
+
+fn test_product(id: i32, category_id: Option<i32>) -> Product {
+    Product {
+        id,
+        name: format!("product {id}"),
+        description: None,
+        url: None,
+        price: 1000.0,
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+fn test_service_data() -> ServiceData {
+    let tags = vec![
+        Tag { id: 1, name: "retro".to_string() },
+        Tag { id: 2, name: "gift".to_string() },
+    ];
+
+    let product_tags = vec![
+        ProductTag { product_id: 1, tag_id: 1 },
+        ProductTag { product_id: 3, tag_id: 1 },
+        ProductTag { product_id: 3, tag_id: 2 },
+    ];
+
+    ServiceData::with_products(vec![
+        test_product(1, Some(1)),
+        test_product(2, Some(3)),
+        test_product(3, Some(4)),
+        test_product(4, None),
+    ])
+        .categories(test_categories())
+        .tags(tags, product_tags)
+}
+
+#[tokio::test]
+async fn filter_products_by_category_and_tag() {
+    // -- setup
+
+    let target = prepare_target(test_service_data());
+
+    let list_ids = |query: &'static str| {
+        let target = &target;
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/products?{query}"))
+                .send(target)
+                .await
+                .take_json::<CollectionResponse<ListedProduct>>()
+                .await
+                .unwrap()
+                .data
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<i32>>()
+        }
+    };
+
+    // -- run 1
+
+    let by_id = list_ids("category=1").await;
+    let by_slug = list_ids("category=consoles").await;
+    let unknown = list_ids("category=toys").await;
+    let by_tag = list_ids("tag=Retro").await;
+    let combined = list_ids("category=books&tag=gift").await;
+
+    // -- assert 1
+
+    assert_eq!(by_id, vec![1, 2], "a category includes its subcategories");
+    assert_eq!(by_slug, vec![2], "categories can be referenced by slug");
+    assert!(unknown.is_empty(), "an unknown category matches no product");
+    assert_eq!(by_tag, vec![1, 3], "tags are compared lowercased");
+    assert_eq!(combined, vec![3], "category and tag filters compose");
+
+    // -- run 2
+
+    let listed = TestClient::get(format!("{BASE_URL}/products?category=consoles"))
+        .send(&target)
+        .await
+        .take_json::<CollectionResponse<ListedProduct>>()
+        .await
+        .unwrap()
+        .data;
+
+    let uncategorized = TestClient::get(format!("{BASE_URL}/products"))
+        .send(&target)
+        .await
+        .take_json::<CollectionResponse<ListedProduct>>()
+        .await
+        .unwrap()
+        .data
+        .into_iter()
+        .find(|p| p.id == 4)
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(listed[0].category, Some(ListedCategory { id: 3, name: "Handhelds".to_string(), slug: "handhelds".to_string() }), "listed products embed their category");
+    assert_eq!(uncategorized.category, None, "products without category list none");
+}
+
+#[tokio::test]
+async fn show_product_with_category_and_tags() {
+    // -- setup
+
+    let target = prepare_target(test_service_data());
+
+    let (_, admin_token) = get_admin_and_token();
+
+    // -- run 1
+
+    let product = TestClient::get(format!("{BASE_URL}/products/3"))
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<DetailedProduct>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    assert_eq!(product.product.id, 3);
+    assert_eq!(product.category.map(|c| c.slug), Some("books".to_string()), "the category is included");
+    assert_eq!(product.tags.iter().map(|t| t.name.as_str()).collect::<Vec<&str>>(), vec!["retro", "gift"], "the tags are included");
+
+    // -- run 2
+
+    let status_code = TestClient::put(format!("{BASE_URL}/products/4"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .form(&[("category_id", "99")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(status_code, StatusCode::BAD_REQUEST, "products can only be filed under existing categories");
+}
+
+#[tokio::test]
+async fn manage_categories() {
+    // -- setup
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(ServiceData::default().categories(test_categories()), reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, admin_token) = get_admin_and_token();
+
+    // -- run 1
+
+    let user_status = TestClient::post(format!("{BASE_URL}/admin/categories"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .form(&[("name", "Toys")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let created = TestClient::post(format!("{BASE_URL}/admin/categories"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .form(&[("name", "Board Games"), ("parent_id", "1")])
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<Category>>()
+        .await
+        .unwrap()
+        .data;
+
+    let missing_parent_status = TestClient::post(format!("{BASE_URL}/admin/categories"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .form(&[("name", "Toys"), ("parent_id", "99")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(user_status, StatusCode::FORBIDDEN, "only admins manage categories");
+    assert_eq!(created, Category { id: 5, name: "Board Games".to_string(), slug: "board-games".to_string(), parent_id: Some(1) });
+    assert_eq!(missing_parent_status, StatusCode::BAD_REQUEST, "the parent category must exist");
+
+    // -- run 2
+
+    let cycle_status = TestClient::put(format!("{BASE_URL}/admin/categories/1"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .form(&[("parent_id", "3")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let moved = TestClient::put(format!("{BASE_URL}/admin/categories/3"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .form(&[("parent_id", "")])
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<Category>>()
+        .await
+        .unwrap()
+        .data;
+
+    let delete_status = TestClient::delete(format!("{BASE_URL}/admin/categories/4"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(cycle_status, StatusCode::BAD_REQUEST, "a category can't hang from its subcategories");
+    assert_eq!(moved.parent_id, None, "an empty parent moves the category to the top level");
+    assert_eq!(locked_reporter.get_fn_calls("category_repo.update"), 1, "category_repo.update() should be called once");
+    assert_eq!(delete_status, StatusCode::ACCEPTED);
+    assert_eq!(locked_reporter.get_fn_calls("category_repo.delete"), 1, "category_repo.delete() should be called once");
+}
+
+#[tokio::test]
+async fn set_product_tags() {
+    // -- setup
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(test_service_data(), reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, admin_token) = get_admin_and_token();
+
+    // -- run 1
+
+    let user_status = TestClient::put(format!("{BASE_URL}/admin/products/1/tags"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .form(&[("tags", "retro")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let tags = TestClient::put(format!("{BASE_URL}/admin/products/1/tags"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .form(&[("tags", " Retro, board games,retro ,")])
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<Vec<Tag>>>()
+        .await
+        .unwrap()
+        .data;
+
+    let missing_status = TestClient::put(format!("{BASE_URL}/admin/products/99/tags"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .form(&[("tags", "retro")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(user_status, StatusCode::FORBIDDEN, "only admins tag products");
+    assert_eq!(tags, vec![
+        Tag { id: 3, name: "board games".to_string() },
+        Tag { id: 1, name: "retro".to_string() },
+    ], "tags are normalized and created when missing");
+    assert_eq!(missing_status, StatusCode::NOT_FOUND, "the product must exist");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("tag_repo.set_product_tags"), 1, "tag_repo.set_product_tags() should be called once");
+}
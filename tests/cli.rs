@@ -6,7 +6,11 @@ use std::sync::{Arc, Mutex};
 use fishbowl::{self, Config};
 use fishbowl::cli;
 use cli::{Command, CommandProcessor};
-use utils::{ServiceData, TestDatabaseService, Reporter};
+use utils::{timestamp, ServiceData, TestDatabaseService, Reporter};
+use utils::test_storage::TestStorage;
+use fishbowl::api::resources::products::models::{Product, ReviewStatus};
+use fishbowl::api::resources::users::repo::email_owners;
+use diesel::pg::Pg;
 
 #[test]
 fn populate_products() {
@@ -22,6 +26,7 @@ fn populate_products() {
 
     let command_processor = CommandProcessor {
         database: Box::new(database),
+        storage: Box::new(TestStorage::new(reporter.clone())),
         config: Config::default(),
     };
 
@@ -54,6 +59,7 @@ fn list_users() {
 
     let command_processor = CommandProcessor {
         database: Box::new(database.clone()),
+        storage: Box::new(TestStorage::new(reporter.clone())),
         config: Config::default(),
     };
 
@@ -70,6 +76,68 @@ fn list_users() {
     assert_eq!(calls, 1, "user_repo.list() should be called once");
 }
 
+#[test]
+fn list_audit() {
+    // -- setup
+
+    let command = Command::List(cli::list::Target::Audit);
+
+    let service_data = ServiceData::default();
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let database = TestDatabaseService::with_reporter(service_data.clone(), reporter.clone());
+
+    let command_processor = CommandProcessor {
+        database: Box::new(database.clone()),
+        storage: Box::new(TestStorage::new(reporter.clone())),
+        config: Config::default(),
+    };
+
+    // -- run 1
+
+    command_processor.process(command);
+
+    let calls = reporter.lock()
+        .expect("Locking Reporter failed")
+        .get_fn_calls("audit_repo.list");
+
+    // -- assert 1
+
+    assert_eq!(calls, 1, "audit_repo.list() should be called once");
+}
+
+#[test]
+fn list_notifications() {
+    // -- setup
+
+    let command = Command::List(cli::list::Target::Notifications);
+
+    let service_data = ServiceData::default();
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let database = TestDatabaseService::with_reporter(service_data.clone(), reporter.clone());
+
+    let command_processor = CommandProcessor {
+        database: Box::new(database.clone()),
+        storage: Box::new(TestStorage::new(reporter.clone())),
+        config: Config::default(),
+    };
+
+    // -- run 1
+
+    command_processor.process(command);
+
+    let calls = reporter.lock()
+        .expect("Locking Reporter failed")
+        .get_fn_calls("outbox_repo.list");
+
+    // -- assert 1
+
+    assert_eq!(calls, 2, "the pending and the failed notifications should be listed");
+}
+
 #[test]
 fn populate_users() {
     // -- setup
@@ -84,6 +152,7 @@ fn populate_users() {
 
     let command_processor = CommandProcessor {
         database: Box::new(database.clone()),
+        storage: Box::new(TestStorage::new(reporter.clone())),
         config: Config::default(),
     };
 
@@ -114,6 +183,7 @@ fn populate_wishlists() {
 
     let command_processor = CommandProcessor {
         database: Box::new(database.clone()),
+        storage: Box::new(TestStorage::new(reporter.clone())),
         config: Config::default(),
     };
 
@@ -144,6 +214,7 @@ fn populate_all() {
 
     let command_processor = CommandProcessor {
         database: Box::new(database.clone()),
+        storage: Box::new(TestStorage::new(reporter.clone())),
         config: Config::default(),
     };
 
@@ -164,3 +235,122 @@ fn populate_all() {
     assert_eq!(user_calls, 1, "user_repo.insert_many() should be called once");
     assert_eq!(wishlist_calls, 1, "wishlist_repo.insert_many() should be called once");
 }
+
+#[test]
+fn list_duplicate_emails() {
+    // -- setup
+
+    let command = Command::build(&["fishbowl".to_string(), "duplicates".to_string(), "emails".to_string()])
+        .unwrap_or_else(|error| panic!("{}", error.message));
+
+    let service_data = ServiceData::default();
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let database = TestDatabaseService::with_reporter(service_data, reporter.clone());
+
+    let command_processor = CommandProcessor {
+        database: Box::new(database),
+        storage: Box::new(TestStorage::new(reporter.clone())),
+        config: Config::default(),
+    };
+
+    // -- run 1
+
+    command_processor.process(command);
+
+    // -- assert 1
+
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("user_repo.list_email_owners"), 1, "user_repo.list_email_owners() should be called once");
+}
+
+#[test]
+fn list_duplicate_emails_before_unique_emails() {
+    // -- run 1
+
+    let sql = diesel::debug_query::<Pg, _>(&email_owners()).to_string();
+
+    // -- assert 1
+
+    assert!(sql.contains("\"users\".\"email\""), "the report should read the emails: {}", sql);
+    assert!(!sql.contains("locale"), "locale is added after the unique email migration: {}", sql);
+    assert!(!sql.contains("created_at"), "the report shouldn't need the user timestamps: {}", sql);
+}
+
+#[test]
+fn merge_duplicates() {
+    // -- setup
+
+    let product = Product {
+        id: 1,
+        name: "Consola Sony Playstation 5".to_string(),
+        description: None,
+        url: None,
+        price: 2679999.0,
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let service_data = ServiceData::with_products(vec![product.clone(), Product { id: 2, ..product }]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let database = TestDatabaseService::with_reporter(service_data, reporter.clone());
+
+    let command_processor = CommandProcessor {
+        database: Box::new(database),
+        storage: Box::new(TestStorage::new(reporter.clone())),
+        config: Config::default(),
+    };
+
+    // -- run 1
+
+    command_processor.process(Command::Duplicates(cli::duplicates::Target::Merge(1, vec![1])));
+    command_processor.process(Command::Duplicates(cli::duplicates::Target::Merge(1, vec![2])));
+
+    let calls = reporter.lock()
+        .expect("Locking Reporter failed")
+        .get_fn_calls("product_repo.merge");
+
+    // -- assert 1
+
+    assert_eq!(calls, 1, "product_repo.merge() should be called once, a product can't be merged into itself");
+}
+
+#[test]
+fn purge_deleted_rows() {
+    // -- setup
+
+    let args = |retention: &str| vec!["fishbowl".to_string(), "purge".to_string(), retention.to_string()];
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let database = TestDatabaseService::with_reporter(ServiceData::default(), reporter.clone());
+
+    let command_processor = CommandProcessor {
+        database: Box::new(database),
+        storage: Box::new(TestStorage::new(reporter.clone())),
+        config: Config::default(),
+    };
+
+    // -- run 1
+
+    let invalid = Command::build(&args("a month"));
+
+    let command = Command::build(&args("90")).ok().unwrap();
+
+    command_processor.process(command);
+
+    // -- assert 1
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert!(invalid.is_err(), "the retention is a number of days");
+    assert_eq!(locked_reporter.get_fn_calls("wish_repo.purge"), 1, "wish_repo.purge() should be called once");
+    assert_eq!(locked_reporter.get_fn_calls("wishlist_repo.purge"), 1, "wishlist_repo.purge() should be called once");
+    assert_eq!(locked_reporter.get_fn_calls("product_repo.purge"), 1, "product_repo.purge() should be called once");
+}
@@ -0,0 +1,159 @@
+use std::sync::{Arc, Mutex};
+use salvo::test::{ResponseExt, TestClient};
+use fishbowl::api::resources::collaborators::models::Collaborator;
+use fishbowl::api::resources::products::models::{Product, ReviewStatus};
+use fishbowl::api::resources::wishlists::models::{Visibility, Wishlist};
+use fishbowl::api::resources::wishes::models::Wish;
+use fishbowl::api::responses::ResourceResponse;
+use crate::utils::{timestamp, get_admin_and_token, get_user_and_token, prepare_api_service, Reporter, ServiceData, BASE_URL};
+
+fn test_wishlist() -> Wishlist {
+    Wishlist {
+        id: 1,
+        title: "Wedding registry".to_string(),
+        description: None,
+        date: None,
+        user_id: 1,
+        visibility: Visibility::Private,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+#[tokio::test]
+async fn add_collaborator() {
+    // -- setup
+
+    let service_data = ServiceData::default()
+        .wishlists(vec![test_wishlist()]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, owner_token) = get_admin_and_token();
+
+    let (_, user_token) = get_user_and_token();
+
+    // -- run 1
+
+    let response = &mut TestClient::post(format!("{BASE_URL}/wishlists/1/collaborators"))
+        .add_header("authorization", format!("Bearer {owner_token}"), true)
+        .form(&[("user_id", "2"), ("role", "owner")])
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let collaborator = response.take_json::<ResourceResponse<Collaborator>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    assert_eq!(status_code, 202, "status code should be 202");
+    assert_eq!(collaborator.role, "OWNER", "role should be normalized");
+
+    // -- run 2
+
+    let response = TestClient::post(format!("{BASE_URL}/wishlists/1/collaborators"))
+        .add_header("authorization", format!("Bearer {owner_token}"), true)
+        .form(&[("user_id", "2"), ("role", "viewer")])
+        .send(&target)
+        .await;
+
+    // -- assert 2
+
+    assert_eq!(response.status_code.unwrap(), 400, "unknown roles are rejected");
+
+    // -- run 3
+
+    let response = TestClient::post(format!("{BASE_URL}/wishlists/1/collaborators"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .form(&[("user_id", "2")])
+        .send(&target)
+        .await;
+
+    let insert_calls = reporter.lock().unwrap().get_fn_calls("collaborator_repo.insert");
+
+    // -- assert 3
+
+    assert_eq!(response.status_code.unwrap(), 403, "a stranger can't add collaborators");
+    assert_eq!(insert_calls, 1, "collaborator_repo.insert() should be called once");
+}
+
+#[tokio::test]
+async fn editor_permissions() {
+    // -- setup
+
+    let editor = Collaborator { id: 1, wishlist_id: 1, user_id: 2, role: "EDITOR".to_string() };
+
+    let wishes = vec![
+        Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+    ];
+
+    let product = Product {
+        id: 1,
+        name: "Toaster".to_string(),
+        description: None,
+        url: None,
+        price: 2000.0,
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let service_data = ServiceData::with_products(vec![product])
+        .wishlists(vec![test_wishlist()])
+        .wishes(wishes)
+        .collaborators(vec![editor]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, editor_token) = get_user_and_token();
+
+    let bearer = format!("Bearer {editor_token}");
+
+    // -- run 1
+
+    let response = TestClient::post(format!("{BASE_URL}/wishlists/1/wishes"))
+        .add_header("authorization", &bearer, true)
+        .form(&[("wishlist_id", "1"), ("product_id", "1")])
+        .send(&target)
+        .await;
+
+    // -- assert 1
+
+    assert_eq!(response.status_code.unwrap(), 202, "an editor can add wishes");
+
+    // -- run 2
+
+    let response = TestClient::delete(format!("{BASE_URL}/wishlists/1"))
+        .add_header("authorization", &bearer, true)
+        .send(&target)
+        .await;
+
+    let delete_calls = reporter.lock().unwrap().get_fn_calls("wishlist_repo.delete");
+
+    // -- assert 2
+
+    assert_eq!(response.status_code.unwrap(), 403, "an editor can't delete the wishlist");
+    assert_eq!(delete_calls, 0, "wishlist_repo.delete() shouldn't be called");
+
+    // -- run 3
+
+    let response = TestClient::delete(format!("{BASE_URL}/wishlists/1/collaborators/2"))
+        .add_header("authorization", &bearer, true)
+        .send(&target)
+        .await;
+
+    // -- assert 3
+
+    assert_eq!(response.status_code.unwrap(), 202, "an editor can leave the wishlist");
+}
@@ -0,0 +1,222 @@
+use salvo::prelude::*;
+use salvo::test::{RequestBuilder, ResponseExt, TestClient};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use image::{DynamicImage, ImageOutputFormat};
+use fishbowl::api::resources::images::models::{Image, ImageResource};
+use fishbowl::api::resources::products::models::{Product, ReviewStatus};
+use fishbowl::api::resources::wishlists::models::{Visibility, Wishlist};
+use fishbowl::api::responses::ResourceResponse;
+use super::utils::{timestamp, get_admin_and_token, get_user_and_token, prepare_api_service, ServiceData, BASE_URL, Reporter};
+
+static BOUNDARY: &str = "fishbowl-boundary";
+
+fn png() -> Vec<u8> {
+    let mut content = Cursor::new(vec![]);
+
+    DynamicImage::new_rgb8(640, 480).write_to(&mut content, ImageOutputFormat::Png).unwrap();
+
+    content.into_inner()
+}
+
+/// Multipart body with the file under the `image` field
+fn multipart(content_type: &str, content: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"image\"\r\nContent-Type: {content_type}\r\n\r\n"
+    ).into_bytes();
+
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+    body
+}
+
+fn upload(method: &str, url: String, token: &str, content_type: &str, content: &[u8]) -> RequestBuilder {
+    let client = match method {
+        "PUT" => TestClient::put(url),
+        _ => TestClient::post(url),
+    };
+
+    client
+        .add_header("authorization", format!("Bearer {token}"), true)
+        .add_header("content-type", format!("multipart/form-data; boundary={BOUNDARY}"), true)
+        .bytes(multipart(content_type, content))
+}
+
+fn test_product() -> Product {
+    Product {
+        id: 1,
+        name: "Kite".to_string(),
+        description: None,
+        url: None,
+        price: 1500.0,
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+#[tokio::test]
+async fn upload_product_image() {
+    // -- setup
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(ServiceData::with_products(vec![test_product()]), reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, admin_token) = get_admin_and_token();
+
+    let url = format!("{BASE_URL}/products/1/images");
+
+    // -- run 1
+
+    let user_status = upload("POST", url.clone(), &user_token, "image/png", &png())
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let unsupported_status = upload("POST", url.clone(), &admin_token, "image/gif", &png())
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let mismatched_status = upload("POST", url.clone(), &admin_token, "image/jpeg", &png())
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(user_status, StatusCode::FORBIDDEN, "only admins add product images");
+    assert_eq!(unsupported_status, StatusCode::BAD_REQUEST, "only png, jpeg and webp images");
+    assert_eq!(mismatched_status, StatusCode::BAD_REQUEST, "the content must match its type");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("storage.put"), 0, "storage.put() should not be called");
+
+    // -- run 2
+
+    let image = upload("POST", url, &admin_token, "image/png", &png())
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<ImageResource>>()
+        .await
+        .unwrap()
+        .data;
+
+    let mut response = TestClient::get(format!("http://localhost{}", image.url))
+        .send(&target)
+        .await;
+
+    let thumbnail_status = TestClient::get(format!("http://localhost{}", image.thumbnail_url))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let missing_status = TestClient::get(format!("{BASE_URL}/uploads/products/1/missing.png"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert!(image.url.starts_with("/api/v1/uploads/products/1/"), "images are stored under the product");
+    assert!(image.thumbnail_url.ends_with("_thumb.png"), "thumbnails are generated");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("storage.put"), 2, "the image and its thumbnail are stored");
+    assert_eq!(response.status_code.unwrap(), StatusCode::OK);
+    assert_eq!(response.headers().get("content-type").unwrap(), "image/png");
+    assert_eq!(response.take_bytes(None).await.unwrap().to_vec(), png(), "uploads are served as stored");
+    assert_eq!(thumbnail_status, StatusCode::OK);
+    assert_eq!(missing_status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn replace_wishlist_cover() {
+    // -- setup
+
+    let wishlist = Wishlist {
+        id: 1,
+        title: "Birthday".to_string(),
+        description: None,
+        date: None,
+        user_id: 2,
+        visibility: Visibility::Public,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let cover = Image {
+        id: 1,
+        product_id: None,
+        wishlist_id: Some(1),
+        content_type: "image/png".to_string(),
+        path: "wishlists/1/old.png".to_string(),
+        thumbnail_path: "wishlists/1/old_thumb.png".to_string(),
+    };
+
+    let service_data = ServiceData::with_wishlists(vec![wishlist]).images(vec![cover]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, admin_token) = get_admin_and_token();
+
+    let url = format!("{BASE_URL}/wishlists/1/cover");
+
+    // -- run 1
+
+    let shown = TestClient::get(url.clone())
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<ImageResource>>()
+        .await
+        .unwrap()
+        .data;
+
+    let admin_status = upload("PUT", url.clone(), &admin_token, "image/png", &png())
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let replaced = upload("PUT", url.clone(), &user_token, "image/png", &png())
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<ImageResource>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    assert_eq!(shown.thumbnail_url, "/api/v1/uploads/wishlists/1/old_thumb.png");
+    assert_eq!(admin_status, StatusCode::FORBIDDEN, "only owners and editors change the cover");
+    assert!(replaced.url.starts_with("/api/v1/uploads/wishlists/1/"));
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("image_repo.replace_wishlist_cover"), 1, "image_repo.replace_wishlist_cover() should be called once");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("storage.delete"), 2, "the previous cover files are discarded");
+
+    // -- run 2
+
+    let status_code = TestClient::delete(url)
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(status_code, StatusCode::ACCEPTED);
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("image_repo.delete"), 1, "image_repo.delete() should be called once");
+}
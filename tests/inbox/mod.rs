@@ -0,0 +1,118 @@
+use std::sync::{Arc, Mutex};
+use salvo::{test::{ResponseExt, TestClient}, hyper::StatusCode};
+use serde_json::json;
+use fishbowl::api::resources::inbox::models::{DetailedNotification, Notification, UnreadCount};
+use fishbowl::api::responses::{CollectionResponse, ResourceResponse};
+use crate::utils::{timestamp, user, get_user_and_token, prepare_api_service, Reporter, ServiceData, BASE_URL};
+
+fn notification(id: i32, user_id: i32, read: bool) -> Notification {
+    Notification {
+        id,
+        user_id,
+        kind: "wish_funded".to_string(),
+        message: json!({ "kind": "wish_funded", "product": "Kite", "wishlist": "Birthday" }),
+        read_at: read.then(timestamp),
+        created_at: timestamp(),
+    }
+}
+
+#[tokio::test]
+async fn list_notifications() {
+    // -- setup
+
+    let notifications = vec![
+        notification(1, 2, true),
+        notification(2, 2, false),
+        notification(3, 1, false),
+        notification(4, 2, false),
+    ];
+
+    let service_data = ServiceData::with_users(vec![user(2, "es")]).notifications(notifications);
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let list = |query: &'static str| {
+        let target = &target;
+        let bearer = format!("Bearer {user_token}");
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/me/notifications{query}"))
+                .add_header("authorization", bearer, true)
+                .send(target)
+                .await
+                .take_json::<CollectionResponse<DetailedNotification>>()
+                .await
+                .unwrap()
+                .data
+        }
+    };
+
+    // -- run
+
+    let all = list("").await;
+    let unread = list("?unread=true").await;
+
+    let count = TestClient::get(format!("{BASE_URL}/me/notifications/unread"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<UnreadCount>>()
+        .await
+        .unwrap()
+        .data;
+
+    let anonymous_status = TestClient::get(format!("{BASE_URL}/me/notifications"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert
+
+    let ids = |notifications: &[DetailedNotification]| notifications.iter().map(|notification| notification.id).collect::<Vec<i32>>();
+
+    assert_eq!(ids(&all), vec![4, 2, 1], "only the user's notifications, the latest first");
+    assert_eq!(ids(&unread), vec![4, 2], "filtered by unread");
+    assert!(all[2].read, "read notifications are flagged");
+    assert_eq!(all[0].subject, "Kite está totalmente financiado", "rendered in the user's locale");
+    assert!(all[0].text.starts_with("Hola User 2"), "addressed to the user");
+    assert_eq!(count, UnreadCount { unread: 2 });
+    assert_eq!(anonymous_status, StatusCode::UNAUTHORIZED, "only for authenticated users");
+}
+
+#[tokio::test]
+async fn read_notifications() {
+    // -- setup
+
+    let notifications = vec![
+        notification(1, 2, false),
+        notification(2, 1, false),
+    ];
+
+    let service_data = ServiceData::default().notifications(notifications);
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let read = |path: &'static str| TestClient::put(format!("{BASE_URL}/me/notifications/{path}"))
+        .add_header("authorization", format!("Bearer {user_token}"), true);
+
+    // -- run
+
+    let own_status = read("1/read").send(&target).await.status_code.unwrap();
+    let other_status = read("2/read").send(&target).await.status_code.unwrap();
+    let all_status = read("read").send(&target).await.status_code.unwrap();
+
+    // -- assert
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(own_status, StatusCode::ACCEPTED, "users read their notifications");
+    assert_eq!(other_status, StatusCode::NOT_FOUND, "but not someone else's");
+    assert_eq!(all_status, StatusCode::ACCEPTED);
+    assert_eq!(locked_reporter.get_fn_calls("inbox_repo.mark_read"), 2);
+    assert_eq!(locked_reporter.get_fn_calls("inbox_repo.mark_all_read"), 1);
+}
@@ -0,0 +1,163 @@
+use std::sync::{Arc, Mutex};
+use salvo::{test::{ResponseExt, TestClient}, hyper::StatusCode};
+use serde_json::json;
+use fishbowl::api::resources::outbox::models::{OutboxEntry, OutboxStatus, OutboxSummary};
+use fishbowl::api::responses::{CollectionResponse, ResourceResponse};
+use fishbowl::services::outbox::{OutboxRun, OutboxWorker, MAX_ATTEMPTS};
+use crate::utils::{timestamp, user, get_admin_and_token, get_user_and_token, prepare_api_service, Reporter, ServiceData, TestDatabaseService, BASE_URL};
+use crate::utils::test_notifier::TestNotifier;
+
+fn entry(id: i32, user_id: i32, status: OutboxStatus, attempts: i32) -> OutboxEntry {
+    OutboxEntry {
+        id,
+        user_id,
+        kind: "account_activation".to_string(),
+        message: json!({ "kind": "account_activation", "token": "abc" }),
+        status,
+        attempts,
+        last_error: None,
+        next_attempt_at: timestamp(),
+        sent_at: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+#[tokio::test]
+async fn list_outbox() {
+    // -- setup
+
+    let outbox = vec![
+        entry(1, 1, OutboxStatus::Sent, 1),
+        entry(2, 1, OutboxStatus::Failed, MAX_ATTEMPTS),
+        entry(3, 1, OutboxStatus::Pending, 2),
+    ];
+
+    let service_data = ServiceData::default().outbox(outbox);
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, admin_token) = get_admin_and_token();
+    let (_, user_token) = get_user_and_token();
+
+    let list = |query: &'static str| {
+        let target = &target;
+        let bearer = format!("Bearer {admin_token}");
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/admin/outbox{query}"))
+                .add_header("authorization", bearer, true)
+                .send(target)
+                .await
+                .take_json::<CollectionResponse<OutboxEntry>>()
+                .await
+                .unwrap()
+                .data
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect::<Vec<i32>>()
+        }
+    };
+
+    // -- run 1
+
+    let all = list("").await;
+    let failed = list("?status=failed").await;
+
+    let summary = TestClient::get(format!("{BASE_URL}/admin/outbox/summary"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<OutboxSummary>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    assert_eq!(all, vec![3, 2, 1], "latest entries come first");
+    assert_eq!(failed, vec![2], "filtered by status");
+    assert_eq!(summary, OutboxSummary { pending: 1, sent: 1, failed: 1 });
+
+    // -- run 2
+
+    let retry = |id: i32, token: &str| TestClient::put(format!("{BASE_URL}/admin/outbox/{id}/retry"))
+        .add_header("authorization", format!("Bearer {token}"), true);
+
+    let retried_status = retry(2, &admin_token).send(&target).await.status_code.unwrap();
+    let pending_status = retry(3, &admin_token).send(&target).await.status_code.unwrap();
+    let user_status = retry(2, &user_token).send(&target).await.status_code.unwrap();
+
+    let invalid_status = TestClient::get(format!("{BASE_URL}/admin/outbox?status=lost"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(retried_status, StatusCode::ACCEPTED, "failed entries can be retried");
+    assert_eq!(pending_status, StatusCode::NOT_FOUND, "only failed entries are retried");
+    assert_eq!(user_status, StatusCode::FORBIDDEN, "only admins retry");
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST, "the status must be valid");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("audit_repo.insert.notification.retry"), 1, "retries get audited");
+}
+
+#[test]
+fn deliver_outbox() {
+    // -- setup
+
+    let mut invalid = entry(3, 1, OutboxStatus::Pending, 0);
+    invalid.message = json!({ "kind": "unknown" });
+
+    let outbox = vec![
+        entry(1, 1, OutboxStatus::Pending, 0),
+        entry(2, 9, OutboxStatus::Pending, 0),
+        invalid,
+        entry(4, 1, OutboxStatus::Sent, 1),
+    ];
+
+    let service_data = ServiceData::with_users(vec![user(1, "es")]).outbox(outbox);
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+    let database = TestDatabaseService::with_reporter(service_data.clone(), reporter.clone());
+
+    let worker = OutboxWorker::new(Arc::new(database), Arc::new(TestNotifier::new(reporter.clone())));
+
+    // -- run 1
+
+    let run = worker.run_once().unwrap();
+
+    // -- assert 1
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(run, OutboxRun { sent: 1, retried: 0, failed: 2 });
+    assert_eq!(locked_reporter.get_fn_calls("notifier.send.account_activation.es"), 1, "sent in the recipient's locale");
+    assert_eq!(locked_reporter.get_fn_calls("outbox_repo.mark_sent"), 1);
+    assert_eq!(locked_reporter.get_fn_calls("outbox_repo.mark_failed"), 2, "missing users and invalid messages aren't retried");
+
+    drop(locked_reporter);
+
+    // -- run 2
+
+    let outbox = vec![
+        entry(1, 1, OutboxStatus::Pending, 0),
+        entry(2, 1, OutboxStatus::Pending, MAX_ATTEMPTS - 1),
+    ];
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+    let database = TestDatabaseService::with_reporter(service_data.outbox(outbox), reporter.clone());
+
+    let worker = OutboxWorker::new(Arc::new(database), Arc::new(TestNotifier::failing(reporter.clone())));
+
+    let run = worker.run_once().unwrap();
+
+    // -- assert 2
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(run, OutboxRun { sent: 0, retried: 1, failed: 1 });
+    assert_eq!(locked_reporter.get_fn_calls("outbox_repo.reschedule"), 1, "failed deliveries are retried later");
+    assert_eq!(locked_reporter.get_fn_calls("outbox_repo.mark_failed"), 1, "until the last attempt");
+}
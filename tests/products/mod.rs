@@ -3,38 +3,59 @@ use salvo::prelude::*;
 use salvo::test::{ResponseExt, TestClient};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use fishbowl::api::resources::products::models::{Product, ListedProduct};
+use chrono::NaiveDate;
+use serde_json::json;
+use fishbowl::api::resources::products::duplicates::DuplicateGroup;
+use fishbowl::api::resources::products::import::ImportedProduct;
+use fishbowl::api::resources::products::models::{Product, ProductChange, ListedProduct, ReviewStatus};
+use fishbowl::api::resources::wishes::models::{Wish, WishProduct};
+use fishbowl::api::resources::wishlists::models::{Visibility, Wishlist};
+use fishbowl::api::errors::{ErrorCode, ErrorResponse};
 use fishbowl::api::responses::{ResourceResponse, CollectionResponse, CollectionPaginatedResponse};
-use super::utils::{prepare_target, prepare_api_service, ServiceData, BASE_URL, Reporter};
+use super::utils::{timestamp, get_admin_and_token, get_user_and_token, prepare_target, prepare_api_service, ServiceData, BASE_URL, Reporter};
 
 fn test_products() -> HashMap<String, Product> {
     let mut map = HashMap::new();
     map.insert("product1".to_string(), Product {
         id: 1,
-        name: format!("product 1"),
-        description: Some(format!("desc")),
-        url: Some(format!("any url")),
+        name: "product 1".to_string(),
+        description: Some("desc".to_string()),
+        url: Some("https://shop.test/any".to_string()),
         price: 34000.6,
-        available: true
-
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map.insert("product2".to_string(), Product {
         id: 2,
-        name: format!("product 2"),
-        description: Some(format!("desc for 2")),
+        name: "product 2".to_string(),
+        description: Some("desc for 2".to_string()),
         url: None,
         price: 120000.6,
-        available: true
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map.insert("product3".to_string(), Product {
         id: 3,
-        name: format!("product 3"),
+        name: "product 3".to_string(),
         description: None,
-        url: Some(format!("any url")),
+        url: Some("https://shop.test/any".to_string()),
         price: 34000.0,
-        available: false
+        available: false,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map
@@ -163,6 +184,10 @@ async fn add_product() {
 
     let target = prepare_target(service_data.clone());
 
+    let (_, auth_token) = get_admin_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
     let Product { name, description, url, price, ..} = product1.clone();
 
     let fields = [
@@ -175,6 +200,7 @@ async fn add_product() {
     // -- run
 
     let response = TestClient::post(format!("{BASE_URL}/products"))
+        .add_header("authorization", &bearer, true)
         .form(&fields)
         .send(&target)
         .await
@@ -184,8 +210,80 @@ async fn add_product() {
 
     // -- assert
 
-    assert_eq!(response.data, Product { id: 1_i32, name, description, url, price, available: false });
+    assert_eq!(response.data, Product { id: 1_i32, name, description, url, price, available: false, review_status: ReviewStatus::Approved, submitted_by: Some(1), category_id: None, created_at: timestamp(), updated_at: timestamp() });
+
+}
+
+#[tokio::test]
+async fn add_product_with_invalid_fields() {
+    // -- setup
+
+    let target = prepare_target(ServiceData::default());
+
+    let (_, auth_token) = get_admin_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
+    let fields = [
+        ("name", ""),
+        ("url", "not a url"),
+        ("price", "cheap"),
+    ];
+
+    // -- run
+
+    let response = &mut TestClient::post(format!("{BASE_URL}/products"))
+        .add_header("authorization", &bearer, true)
+        .form(&fields)
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let body = response.take_json::<ErrorResponse>().await.unwrap();
+
+    // -- assert
+
+    assert_eq!(status_code, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body.error.code, ErrorCode::ValidationFailed);
+    assert_eq!(body.error.details, Some(json!([
+        { "field": "name", "code": "too_short", "message": "`name` cannot be empty" },
+        { "field": "url", "code": "invalid_url", "message": "`url` must be an http(s) url" },
+        { "field": "price", "code": "invalid_number", "message": "`price` is not valid: invalid float literal" },
+    ])), "every invalid field is reported at once");
+}
+
+#[tokio::test]
+async fn error_envelope() {
+    // -- setup
+
+    let target = prepare_target(ServiceData::default());
+
+    // -- run
+
+    let response = &mut TestClient::get(format!("{BASE_URL}/products/99"))
+        .add_header("x-request-id", "trace-99", true)
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
 
+    let body = response.take_json::<ErrorResponse>().await.unwrap();
+
+    let anonymous = TestClient::post(format!("{BASE_URL}/products"))
+        .send(&target)
+        .await
+        .take_json::<ErrorResponse>()
+        .await
+        .unwrap();
+
+    // -- assert
+
+    assert_eq!(status_code, StatusCode::NOT_FOUND);
+    assert_eq!(body.error.code, ErrorCode::NotFound);
+    assert_eq!(body.error.request_id.as_deref(), Some("trace-99"), "the caller's request id is kept");
+    assert_eq!(anonymous.error.code, ErrorCode::Unauthenticated);
+    assert!(anonymous.error.request_id.is_some(), "a request id is assigned when missing");
 }
 
 #[tokio::test]
@@ -198,8 +296,13 @@ async fn remove_product() {
 
     let target = prepare_target(service_data.clone());
 
+    let (_, auth_token) = get_admin_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
     // -- run 1
     let status_code = TestClient::delete(format!("{BASE_URL}/products/1"))
+        .add_header("authorization", &bearer, true)
         .send(&target)
         .await
         .status_code
@@ -212,6 +315,7 @@ async fn remove_product() {
     // -- run 2
 
     let status_code = TestClient::delete(format!("{BASE_URL}/products/2"))
+        .add_header("authorization", &bearer, true)
         .send(&target)
         .await
         .status_code
@@ -232,6 +336,10 @@ async fn update_product() {
 
     let target = prepare_target(service_data.clone());
 
+    let (_, auth_token) = get_admin_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
     let description = "a new description";
     let price = 99000.0;
 
@@ -249,6 +357,7 @@ async fn update_product() {
     // -- run 1
 
     let response = TestClient::put(format!("{BASE_URL}/products/3"))
+        .add_header("authorization", &bearer, true)
         .form(&fields)
         .send(&target)
         .await
@@ -263,6 +372,7 @@ async fn update_product() {
     // -- run 2
 
     let status_code = TestClient::put(format!("{BASE_URL}/products/1"))
+        .add_header("authorization", &bearer, true)
         .form(&fields)
         .send(&target)
         .await
@@ -272,4 +382,701 @@ async fn update_product() {
     // -- assert 2
 
     assert_eq!(status_code, StatusCode::NOT_FOUND, "when a product is not found");
-}
\ No newline at end of file
+
+    // -- run 3
+
+    let response = &mut TestClient::put(format!("{BASE_URL}/products/3"))
+        .add_header("authorization", &bearer, true)
+        .form(&[("name", ""), ("url", "ftp://shop.test/any"), ("price", "-1")])
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let body = response.take_json::<ErrorResponse>().await.unwrap();
+
+    // -- assert 3
+
+    assert_eq!(status_code, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body.error.details, Some(json!([
+        { "field": "name", "code": "too_short", "message": "`name` cannot be empty" },
+        { "field": "url", "code": "invalid_url", "message": "`url` must be an http(s) url" },
+        { "field": "price", "code": "too_small", "message": "`price` must be at least 0" },
+    ])), "updates are checked like new products");
+}
+
+#[tokio::test]
+async fn write_products_as_json() {
+    // -- setup
+
+    let products = test_products();
+    let product1 = products.get("product1").unwrap();
+    let service_data = ServiceData::with_products(vec![product1.clone()]);
+
+    let target = prepare_target(service_data);
+
+    let (_, auth_token) = get_admin_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
+    // -- run 1
+
+    let created = TestClient::post(format!("{BASE_URL}/products"))
+        .add_header("authorization", &bearer, true)
+        .json(&json!({ "name": "Kite", "price": 1500.5, "url": null }))
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<Product>>()
+        .await
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(created.data.name, "Kite");
+    assert_eq!(created.data.price, 1500.5);
+    assert_eq!(created.data.url, None, "null reads as no value");
+
+    // -- run 2
+
+    let updated = TestClient::put(format!("{BASE_URL}/products/1"))
+        .add_header("authorization", &bearer, true)
+        .json(&json!({ "description": null, "available": false }))
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<Product>>()
+        .await
+        .unwrap();
+
+    // -- assert 2
+
+    let expected = Product { description: None, available: false, ..product1.clone() };
+
+    assert_eq!(updated.data, expected, "null clears the description, missing fields are kept");
+
+    // -- run 3
+
+    let null_name_status = TestClient::put(format!("{BASE_URL}/products/1"))
+        .add_header("authorization", &bearer, true)
+        .json(&json!({ "name": null }))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let not_an_object_status = TestClient::post(format!("{BASE_URL}/products"))
+        .add_header("authorization", &bearer, true)
+        .json(&json!(["Kite"]))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let malformed_status = TestClient::post(format!("{BASE_URL}/products"))
+        .add_header("authorization", &bearer, true)
+        .add_header("content-type", "application/json", true)
+        .body("{ \"name\": ")
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 3
+
+    assert_eq!(null_name_status, StatusCode::UNPROCESSABLE_ENTITY, "a name is required");
+    assert_eq!(not_an_object_status, StatusCode::BAD_REQUEST, "the body must be an object");
+    assert_eq!(malformed_status, StatusCode::BAD_REQUEST, "the body must be valid JSON");
+}
+
+#[tokio::test]
+async fn product_writes_require_admin() {
+    // -- setup
+
+    let products = test_products();
+    let product1 = products.get("product1").unwrap();
+    let service_data = ServiceData::with_products(vec![product1.clone()]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, auth_token) = get_user_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
+    // -- run 1
+
+    let anonymous_status = TestClient::delete(format!("{BASE_URL}/products/1"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let user_status = TestClient::delete(format!("{BASE_URL}/products/1"))
+        .add_header("authorization", &bearer, true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let user_update_status = TestClient::put(format!("{BASE_URL}/products/1"))
+        .add_header("authorization", &bearer, true)
+        .form(&[("price", "1500")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let read_status = TestClient::get(format!("{BASE_URL}/products/1"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 1
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(anonymous_status, StatusCode::UNAUTHORIZED, "writing products requires authentication");
+    assert_eq!(user_status, StatusCode::FORBIDDEN, "users can't delete products");
+    assert_eq!(read_status, StatusCode::OK, "anyone can read products");
+    assert_eq!(locked_reporter.get_fn_calls("product_repo.delete"), 0, "product_repo.delete() should not be called");
+    assert_eq!(user_update_status, StatusCode::FORBIDDEN, "users can't update products");
+    assert_eq!(locked_reporter.get_fn_calls("product_repo.update"), 0, "product_repo.update() should not be called");
+}
+
+#[tokio::test]
+async fn submit_and_review_product() {
+    // -- setup
+
+    let products = test_products();
+    let product1 = products.get("product1").unwrap().clone();
+
+    let pending_product = Product {
+        id: 2,
+        name: "Kite".to_string(),
+        review_status: ReviewStatus::Pending,
+        submitted_by: Some(2),
+        available: false,
+        ..product1.clone()
+    };
+
+    let service_data = ServiceData::with_products(vec![product1, pending_product]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, admin_token) = get_admin_and_token();
+
+    // -- run 1
+
+    let submitted = TestClient::post(format!("{BASE_URL}/products"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .form(&[("name", "Skates"), ("price", "80000")])
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<Product>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    assert_eq!(submitted.review_status, ReviewStatus::Pending, "user submissions wait for review");
+    assert_eq!(submitted.submitted_by, Some(2), "the submitter is recorded");
+    assert!(!submitted.available, "pending products aren't available");
+
+    // -- run 2
+
+    let listed = TestClient::get(format!("{BASE_URL}/products"))
+        .send(&target)
+        .await
+        .take_json::<CollectionResponse<ListedProduct>>()
+        .await
+        .unwrap()
+        .data;
+
+    let anonymous_status = TestClient::get(format!("{BASE_URL}/products/2"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let submitter_status = TestClient::get(format!("{BASE_URL}/products/2"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(listed.len(), 1, "only approved products are listed");
+    assert_eq!(anonymous_status, StatusCode::FORBIDDEN, "pending products are hidden");
+    assert_eq!(submitter_status, StatusCode::OK, "the submitter sees the pending product");
+
+    // -- run 3
+
+    let user_pending_status = TestClient::get(format!("{BASE_URL}/admin/products/pending"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let pending = TestClient::get(format!("{BASE_URL}/admin/products/pending"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .send(&target)
+        .await
+        .take_json::<CollectionResponse<Product>>()
+        .await
+        .unwrap()
+        .data;
+
+    let approved = TestClient::put(format!("{BASE_URL}/admin/products/2/approve"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<Product>>()
+        .await
+        .unwrap()
+        .data;
+
+    let rejected = TestClient::put(format!("{BASE_URL}/admin/products/2/reject"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<Product>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 3
+
+    assert_eq!(user_pending_status, StatusCode::FORBIDDEN, "only admins review products");
+    assert_eq!(pending.len(), 1, "pending products are listed for admins");
+    assert_eq!(approved.review_status, ReviewStatus::Approved, "product should be approved");
+    assert!(approved.available, "approved products become available");
+    assert_eq!(rejected.review_status, ReviewStatus::Rejected, "product should be rejected");
+    assert!(!rejected.available, "rejected products aren't available");
+}
+
+#[tokio::test]
+async fn remove_product_with_wishes() {
+    // -- setup
+
+    let products = test_products();
+    let product1 = products.get("product1").unwrap();
+
+    let wishes = vec![
+        Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+    ];
+
+    let service_data = ServiceData::with_products(vec![product1.clone()])
+        .wishes(wishes);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, auth_token) = get_admin_and_token();
+
+    // -- run
+
+    let status_code = TestClient::delete(format!("{BASE_URL}/products/1"))
+        .add_header("authorization", format!("Bearer {auth_token}"), true)
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(status_code, StatusCode::CONFLICT, "products with wishes can't be deleted");
+    assert_eq!(locked_reporter.get_fn_calls("product_repo.delete"), 0, "product_repo.delete() should not be called");
+}
+
+#[tokio::test]
+async fn filter_products() {
+    // -- setup
+
+    let test_products = test_products();
+    let product1 = test_products.get("product1").unwrap();
+    let product2 = test_products.get("product2").unwrap();
+    let product3 = test_products.get("product3").unwrap();
+
+    let service_data = ServiceData::with_products(vec![
+        product1.clone(),
+        product2.clone(),
+        product3.clone(),
+    ]);
+
+    let target = prepare_target(service_data);
+
+    let list_ids = |query: &'static str| {
+        let target = &target;
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/products?{query}"))
+                .send(target)
+                .await
+                .take_json::<CollectionResponse<ListedProduct>>()
+                .await
+                .unwrap()
+                .data
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<i32>>()
+        }
+    };
+
+    // -- run 1
+
+    let searched = list_ids("q=DESC").await;
+    let price_range = list_ids("min_price=34000.5&max_price=200000").await;
+    let available = list_ids("available=false").await;
+    let by_price = list_ids("sort=price&order=desc").await;
+    let paginated = TestClient::get(format!("{BASE_URL}/products?sort=price&per_page=2&page=1"))
+        .send(&target)
+        .await
+        .take_json::<CollectionPaginatedResponse<ListedProduct>>()
+        .await
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(searched, vec![1, 2], "search in name and description, case insensitive");
+    assert_eq!(price_range, vec![1, 2], "filter by price range");
+    assert_eq!(available, vec![3], "filter by availability");
+    assert_eq!(by_price, vec![2, 1, 3], "sort by price descending");
+    assert_eq!(paginated.data.iter().map(|p| p.id).collect::<Vec<i32>>(), vec![3, 1], "filters compose with pagination");
+    assert_eq!(paginated.pagination.entries, 3, "pagination: entries");
+
+    // -- run 2
+
+    let status_code = TestClient::get(format!("{BASE_URL}/products?sort=url"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(status_code, StatusCode::BAD_REQUEST, "unknown sort fields are rejected");
+}
+
+#[tokio::test]
+async fn list_products_changed_since() {
+    // -- setup
+
+    let test_products = test_products();
+    let at = |day: u32| NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(12, 0, 0).unwrap();
+
+    let product1 = Product { created_at: at(1), updated_at: at(20), ..test_products.get("product1").unwrap().clone() };
+    let product2 = Product { created_at: at(3), updated_at: at(3), ..test_products.get("product2").unwrap().clone() };
+    let product3 = Product { created_at: at(2), updated_at: at(10), ..test_products.get("product3").unwrap().clone() };
+
+    let service_data = ServiceData::with_products(vec![product1, product2, product3]);
+
+    let target = prepare_target(service_data);
+
+    let list = |query: &'static str| {
+        let target = &target;
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/products?{query}"))
+                .send(target)
+                .await
+                .take_json::<CollectionResponse<ListedProduct>>()
+                .await
+                .unwrap()
+                .data
+        }
+    };
+
+    // -- run 1
+
+    let by_creation = list("sort=created_at&order=desc").await;
+    let changed = list("updated_since=2026-03-10%2012:00:00&sort=updated_at").await;
+
+    // -- assert 1
+
+    assert_eq!(by_creation.iter().map(|p| p.id).collect::<Vec<i32>>(), vec![2, 3, 1], "sort by creation, newest first");
+    assert_eq!(changed.iter().map(|p| p.id).collect::<Vec<i32>>(), vec![3, 1], "only the products updated since then");
+    assert_eq!(changed[1].updated_at, at(20), "the timestamps are listed");
+
+    // -- run 2
+
+    let status_code = TestClient::get(format!("{BASE_URL}/products?updated_since=yesterday"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(status_code, StatusCode::BAD_REQUEST, "`updated_since` must be a date");
+}
+
+#[tokio::test]
+async fn import_product() {
+    // -- setup
+
+    let boundary = "fishbowl-boundary";
+
+    let document = |html: &str| format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"document\"; filename=\"page.html\"\r\nContent-Type: text/html\r\n\r\n{html}\r\n--{boundary}--\r\n"
+    ).into_bytes();
+
+    let page = r#"<html><head>
+        <script type="application/ld+json">{"@type": "Product", "name": "Skates", "offers": {"price": "80000", "priceCurrency": "COP"}}</script>
+        </head></html>"#;
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(ServiceData::default(), reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    // -- run 1
+
+    let imported = TestClient::post(format!("{BASE_URL}/products/import"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .add_header("content-type", format!("multipart/form-data; boundary={boundary}"), true)
+        .bytes(document(page))
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<ImportedProduct>>()
+        .await
+        .unwrap()
+        .data;
+
+    let anonymous_status = TestClient::post(format!("{BASE_URL}/products/import"))
+        .add_header("content-type", format!("multipart/form-data; boundary={boundary}"), true)
+        .bytes(document(page))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let incomplete_status = TestClient::post(format!("{BASE_URL}/products/import"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .add_header("content-type", format!("multipart/form-data; boundary={boundary}"), true)
+        .bytes(document(r#"<meta property="og:title" content="Skates">"#))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(imported.product.name, "Skates");
+    assert_eq!(imported.product.price, 80000.0);
+    assert_eq!(imported.currency.as_deref(), Some("COP"));
+    assert_eq!(imported.product.review_status, ReviewStatus::Pending, "user imports go through review");
+    assert_eq!(imported.product.submitted_by, Some(2));
+    assert_eq!(anonymous_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(incomplete_status, StatusCode::BAD_REQUEST, "the price is required");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("product_repo.insert"), 0, "imported products aren't saved");
+}
+
+#[tokio::test]
+async fn product_price_history() {
+    // -- setup
+
+    let product1 = test_products().get("product1").unwrap().clone();
+
+    let change = ProductChange {
+        id: 1,
+        product_id: 1,
+        previous_price: 30000.0,
+        price: 34000.6,
+        previous_available: false,
+        available: true,
+        changed_at: NaiveDate::from_ymd_opt(2026, 10, 1).unwrap().and_hms_opt(12, 0, 0).unwrap(),
+    };
+
+    let wishlist = Wishlist {
+        id: 1,
+        title: "Birthday".to_string(),
+        description: None,
+        date: None,
+        user_id: 2,
+        visibility: Visibility::Private,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let service_data = ServiceData::with_products(vec![product1])
+        .product_changes(vec![change.clone()])
+        .wishlists(vec![wishlist])
+        .wishes(vec![
+            Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 1500.0, created_at: timestamp(), updated_at: timestamp() },
+        ]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, admin_token) = get_admin_and_token();
+
+    // -- run 1
+
+    let history = TestClient::get(format!("{BASE_URL}/products/1/history"))
+        .send(&target)
+        .await
+        .take_json::<CollectionResponse<ProductChange>>()
+        .await
+        .unwrap()
+        .data;
+
+    let missing_status = TestClient::get(format!("{BASE_URL}/products/99/history"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let wish = TestClient::get(format!("{BASE_URL}/wishlists/1/wishes/1?detailed=true"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .send(&target)
+        .await
+        .take_json::<ResourceResponse<WishProduct>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    assert_eq!(history, vec![change]);
+    assert_eq!(missing_status, StatusCode::NOT_FOUND);
+    assert_eq!(wish.original_price, 1500.0, "the price when the product was wished is kept");
+    assert!(wish.price_changed, "wishes flag products whose price changed");
+
+    // -- run 2
+
+    for form in [[("name", "Kite")], [("price", "36000")]] {
+        TestClient::put(format!("{BASE_URL}/products/1"))
+            .add_header("authorization", format!("Bearer {admin_token}"), true)
+            .form(&form)
+            .send(&target)
+            .await;
+    }
+
+    // -- assert 2
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(locked_reporter.get_fn_calls("product_repo.update"), 2, "product_repo.update() should be called twice");
+    assert_eq!(locked_reporter.get_fn_calls("product_repo.record_change"), 1, "only price and availability changes are recorded");
+}
+
+#[tokio::test]
+async fn merge_duplicate_products() {
+    // -- setup
+
+    let products = test_products();
+    let product1 = products.get("product1").unwrap().clone();
+    let product2 = products.get("product2").unwrap().clone();
+
+    let duplicate = Product {
+        id: 4,
+        name: "Product 1!".to_string(),
+        url: None,
+        price: 33000.0,
+        review_status: ReviewStatus::Pending,
+        ..product1.clone()
+    };
+
+    let service_data = ServiceData::with_products(vec![product1, product2, duplicate]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, admin_token) = get_admin_and_token();
+
+    let merge = |id: i32, token: &str, duplicate_ids: &'static str| {
+        let target = &target;
+        let token = token.to_string();
+
+        async move {
+            TestClient::post(format!("{BASE_URL}/admin/products/{id}/merge"))
+                .add_header("authorization", format!("Bearer {token}"), true)
+                .form(&[("duplicate_ids", duplicate_ids)])
+                .send(target)
+                .await
+                .status_code
+                .unwrap()
+        }
+    };
+
+    // -- run 1
+
+    let groups = TestClient::get(format!("{BASE_URL}/admin/products/duplicates"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .send(&target)
+        .await
+        .take_json::<CollectionResponse<DuplicateGroup>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    let ids: Vec<Vec<i32>> = groups.iter()
+        .map(|group| group.products.iter().map(|p| p.id).collect())
+        .collect();
+
+    assert_eq!(ids, vec![vec![1, 4]], "similar names at similar prices, pending submissions included");
+
+    // -- run 2
+
+    let user_status = merge(1, &user_token, "4").await;
+    let itself_status = merge(1, &admin_token, "1,4").await;
+    let missing_status = merge(1, &admin_token, "99").await;
+    let merged_status = merge(1, &admin_token, "4").await;
+
+    // -- assert 2
+
+    assert_eq!(user_status, StatusCode::FORBIDDEN, "only admins merge products");
+    assert_eq!(itself_status, StatusCode::BAD_REQUEST, "a product can't be merged into itself");
+    assert_eq!(missing_status, StatusCode::NOT_FOUND, "every duplicate must exist");
+    assert_eq!(merged_status, StatusCode::ACCEPTED);
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("product_repo.merge"), 1, "product_repo.merge() should be called once");
+
+    // -- run 3
+
+    let merge_json = |body: serde_json::Value| {
+        let target = &target;
+        let token = admin_token.clone();
+
+        async move {
+            TestClient::post(format!("{BASE_URL}/admin/products/1/merge"))
+                .add_header("authorization", format!("Bearer {token}"), true)
+                .json(&body)
+                .send(target)
+                .await
+                .status_code
+                .unwrap()
+        }
+    };
+
+    let json_status = merge_json(json!({ "duplicate_ids": [4] })).await;
+    let invalid_status = merge_json(json!({ "duplicate_ids": ["four"] })).await;
+    let missing_field_status = merge_json(json!({})).await;
+
+    // -- assert 3
+
+    assert_eq!(json_status, StatusCode::ACCEPTED, "duplicates can be sent as a JSON array");
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST, "duplicates must be ids");
+    assert_eq!(missing_field_status, StatusCode::BAD_REQUEST, "duplicates are required");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("product_repo.merge"), 2, "product_repo.merge() should be called twice");
+}
@@ -0,0 +1,77 @@
+use std::sync::{Arc, Mutex};
+use salvo::prelude::*;
+use salvo::test::{ResponseExt, TestClient};
+use fishbowl::api::resources::search::models::SearchHit;
+use fishbowl::api::responses::CollectionPaginatedResponse;
+use crate::utils::{prepare_api_service, Reporter, ServiceData, BASE_URL};
+
+fn hit(kind: &str, id: i32, title: &str, rank: f32) -> SearchHit {
+    SearchHit {
+        kind: kind.to_string(),
+        id,
+        title: title.to_string(),
+        headline: format!("<mark>{title}</mark>"),
+        rank,
+    }
+}
+
+#[tokio::test]
+async fn search() {
+    // -- setup
+
+    let search_hits = vec![
+        hit("product", 1, "Consola Sony Playstation 5", 0.6),
+        hit("wishlist", 3, "Consola para Navidad", 0.4),
+        hit("product", 2, "Bicicleta de montaña", 0.3),
+    ];
+
+    let service_data = ServiceData::default().search_hits(search_hits);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    // -- run 1
+
+    let response = TestClient::get(format!("{BASE_URL}/search?q=consola&per_page=1&page=2"))
+        .send(&target)
+        .await
+        .take_json::<CollectionPaginatedResponse<SearchHit>>()
+        .await
+        .unwrap();
+
+    // -- assert 1
+
+    assert_eq!(response.pagination.entries, 2, "pagination: entries");
+    assert_eq!(response.pagination.total_pages, 2, "pagination: total pages");
+    assert_eq!(response.data, vec![hit("wishlist", 3, "Consola para Navidad", 0.4)], "second page of hits");
+
+    // -- run 2
+
+    let missing_status = TestClient::get(format!("{BASE_URL}/search"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let empty_status = TestClient::get(format!("{BASE_URL}/search?q=%20"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let language_status = TestClient::get(format!("{BASE_URL}/search?q=consola&lang=fr"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    let locked_reporter = reporter.lock().unwrap();
+
+    assert_eq!(missing_status, StatusCode::BAD_REQUEST, "`q` is required");
+    assert_eq!(empty_status, StatusCode::BAD_REQUEST, "`q` cannot be blank");
+    assert_eq!(language_status, StatusCode::BAD_REQUEST, "only spanish and english are supported");
+    assert_eq!(locked_reporter.get_fn_calls("search_repo.search"), 1, "search_repo.search() should be called once");
+}
@@ -0,0 +1,170 @@
+use std::sync::{Arc, Mutex};
+use chrono::NaiveDate;
+use salvo::test::{ResponseExt, TestClient};
+use fishbowl::api::resources::shares::models::ShareLink;
+use fishbowl::api::resources::wishlists::models::{Visibility, Wishlist};
+use fishbowl::api::responses::ResourceResponse;
+use crate::utils::{timestamp, get_admin_and_token, get_user_and_token, prepare_api_service, Reporter, ServiceData, BASE_URL};
+
+fn test_wishlist() -> Wishlist {
+    Wishlist {
+        id: 1,
+        title: "Surprise party".to_string(),
+        description: None,
+        date: None,
+        user_id: 1,
+        visibility: Visibility::LinkOnly,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+#[tokio::test]
+async fn create_share_link() {
+    // -- setup
+
+    let service_data = ServiceData::default()
+        .wishlists(vec![test_wishlist()]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, owner_token) = get_admin_and_token();
+
+    let (_, user_token) = get_user_and_token();
+
+    // -- run 1
+
+    let response = &mut TestClient::post(format!("{BASE_URL}/wishlists/1/shares"))
+        .add_header("authorization", format!("Bearer {owner_token}"), true)
+        .form(&[("expires_at", "2030-01-01 00:00:00")])
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let share_link = response.take_json::<ResourceResponse<ShareLink>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    assert_eq!(status_code, 202, "status code should be 202");
+    assert_eq!(share_link.token.len(), 32, "token should be 32 characters long");
+    assert!(share_link.expires_at.is_some(), "expiration should be stored");
+
+    // -- run 2
+
+    let response = TestClient::post(format!("{BASE_URL}/wishlists/1/shares"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .form(&[("expires_at", "2030-01-01 00:00:00")])
+        .send(&target)
+        .await;
+
+    // -- assert 2
+
+    assert_eq!(response.status_code.unwrap(), 403, "only owners can share the wishlist");
+}
+
+#[tokio::test]
+async fn share_private_wishlist() {
+    // -- setup
+
+    let private = Wishlist { visibility: Visibility::Private, ..test_wishlist() };
+
+    let share_links = vec![
+        ShareLink { id: 1, wishlist_id: 1, token: "active".to_string(), expires_at: None, revoked: false },
+    ];
+
+    let service_data = ServiceData::default()
+        .wishlists(vec![private])
+        .share_links(share_links);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, owner_token) = get_admin_and_token();
+
+    // -- run
+
+    let create_status = TestClient::post(format!("{BASE_URL}/wishlists/1/shares"))
+        .add_header("authorization", format!("Bearer {owner_token}"), true)
+        .form(&[("expires_at", "2030-01-01 00:00:00")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let show_status = TestClient::get(format!("{BASE_URL}/shared/active"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert
+
+    assert_eq!(create_status, 409, "private wishlists can't be shared");
+    assert_eq!(show_status, 404, "links don't open private wishlists");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("share_link_repo.insert"), 0);
+}
+
+#[tokio::test]
+async fn show_shared_wishlist() {
+    // -- setup
+
+    let expired_at = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+    let share_links = vec![
+        ShareLink { id: 1, wishlist_id: 1, token: "active".to_string(), expires_at: None, revoked: false },
+        ShareLink { id: 2, wishlist_id: 1, token: "revoked".to_string(), expires_at: None, revoked: true },
+        ShareLink { id: 3, wishlist_id: 1, token: "expired".to_string(), expires_at: Some(expired_at), revoked: false },
+    ];
+
+    let service_data = ServiceData::default()
+        .wishlists(vec![test_wishlist()])
+        .share_links(share_links);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    // -- run 1
+
+    let response = &mut TestClient::get(format!("{BASE_URL}/shared/active"))
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let wishlist = response.take_json::<ResourceResponse<Wishlist>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 1
+
+    assert_eq!(status_code, 200, "anonymous users can open an active link");
+    assert_eq!(wishlist, test_wishlist());
+
+    // -- run 2
+
+    let revoked_status = TestClient::get(format!("{BASE_URL}/shared/revoked"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    let expired_status = TestClient::get(format!("{BASE_URL}/shared/expired"))
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 2
+
+    assert_eq!(revoked_status, 404, "a revoked link is not found");
+    assert_eq!(expired_status, 404, "an expired link is not found");
+}
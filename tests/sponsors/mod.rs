@@ -1,13 +1,54 @@
 use std::sync::{Arc, Mutex};
 use fishbowl::api::resources::{sponsors::models::Sponsor, wishes::models::Wish};
-use salvo::test::TestClient;
-use crate::utils::{get_admin_and_token, prepare_api_service, Reporter, ServiceData, BASE_URL};
+use fishbowl::api::resources::wishlists::models::{Visibility, Wishlist};
+use fishbowl::api::resources::products::models::{Product, ReviewStatus};
+use fishbowl::api::resources::users::models::User;
+use chrono::NaiveDate;
+use fishbowl::api::responses::CollectionResponse;
+use salvo::test::{ResponseExt, TestClient};
+use serde_json::Value;
+use crate::utils::{timestamp, get_admin_and_token, prepare_api_service, Reporter, ServiceData, BASE_URL};
+
+fn public_wishlist() -> Wishlist {
+    Wishlist {
+        id: 1,
+        title: "Birthday".to_string(),
+        description: None,
+        date: None,
+        user_id: 2,
+        visibility: Visibility::Public,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
+
+fn wished_product() -> Product {
+    Product {
+        id: 1,
+        name: "Kite".to_string(),
+        description: None,
+        url: None,
+        price: 2000.0,
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    }
+}
 
 #[tokio::test]
 async fn add_sponsor() {
     // setup
 
-    let service_data = ServiceData::default();
+    let wishes = vec![
+        Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+    ];
+
+    let service_data = ServiceData::with_products(vec![wished_product()])
+        .wishlists(vec![public_wishlist()])
+        .wishes(wishes);
 
     let reporter = Arc::new(Mutex::new(Reporter::new()));
 
@@ -46,17 +87,83 @@ async fn add_sponsor() {
     assert_eq!(insert_calls, 1, "sponsor_repo.insert() should be called once");
 }
 
+#[tokio::test]
+async fn add_sponsor_notifies_owner() {
+    // setup
+
+    let wishes = vec![
+        Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+    ];
+
+    let users = [(1, "Sr admin"), (2, "Sr user")].map(|(id, name)| User {
+        id,
+        name: name.to_string(),
+        role: "USER".to_string(),
+        email: format!("user{id}@dummy.test"),
+        active: true,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        locale: "en".to_string(),
+    });
+
+    let sponsors = vec![
+        Sponsor { id: 2, leader: Some(true), amount: 500.0, user_id: 2, wish_id: 1, created_at: timestamp(), updated_at: timestamp() },
+    ];
+
+    let service_data = ServiceData::with_users(users.to_vec())
+        .products(vec![wished_product()])
+        .wishlists(vec![public_wishlist()])
+        .wishes(wishes)
+        .sponsors(sponsors);
+
+    let (_, auth_token) = get_admin_and_token();
+
+    let sponsor = |service_data: ServiceData, amount: &'static str| {
+        let bearer = format!("Bearer {auth_token}");
+
+        async move {
+            let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+            let target = prepare_api_service(service_data, reporter.clone());
+
+            TestClient::post(format!("{BASE_URL}/sponsors"))
+                .add_header("authorization", bearer, true)
+                .form(&[("wish_id", "1"), ("amount", amount)])
+                .send(&target)
+                .await;
+
+            let locked_reporter = reporter.lock().unwrap();
+
+            [
+                "inbox_repo.insert.wish_sponsored",
+                "inbox_repo.insert.wish_funded",
+                "outbox_repo.insert.wish_funded",
+            ].map(|fn_name| locked_reporter.get_fn_calls(fn_name))
+        }
+    };
+
+    // run
+
+    let funding = sponsor(service_data.clone(), "1500").await;
+    let partial = sponsor(service_data.clone(), "1000").await;
+
+    // assert
+
+    assert_eq!(funding, [1, 1, 1], "the owner hears about the sponsor and the funded wish");
+    assert_eq!(partial, [1, 0, 0], "the wish isn't funded yet");
+}
+
 #[tokio::test]
 async fn list_sponsors_wish() {
     // setup
 
     let wishes = vec![
-        Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true },
+        Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
     ];
 
-    let sponsor1 = Sponsor {id: 1, leader: Some(true), amount: 56000.0, user_id: 1, wish_id: 1 };
-    let sponsor2 = Sponsor {id: 2, leader: None, amount: 21000.5, user_id: 2, wish_id: 1 };
-    let sponsor3 = Sponsor {id: 3, leader: None, amount: 100000.0, user_id: 1, wish_id: 2 };
+    let sponsor1 = Sponsor {id: 1, leader: Some(true), amount: 56000.0, user_id: 1, wish_id: 1, created_at: timestamp(), updated_at: timestamp() };
+    let sponsor2 = Sponsor {id: 2, leader: None, amount: 21000.5, user_id: 2, wish_id: 1, created_at: timestamp(), updated_at: timestamp() };
+    let sponsor3 = Sponsor {id: 3, leader: None, amount: 100000.0, user_id: 1, wish_id: 2, created_at: timestamp(), updated_at: timestamp() };
 
     let sponsors = vec![
         sponsor1,
@@ -65,6 +172,7 @@ async fn list_sponsors_wish() {
     ];
 
     let service_data = ServiceData::default()
+        .wishlists(vec![public_wishlist()])
         .wishes(wishes)
         .sponsors(sponsors);
 
@@ -93,4 +201,58 @@ async fn list_sponsors_wish() {
 
     assert_eq!(status_code, 200, "status code should be ok 200");
     assert_eq!(list_calls, 1, "sponsor_repo.list_by_wish() should be called once");
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn list_sponsors_changed_since() {
+    // setup
+
+    let wishes = vec![
+        Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+    ];
+
+    let at = |day: u32| NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(12, 0, 0).unwrap();
+
+    let sponsors = vec![
+        Sponsor { id: 1, leader: Some(true), amount: 500.0, user_id: 1, wish_id: 1, created_at: at(1), updated_at: at(20) },
+        Sponsor { id: 2, leader: None, amount: 300.0, user_id: 2, wish_id: 1, created_at: at(3), updated_at: at(3) },
+        Sponsor { id: 3, leader: None, amount: 200.0, user_id: 1, wish_id: 1, created_at: at(2), updated_at: at(10) },
+    ];
+
+    let service_data = ServiceData::default()
+        .wishlists(vec![public_wishlist()])
+        .wishes(wishes)
+        .sponsors(sponsors);
+
+    let target = prepare_api_service(service_data, Arc::new(Mutex::new(Reporter::new())));
+
+    let (_, auth_token) = get_admin_and_token();
+
+    let list_ids = |query: &'static str| {
+        let (target, auth_token) = (&target, &auth_token);
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/wishes/1/sponsors?{query}"))
+                .add_header("authorization", format!("Bearer {auth_token}"), true)
+                .send(target)
+                .await
+                .take_json::<CollectionResponse<Value>>()
+                .await
+                .unwrap()
+                .data
+                .iter()
+                .map(|sponsor| sponsor["id"].as_i64().unwrap())
+                .collect::<Vec<i64>>()
+        }
+    };
+
+    // run
+
+    let by_creation = list_ids("sort=created_at&order=desc").await;
+    let changed = list_ids("updated_since=2026-03-10%2012:00:00&sort=updated_at").await;
+
+    // assert
+
+    assert_eq!(by_creation, vec![2, 3, 1], "sort by creation, newest first");
+    assert_eq!(changed, vec![3, 1], "only the sponsors updated since then");
+}
@@ -0,0 +1,137 @@
+use std::sync::{Arc, Mutex};
+use salvo::test::{ResponseExt, TestClient};
+use fishbowl::api::resources::products::models::{Product, ReviewStatus};
+use fishbowl::api::resources::templates::models::{DetailedTemplate, Template};
+use fishbowl::api::resources::wishlists::models::{Visibility, Wishlist};
+use fishbowl::api::responses::ResourceResponse;
+use crate::utils::{timestamp, get_admin_and_token, get_user_and_token, prepare_api_service, Reporter, ServiceData, BASE_URL};
+
+fn test_template() -> DetailedTemplate {
+    let product = Product {
+        id: 1,
+        name: "Baby monitor".to_string(),
+        description: None,
+        url: None,
+        price: 250000.0,
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    DetailedTemplate {
+        id: 1,
+        title: "Baby shower".to_string(),
+        description: Some("Everything for the first months".to_string()),
+        products: vec![product],
+    }
+}
+
+#[tokio::test]
+async fn instantiate_template() {
+    // -- setup
+
+    let service_data = ServiceData::default().templates(vec![test_template()]);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (user, auth_token) = get_user_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
+    // -- run 1
+
+    let response = &mut TestClient::post(format!("{BASE_URL}/templates/1/instantiate"))
+        .add_header("authorization", &bearer, true)
+        .form(&[("date", "2024-05-22 13:45:00")])
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let wishlist = response.take_json::<ResourceResponse<Wishlist>>()
+        .await
+        .unwrap()
+        .data;
+
+    let insert_calls = reporter.lock().unwrap().get_fn_calls("wishlist_repo.insert_with_products");
+
+    // -- assert 1
+
+    assert_eq!(status_code, 202, "status code should be 202");
+    assert_eq!(insert_calls, 1, "wishlist_repo.insert_with_products() should be called once");
+    assert_eq!(wishlist.title, "Baby shower", "title should be taken from the template");
+    assert_eq!(wishlist.user_id, user.id, "wishlist should belong to the caller");
+    assert_eq!(wishlist.visibility, Visibility::Private, "wishlist should be private");
+
+    // -- run 2
+
+    let response = TestClient::post(format!("{BASE_URL}/templates/2/instantiate"))
+        .add_header("authorization", &bearer, true)
+        .form(&[("title", "Missing")])
+        .send(&target)
+        .await;
+
+    // -- assert 2
+
+    assert_eq!(response.status_code.unwrap(), 404, "status code should be 404 not found");
+}
+
+#[tokio::test]
+async fn create_template() {
+    // -- setup
+
+    let service_data = ServiceData::default();
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, admin_token) = get_admin_and_token();
+
+    let (_, user_token) = get_user_and_token();
+
+    let fields = [
+        ("title", "Birthday"),
+        ("product_ids", "1,2,3"),
+    ];
+
+    // -- run 1
+
+    let response = &mut TestClient::post(format!("{BASE_URL}/admin/templates"))
+        .add_header("authorization", format!("Bearer {admin_token}"), true)
+        .form(&fields)
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let template = response.take_json::<ResourceResponse<Template>>()
+        .await
+        .unwrap()
+        .data;
+
+    let insert_calls = reporter.lock().unwrap().get_fn_calls("template_repo.insert");
+
+    // -- assert 1
+
+    assert_eq!(status_code, 202, "status code should be 202");
+    assert_eq!(insert_calls, 1, "template_repo.insert() should be called once");
+    assert_eq!(template.title, "Birthday");
+
+    // -- run 2
+
+    let response = TestClient::post(format!("{BASE_URL}/admin/templates"))
+        .add_header("authorization", format!("Bearer {user_token}"), true)
+        .form(&fields)
+        .send(&target)
+        .await;
+
+    // -- assert 2
+
+    assert_eq!(response.status_code.unwrap(), 403, "a regular user gets forbidden");
+}
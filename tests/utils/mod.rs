@@ -4,19 +4,44 @@ pub mod test_wishlist_repo;
 pub mod test_wish_repo;
 pub mod test_sponsor_repo;
 pub mod test_auth_repo;
+pub mod test_template_repo;
+pub mod test_collaborator_repo;
+pub mod test_share_link_repo;
+pub mod test_follower_repo;
+pub mod test_search_repo;
+pub mod test_category_repo;
+pub mod test_tag_repo;
+pub mod test_image_repo;
+pub mod test_audit_repo;
+pub mod test_outbox_repo;
+pub mod test_inbox_repo;
+pub mod test_storage;
+pub mod test_notifier;
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use chrono::{NaiveDate, NaiveDateTime};
+use fishbowl::api::resources::audit::models::AuditLog;
+use fishbowl::api::resources::outbox::models::OutboxEntry;
+use fishbowl::api::resources::inbox::models::Notification;
+use fishbowl::api::resources::categories::models::Category;
+use fishbowl::api::resources::collaborators::models::Collaborator;
+use fishbowl::api::resources::followers::models::Follower;
+use fishbowl::api::resources::images::models::Image;
+use fishbowl::api::resources::search::models::SearchHit;
+use fishbowl::api::resources::shares::models::ShareLink;
 use fishbowl::api::resources::sponsors::models::Sponsor;
+use fishbowl::api::resources::tags::models::{ProductTag, Tag};
+use fishbowl::api::resources::templates::models::DetailedTemplate;
 use fishbowl::api::resources::wishes::models::Wish;
 use salvo::prelude::*;
 use fishbowl::api;
 use api::utils::pagination::Paginate;
+use api::utils::sorting::{SortDirection, SortField, SyncFilter};
 use fishbowl::api::auth;
 use fishbowl::services::{ServiceInjector, InjectableServices};
 use fishbowl::services::database::contracts;
-use fishbowl::services::notifications::ConsoleNotifier;
-use api::resources::products::models::Product;
+use api::resources::products::models::{Product, ProductChange};
 use api::resources::wishlists::models::Wishlist;
 use api::resources::users::models::User;
 use api::auth::models::User as AuthUser;
@@ -24,18 +49,44 @@ use test_auth_repo::TestAuthRepo;
 use test_product_repo::TestProductRepo;
 use test_user_repo::TestUserRepo;
 use self::test_sponsor_repo::TestSponsorRepo;
+use self::test_template_repo::TestTemplateRepo;
+use self::test_collaborator_repo::TestCollaboratorRepo;
+use self::test_share_link_repo::TestShareLinkRepo;
+use self::test_follower_repo::TestFollowerRepo;
+use self::test_search_repo::TestSearchRepo;
+use self::test_category_repo::TestCategoryRepo;
+use self::test_tag_repo::TestTagRepo;
+use self::test_image_repo::TestImageRepo;
+use self::test_audit_repo::TestAuditRepo;
+use self::test_outbox_repo::TestOutboxRepo;
+use self::test_inbox_repo::TestInboxRepo;
+use self::test_storage::TestStorage;
+use self::test_notifier::TestNotifier;
 use self::test_wish_repo::TestWishRepo;
 use self::test_wishlist_repo::TestWishlistRepo;
 
 pub static BASE_URL: &str = "http://localhost/api/v1";
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct ServiceData {
     pub products: Vec<Product>,
     pub users: Vec<User>,
     pub wishlists: Vec<Wishlist>,
     pub wishes: Vec<Wish>,
     pub sponsors: Vec<Sponsor>,
+    pub templates: Vec<DetailedTemplate>,
+    pub collaborators: Vec<Collaborator>,
+    pub share_links: Vec<ShareLink>,
+    pub followers: Vec<Follower>,
+    pub search_hits: Vec<SearchHit>,
+    pub categories: Vec<Category>,
+    pub tags: Vec<Tag>,
+    pub product_tags: Vec<ProductTag>,
+    pub images: Vec<Image>,
+    pub product_changes: Vec<ProductChange>,
+    pub audit_logs: Vec<AuditLog>,
+    pub outbox: Vec<OutboxEntry>,
+    pub notifications: Vec<Notification>,
 }
 
 impl ServiceData {
@@ -72,17 +123,53 @@ impl ServiceData {
     pub fn sponsors(self, sponsors: Vec<Sponsor>) -> Self {
         Self { sponsors, ..self }
     }
-}
 
-impl Default for ServiceData {
-    fn default() -> Self {
-        Self {
-            products: vec![],
-            users: vec![],
-            wishlists: vec![],
-            wishes: vec![],
-            sponsors: vec![],
-        }
+    pub fn templates(self, templates: Vec<DetailedTemplate>) -> Self {
+        Self { templates, ..self }
+    }
+
+    pub fn collaborators(self, collaborators: Vec<Collaborator>) -> Self {
+        Self { collaborators, ..self }
+    }
+
+    pub fn share_links(self, share_links: Vec<ShareLink>) -> Self {
+        Self { share_links, ..self }
+    }
+
+    pub fn followers(self, followers: Vec<Follower>) -> Self {
+        Self { followers, ..self }
+    }
+
+    pub fn search_hits(self, search_hits: Vec<SearchHit>) -> Self {
+        Self { search_hits, ..self }
+    }
+
+    pub fn categories(self, categories: Vec<Category>) -> Self {
+        Self { categories, ..self }
+    }
+
+    pub fn tags(self, tags: Vec<Tag>, product_tags: Vec<ProductTag>) -> Self {
+        Self { tags, product_tags, ..self }
+    }
+
+    pub fn images(self, images: Vec<Image>) -> Self {
+        Self { images, ..self }
+    }
+
+    pub fn product_changes(self, product_changes: Vec<ProductChange>) -> Self {
+        Self { product_changes, ..self }
+    }
+
+    pub fn audit_logs(self, audit_logs: Vec<AuditLog>) -> Self {
+        Self { audit_logs, ..self }
+    }
+
+    pub fn outbox(self, outbox: Vec<OutboxEntry>) -> Self {
+        Self { outbox, ..self }
+    }
+
+    pub fn notifications(self, notifications: Vec<Notification>) -> Self {
+        Self { notifications, ..self }
     }
 }
 
@@ -138,11 +225,20 @@ impl contracts::DatabaseService for TestDatabaseService {
     }
 
     fn product_repo(&self) -> Box<dyn contracts::ProductRepo> {
-        Box::new(TestProductRepo::new(self.data.products.clone(), self.reporter.clone()))
+        let repo = TestProductRepo::new(self.data.products.clone(), self.reporter.clone())
+            .with_wishes(self.data.wishes.clone())
+            .with_tags(self.data.tags.clone(), self.data.product_tags.clone())
+            .with_changes(self.data.product_changes.clone())
+            .with_categories(self.data.categories.clone());
+
+        Box::new(repo)
     }
 
     fn sponsor_repo(&self) -> Box<dyn contracts::SponsorRepo> {
-        Box::new(TestSponsorRepo::new(self.data.sponsors.clone(), self.reporter.clone()))
+        let repo = TestSponsorRepo::new(self.data.sponsors.clone(), self.reporter.clone())
+            .with_wishes(self.data.wishes.clone(), self.data.products.clone());
+
+        Box::new(repo)
     }
 
     fn wishlist_repo(&self) -> Box<dyn contracts::WishlistRepo> {
@@ -158,7 +254,50 @@ impl contracts::DatabaseService for TestDatabaseService {
     }
     
     fn follower_repo(&self) -> Box<dyn contracts::FollowerRepo> {
-        todo!()
+        Box::new(TestFollowerRepo::new(self.data.followers.clone(), self.reporter.clone()))
+    }
+
+    fn template_repo(&self) -> Box<dyn contracts::TemplateRepo> {
+        Box::new(TestTemplateRepo::new(self.data.templates.clone(), self.reporter.clone()))
+    }
+
+    fn collaborator_repo(&self) -> Box<dyn contracts::CollaboratorRepo> {
+        Box::new(TestCollaboratorRepo::new(self.data.collaborators.clone(), self.reporter.clone()))
+    }
+
+    fn share_link_repo(&self) -> Box<dyn contracts::ShareLinkRepo> {
+        Box::new(TestShareLinkRepo::new(self.data.share_links.clone(), self.reporter.clone()))
+    }
+
+    fn search_repo(&self) -> Box<dyn contracts::SearchRepo> {
+        Box::new(TestSearchRepo::new(self.data.search_hits.clone(), self.reporter.clone()))
+    }
+
+    fn category_repo(&self) -> Box<dyn contracts::CategoryRepo> {
+        Box::new(TestCategoryRepo::new(self.data.categories.clone(), self.reporter.clone()))
+    }
+
+    fn tag_repo(&self) -> Box<dyn contracts::TagRepo> {
+        let repo = TestTagRepo::new(self.data.tags.clone(), self.reporter.clone())
+            .with_product_tags(self.data.product_tags.clone());
+
+        Box::new(repo)
+    }
+
+    fn image_repo(&self) -> Box<dyn contracts::ImageRepo> {
+        Box::new(TestImageRepo::new(self.data.images.clone(), self.reporter.clone()))
+    }
+
+    fn audit_repo(&self) -> Box<dyn contracts::AuditRepo> {
+        Box::new(TestAuditRepo::new(self.data.audit_logs.clone(), self.reporter.clone()))
+    }
+
+    fn outbox_repo(&self) -> Box<dyn contracts::OutboxRepo> {
+        Box::new(TestOutboxRepo::new(self.data.outbox.clone(), self.reporter.clone()))
+    }
+
+    fn inbox_repo(&self) -> Box<dyn contracts::InboxRepo> {
+        Box::new(TestInboxRepo::new(self.data.notifications.clone(), self.reporter.clone()))
     }
 }
 
@@ -169,8 +308,8 @@ pub trait MockService<T> {
     fn data(&self) -> Vec<T>;
 }
 
-fn get_paginated_page<T: Clone>(items: &Vec<T>, page: i64, per_page: i64) -> &[T] {
-    let mut pagination = items.clone().paginate(page);
+fn get_paginated_page<T: Clone>(items: &[T], page: i64, per_page: i64) -> &[T] {
+    let mut pagination = items.to_vec().paginate(page);
     pagination = pagination.per_page(per_page);
 
     let start = pagination.offset as usize;
@@ -189,9 +328,14 @@ pub fn router(service_injector: ServiceInjector) -> Router {
 }
 
 pub fn prepare_target(service_data: ServiceData) -> Service {
+    let database = TestDatabaseService::new(service_data);
+    let storage = TestStorage::new(database.reporter.clone());
+    let notifier = TestNotifier::new(database.reporter.clone());
+
     let services = InjectableServices {
-        database: TestDatabaseService::new(service_data),
-        notifier: ConsoleNotifier,
+        database,
+        notifier,
+        storage,
     };
 
     let service_injector = ServiceInjector::new(services);
@@ -203,8 +347,9 @@ pub fn prepare_target(service_data: ServiceData) -> Service {
 
 pub fn prepare_api_service(service_data: ServiceData, reporter: Arc<Mutex<Reporter>>) -> Service {
     let services = InjectableServices {
-        database: TestDatabaseService::with_reporter(service_data, reporter),
-        notifier: ConsoleNotifier,
+        database: TestDatabaseService::with_reporter(service_data, reporter.clone()),
+        notifier: TestNotifier::new(reporter.clone()),
+        storage: TestStorage::new(reporter),
     };
 
     let service_injector = ServiceInjector::new(services);
@@ -214,6 +359,50 @@ pub fn prepare_api_service(service_data: ServiceData, reporter: Arc<Mutex<Report
      Service::new(router)
 }
 
+/// Creation and update time of every row of the doubles
+pub fn timestamp() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()
+}
+
+/// Mirrors the sync filters of the diesel repos, `stamps` gives the id, created_at and updated_at of an entry
+pub fn sync_filtered<T>(entries: Vec<T>, filter: &SyncFilter, stamps: impl Fn(&T) -> (i32, NaiveDateTime, NaiveDateTime)) -> Vec<T> {
+    let mut entries: Vec<T> = entries.into_iter()
+        .filter(|entry| filter.updated_since.is_none_or(|since| stamps(entry).2 >= since))
+        .collect();
+
+    entries.sort_by(|a, b| {
+        let (a_id, a_created, a_updated) = stamps(a);
+        let (b_id, b_created, b_updated) = stamps(b);
+
+        let ordering = match filter.sort_by.unwrap_or_default() {
+            SortField::Id => a_id.cmp(&b_id),
+            SortField::CreatedAt => a_created.cmp(&b_created),
+            SortField::UpdatedAt => a_updated.cmp(&b_updated),
+        };
+
+        match filter.direction {
+            SortDirection::Asc => ordering,
+            SortDirection::Desc => ordering.reverse(),
+        }.then(a_id.cmp(&b_id))
+    });
+
+    entries
+}
+
+/// A plain active user notified in the given locale
+pub fn user(id: i32, locale: &str) -> User {
+    User {
+        id,
+        name: format!("User {id}"),
+        role: "USER".to_string(),
+        email: format!("user{id}@dummy.test"),
+        active: true,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        locale: locale.to_string(),
+    }
+}
+
 pub fn get_user_and_token() -> (AuthUser, String) {
     let admin = AuthUser {
         id: 2,
@@ -222,6 +411,9 @@ pub fn get_user_and_token() -> (AuthUser, String) {
         role: "USER".to_string(),
         active: true,
         password: vec![],
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        locale: "en".to_string(),
     };
 
     let auth_token = auth::create_bearer_token(&admin).unwrap();
@@ -237,6 +429,9 @@ pub fn get_admin_and_token() -> (AuthUser, String) {
         role: "ADMIN".to_string(),
         active: true,
         password: vec![],
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        locale: "en".to_string(),
     };
 
     let auth_token = auth::create_bearer_token(&admin).unwrap();
@@ -0,0 +1,68 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::audit::models::{AuditFilter, AuditLog, NewAuditLog};
+use fishbowl::services::database::contracts;
+use super::{timestamp, get_paginated_page, MockService, Reporter};
+
+pub struct TestAuditRepo {
+    pub data: Vec<AuditLog>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl TestAuditRepo {
+    /// Mirrors the filtered query of the diesel repo
+    fn filtered(&self, filter: &AuditFilter) -> Vec<AuditLog> {
+        let mut logs: Vec<AuditLog> = self.data().into_iter()
+            .filter(|log| filter.actor_id.is_none_or(|actor_id| log.actor_id == Some(actor_id)))
+            .filter(|log| filter.action.as_ref().is_none_or(|action| &log.action == action))
+            .filter(|log| filter.resource_type.as_ref().is_none_or(|resource_type| &log.resource_type == resource_type))
+            .filter(|log| filter.resource_id.is_none_or(|resource_id| log.resource_id == Some(resource_id)))
+            .filter(|log| filter.from.is_none_or(|from| log.created_at >= from))
+            .filter(|log| filter.to.is_none_or(|to| log.created_at <= to))
+            .collect();
+
+        logs.sort_by_key(|log| std::cmp::Reverse(log.id));
+
+        logs
+    }
+}
+
+impl MockService<AuditLog> for TestAuditRepo {
+    fn new(data: Vec<AuditLog>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<AuditLog> {
+        self.data.clone()
+    }
+}
+
+impl contracts::AuditRepo for TestAuditRepo {
+    /// Registers the call along with the action, e.g. `audit_repo.insert.product.update`
+    fn insert(&self, new_log: NewAuditLog) -> Result<AuditLog, Error> {
+        let NewAuditLog { actor_id, action, resource_type, resource_id, changes, ip } = new_log;
+
+        let mut reporter = self.reporter.lock().expect("Locking Reporter failed");
+
+        reporter.register_fn_call("audit_repo.insert");
+        reporter.register_fn_call(&format!("audit_repo.insert.{resource_type}.{action}"));
+
+        let id = self.data.iter().map(|log| log.id + 1).max().unwrap_or(1);
+
+        Ok(AuditLog { id, actor_id, action, resource_type, resource_id, changes, ip, created_at: timestamp() })
+    }
+
+    fn list(&self, filter: &AuditFilter) -> Result<Vec<AuditLog>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("audit_repo.list");
+
+        Ok(self.filtered(filter))
+    }
+
+    fn list_paginated(&self, filter: &AuditFilter, page: i64, per_page: i64) -> Result<(i64, Vec<AuditLog>), Error> {
+        let logs = self.filtered(filter);
+
+        Ok((logs.len() as i64, get_paginated_page(&logs, page, per_page).to_vec()))
+    }
+}
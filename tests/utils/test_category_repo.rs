@@ -0,0 +1,95 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::categories::models::{Category, NewCategory};
+use fishbowl::services::database::contracts;
+use super::{MockService, Reporter};
+
+pub struct TestCategoryRepo {
+    pub data: Vec<Category>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl MockService<Category> for TestCategoryRepo {
+    fn new(data: Vec<Category>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<Category> {
+        self.data.clone()
+    }
+}
+
+impl contracts::CategoryRepo for TestCategoryRepo {
+    fn list(&self) -> Result<Vec<Category>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("category_repo.list");
+
+        Ok(self.data())
+    }
+
+    fn find_one(&self, id: i32) -> Result<Category, Error> {
+        self.data().into_iter()
+            .find(|c| c.id == id)
+            .ok_or(Error::NotFound)
+    }
+
+    fn find_by_slug(&self, slug: &str) -> Result<Category, Error> {
+        self.data().into_iter()
+            .find(|c| c.slug == slug)
+            .ok_or(Error::NotFound)
+    }
+
+    fn find_or_insert(&self, new_category: NewCategory) -> Result<Category, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("category_repo.find_or_insert");
+
+        match self.find_by_slug(&new_category.slug) {
+            Err(Error::NotFound) => self.insert(new_category),
+            found => found,
+        }
+    }
+
+    fn insert(&self, new_category: NewCategory) -> Result<Category, Error> {
+        let NewCategory { name, slug, parent_id } = new_category;
+        let id = self.data().iter().map(|c| c.id).max().unwrap_or(0) + 1;
+
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("category_repo.insert");
+
+        Ok(Category { id, name, slug, parent_id })
+    }
+
+    fn update(&self, category: &Category) -> Result<Category, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("category_repo.update");
+
+        Ok(category.clone())
+    }
+
+    fn delete(&self, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("category_repo.delete");
+
+        Ok(self.data().iter().filter(|c| c.id == id).count())
+    }
+
+    fn descendant_ids(&self, id: i32) -> Result<Vec<i32>, Error> {
+        let categories = self.data();
+        let mut ids = vec![id];
+        let mut index = 0;
+
+        while index < ids.len() {
+            let parent_id = ids[index];
+
+            ids.extend(categories.iter().filter(|c| c.parent_id == Some(parent_id)).map(|c| c.id));
+            index += 1;
+        }
+
+        Ok(ids)
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::collaborators::models::{Collaborator, NewCollaborator};
+use fishbowl::services::database::contracts;
+use super::{MockService, Reporter};
+
+pub struct TestCollaboratorRepo {
+    pub data: Vec<Collaborator>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl MockService<Collaborator> for TestCollaboratorRepo {
+    fn new(data: Vec<Collaborator>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<Collaborator> {
+        self.data.clone()
+    }
+}
+
+impl contracts::CollaboratorRepo for TestCollaboratorRepo {
+    fn list_by_wishlist(&self, wishlist_id: i32) -> Result<Vec<Collaborator>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("collaborator_repo.list_by_wishlist");
+
+        Ok(self.data().into_iter().filter(|c| c.wishlist_id == wishlist_id).collect())
+    }
+
+    fn find_one(&self, wishlist_id: i32, user_id: i32) -> Result<Collaborator, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("collaborator_repo.find_one");
+
+        self.data().into_iter()
+            .find(|c| c.wishlist_id == wishlist_id && c.user_id == user_id)
+            .ok_or(Error::NotFound)
+    }
+
+    fn insert(&self, new_collaborator: NewCollaborator) -> Result<Collaborator, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("collaborator_repo.insert");
+
+        let NewCollaborator { wishlist_id, user_id, role } = new_collaborator;
+
+        Ok(Collaborator { id: 1, wishlist_id, user_id, role })
+    }
+
+    fn delete(&self, wishlist_id: i32, user_id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("collaborator_repo.delete");
+
+        Ok(self.data().iter().filter(|c| c.wishlist_id == wishlist_id && c.user_id == user_id).count())
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::followers::models::{Follower, NewFollower};
+use fishbowl::services::database::contracts;
+use super::{MockService, Reporter};
+
+pub struct TestFollowerRepo {
+    pub data: Vec<Follower>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl MockService<Follower> for TestFollowerRepo {
+    fn new(data: Vec<Follower>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<Follower> {
+        self.data.clone()
+    }
+}
+
+impl contracts::FollowerRepo for TestFollowerRepo {
+    fn insert(&self, _new_follower: NewFollower) -> Result<Follower, Error> {
+        todo!()
+    }
+
+    fn update(&self, _follower: &Follower) -> Result<Follower, Error> {
+        todo!()
+    }
+
+    fn delete(&self, _id: i32) -> Result<usize, Error> {
+        todo!()
+    }
+
+    fn is_following(&self, wishlist_id: i32, user_id: i32) -> Result<bool, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("follower_repo.is_following");
+
+        Ok(self.data().iter().any(|f| f.wishlist_id == wishlist_id && f.user_id == user_id && f.active))
+    }
+}
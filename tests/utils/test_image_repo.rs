@@ -0,0 +1,71 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::images::models::{Image, NewImage};
+use fishbowl::services::database::contracts;
+use super::{MockService, Reporter};
+
+pub struct TestImageRepo {
+    pub data: Vec<Image>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl MockService<Image> for TestImageRepo {
+    fn new(data: Vec<Image>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<Image> {
+        self.data.clone()
+    }
+}
+
+impl contracts::ImageRepo for TestImageRepo {
+    fn find_one(&self, id: i32) -> Result<Image, Error> {
+        self.data().into_iter()
+            .find(|i| i.id == id)
+            .ok_or(Error::NotFound)
+    }
+
+    fn list_by_product(&self, product_id: i32) -> Result<Vec<Image>, Error> {
+        Ok(self.data().into_iter()
+            .filter(|i| i.product_id == Some(product_id))
+            .collect()
+        )
+    }
+
+    fn find_wishlist_cover(&self, wishlist_id: i32) -> Result<Image, Error> {
+        self.data().into_iter()
+            .find(|i| i.wishlist_id == Some(wishlist_id))
+            .ok_or(Error::NotFound)
+    }
+
+    fn insert(&self, new_image: NewImage) -> Result<Image, Error> {
+        let NewImage { product_id, wishlist_id, content_type, path, thumbnail_path } = new_image;
+        let id = self.data().iter().map(|i| i.id).max().unwrap_or(0) + 1;
+
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("image_repo.insert");
+
+        Ok(Image { id, product_id, wishlist_id, content_type, path, thumbnail_path })
+    }
+
+    fn replace_wishlist_cover(&self, new_image: NewImage) -> Result<(Image, Option<Image>), Error> {
+        let previous = new_image.wishlist_id
+            .and_then(|wishlist_id| self.find_wishlist_cover(wishlist_id).ok());
+
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("image_repo.replace_wishlist_cover");
+
+        Ok((self.insert(new_image)?, previous))
+    }
+
+    fn delete(&self, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("image_repo.delete");
+
+        Ok(self.data().iter().filter(|i| i.id == id).count())
+    }
+}
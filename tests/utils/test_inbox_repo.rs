@@ -0,0 +1,83 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::inbox::models::{NewNotification, Notification};
+use fishbowl::services::database::contracts;
+use super::{timestamp, get_paginated_page, MockService, Reporter};
+
+pub struct TestInboxRepo {
+    pub data: Vec<Notification>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl TestInboxRepo {
+    /// Mirrors the filtered query of the diesel repo
+    fn filtered(&self, user_id: i32, unread: bool) -> Vec<Notification> {
+        let mut notifications: Vec<Notification> = self.data().into_iter()
+            .filter(|notification| notification.user_id == user_id)
+            .filter(|notification| !unread || notification.read_at.is_none())
+            .collect();
+
+        notifications.sort_by_key(|notification| std::cmp::Reverse(notification.id));
+
+        notifications
+    }
+}
+
+impl MockService<Notification> for TestInboxRepo {
+    fn new(data: Vec<Notification>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<Notification> {
+        self.data.clone()
+    }
+}
+
+impl contracts::InboxRepo for TestInboxRepo {
+    /// Registers the call along with the kind of message, e.g. `inbox_repo.insert.wish_funded`
+    fn insert(&self, new_notification: NewNotification) -> Result<Notification, Error> {
+        let NewNotification { user_id, kind, message } = new_notification;
+
+        let mut reporter = self.reporter.lock().expect("Locking Reporter failed");
+
+        reporter.register_fn_call("inbox_repo.insert");
+        reporter.register_fn_call(&format!("inbox_repo.insert.{kind}"));
+
+        let id = self.data.iter().map(|notification| notification.id + 1).max().unwrap_or(1);
+
+        Ok(Notification {
+            id,
+            user_id,
+            kind,
+            message,
+            read_at: None,
+            created_at: timestamp(),
+        })
+    }
+
+    fn list_paginated(&self, user_id: i32, unread: bool, page: i64, per_page: i64) -> Result<(i64, Vec<Notification>), Error> {
+        let notifications = self.filtered(user_id, unread);
+
+        Ok((notifications.len() as i64, get_paginated_page(&notifications, page, per_page).to_vec()))
+    }
+
+    fn count_unread(&self, user_id: i32) -> Result<i64, Error> {
+        Ok(self.filtered(user_id, true).len() as i64)
+    }
+
+    fn mark_read(&self, user_id: i32, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("inbox_repo.mark_read");
+
+        Ok(self.data.iter().filter(|notification| notification.id == id && notification.user_id == user_id).count())
+    }
+
+    fn mark_all_read(&self, user_id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("inbox_repo.mark_all_read");
+
+        Ok(self.filtered(user_id, true).len())
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::{Arc, Mutex};
+use fishbowl::api::resources::users::models::User;
+use fishbowl::services::notifications::contracts::Notifier;
+use fishbowl::services::notifications::messages::{Locale, Message};
+use fishbowl::services::notifications::DeliveryError;
+use super::Reporter;
+
+/// Registers each message as `notifier.send.{kind}.{locale}`
+pub struct TestNotifier {
+    pub reporter: Arc<Mutex<Reporter>>,
+    /// Every delivery fails, like an unreachable mail server
+    pub failing: bool,
+}
+
+impl TestNotifier {
+    pub fn new(reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { reporter, failing: false }
+    }
+
+    pub fn failing(reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { reporter, failing: true }
+    }
+}
+
+impl Notifier for TestNotifier {
+    fn send(&self, recipient: &User, message: &Message) -> Result<(), DeliveryError> {
+        let locale = Locale::from(recipient.locale.as_str());
+
+        let mut reporter = self.reporter.lock().expect("Locking Reporter failed");
+
+        reporter.register_fn_call("notifier.send");
+        reporter.register_fn_call(&format!("notifier.send.{}.{locale}", message.kind()));
+
+        match self.failing {
+            true => Err(DeliveryError("Connection refused".to_string())),
+            false => Ok(()),
+        }
+    }
+}
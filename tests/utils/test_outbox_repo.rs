@@ -0,0 +1,118 @@
+use std::sync::{Arc, Mutex};
+use chrono::Duration;
+use diesel::result::Error;
+use fishbowl::api::resources::outbox::models::{NewOutboxEntry, OutboxEntry, OutboxStatus, OutboxSummary};
+use fishbowl::services::database::contracts;
+use super::{timestamp, get_paginated_page, MockService, Reporter};
+
+pub struct TestOutboxRepo {
+    pub data: Vec<OutboxEntry>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl TestOutboxRepo {
+    /// Mirrors the filtered query of the diesel repo
+    fn filtered(&self, status: Option<OutboxStatus>) -> Vec<OutboxEntry> {
+        let mut entries: Vec<OutboxEntry> = self.data().into_iter()
+            .filter(|entry| status.is_none_or(|status| entry.status == status))
+            .collect();
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.id));
+
+        entries
+    }
+
+    /// Registers the call and whether the entry exists
+    fn update(&self, fn_name: &str, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call(fn_name);
+
+        Ok(self.data.iter().filter(|entry| entry.id == id).count())
+    }
+}
+
+impl MockService<OutboxEntry> for TestOutboxRepo {
+    fn new(data: Vec<OutboxEntry>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<OutboxEntry> {
+        self.data.clone()
+    }
+}
+
+impl contracts::OutboxRepo for TestOutboxRepo {
+    /// Registers the call along with the kind of message, e.g. `outbox_repo.insert.account_activation`
+    fn insert(&self, new_entry: NewOutboxEntry) -> Result<OutboxEntry, Error> {
+        let NewOutboxEntry { user_id, kind, message } = new_entry;
+
+        let mut reporter = self.reporter.lock().expect("Locking Reporter failed");
+
+        reporter.register_fn_call("outbox_repo.insert");
+        reporter.register_fn_call(&format!("outbox_repo.insert.{kind}"));
+
+        let id = self.data.iter().map(|entry| entry.id + 1).max().unwrap_or(1);
+
+        Ok(OutboxEntry {
+            id,
+            user_id,
+            kind,
+            message,
+            status: OutboxStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: timestamp(),
+            sent_at: None,
+            created_at: timestamp(),
+            updated_at: timestamp(),
+        })
+    }
+
+    fn list(&self, status: Option<OutboxStatus>) -> Result<Vec<OutboxEntry>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("outbox_repo.list");
+
+        Ok(self.filtered(status))
+    }
+
+    fn list_paginated(&self, status: Option<OutboxStatus>, page: i64, per_page: i64) -> Result<(i64, Vec<OutboxEntry>), Error> {
+        let entries = self.filtered(status);
+
+        Ok((entries.len() as i64, get_paginated_page(&entries, page, per_page).to_vec()))
+    }
+
+    fn summary(&self) -> Result<OutboxSummary, Error> {
+        Ok(self.data.iter().fold(OutboxSummary::default(), |summary, entry| summary.add(entry.status, 1)))
+    }
+
+    /// Every pending entry is due
+    fn claim_due(&self, limit: i64, _lease: Duration) -> Result<Vec<OutboxEntry>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("outbox_repo.claim_due");
+
+        Ok(self.filtered(Some(OutboxStatus::Pending)).into_iter().rev().take(limit as usize).collect())
+    }
+
+    fn mark_sent(&self, id: i32) -> Result<usize, Error> {
+        self.update("outbox_repo.mark_sent", id)
+    }
+
+    fn reschedule(&self, id: i32, _error: &str, _delay: Duration) -> Result<usize, Error> {
+        self.update("outbox_repo.reschedule", id)
+    }
+
+    fn mark_failed(&self, id: i32, _error: &str) -> Result<usize, Error> {
+        self.update("outbox_repo.mark_failed", id)
+    }
+
+    fn retry(&self, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("outbox_repo.retry");
+
+        Ok(self.data.iter().filter(|entry| entry.id == id && entry.status == OutboxStatus::Failed).count())
+    }
+}
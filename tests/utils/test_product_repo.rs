@@ -1,17 +1,97 @@
 use std::sync::{Arc, Mutex};
 use diesel::result::Error;
-use fishbowl::api::resources::products::models::{Product, ListedProduct, NewProduct};
+use fishbowl::api::resources::products::models::{Product, ListedProduct, NewProduct, NewProductChange, ProductChange, ProductFilter, ProductSortField, ReviewStatus};
+use fishbowl::api::utils::sorting::SortDirection;
+use fishbowl::api::resources::categories::models::{Category, ListedCategory};
+use fishbowl::api::resources::images::models::Image;
+use fishbowl::api::resources::tags::models::{ProductTag, Tag};
+use fishbowl::api::resources::wishes::models::Wish;
+use fishbowl::models::Composable;
 use fishbowl::services::database::contracts;
-use super::{MockService, get_paginated_page, Reporter};
+use super::{timestamp, MockService, get_paginated_page, Reporter};
 
 pub struct TestProductRepo {
     pub data: Vec<Product>,
+    pub wishes: Vec<Wish>,
+    pub tags: Vec<Tag>,
+    pub product_tags: Vec<ProductTag>,
+    pub changes: Vec<ProductChange>,
+    pub categories: Vec<Category>,
     pub reporter: Arc<Mutex<Reporter>>,
 }
 
+impl TestProductRepo {
+    pub fn with_wishes(self, wishes: Vec<Wish>) -> Self {
+        Self { wishes, ..self }
+    }
+
+    pub fn with_tags(self, tags: Vec<Tag>, product_tags: Vec<ProductTag>) -> Self {
+        Self { tags, product_tags, ..self }
+    }
+
+    pub fn with_changes(self, changes: Vec<ProductChange>) -> Self {
+        Self { changes, ..self }
+    }
+
+    pub fn with_categories(self, categories: Vec<Category>) -> Self {
+        Self { categories, ..self }
+    }
+
+    /// Mirrors the category join of the catalog
+    fn listed(&self, product: &Product) -> ListedProduct {
+        let category = self.categories.iter()
+            .find(|c| Some(c.id) == product.category_id)
+            .map(|c| ListedCategory::from(c.clone()));
+
+        ListedProduct::compose(product.clone(), category)
+    }
+
+    fn tagged(&self, product_id: i32, name: &str) -> bool {
+        self.tags.iter()
+            .filter(|t| t.name == name)
+            .any(|t| self.product_tags.contains(&ProductTag { product_id, tag_id: t.id }))
+    }
+
+    /// Mirrors the catalog query of the diesel repo
+    fn catalog(&self, filter: &ProductFilter) -> Vec<Product> {
+        let q = filter.q.as_ref().map(|q| q.to_lowercase());
+
+        let mut products: Vec<Product> = self.data().into_iter()
+            .filter(|p| p.review_status == ReviewStatus::Approved)
+            .filter(|p| q.as_ref().is_none_or(|q| {
+                p.name.to_lowercase().contains(q)
+                    || p.description.as_ref().is_some_and(|d| d.to_lowercase().contains(q))
+            }))
+            .filter(|p| filter.min_price.is_none_or(|min| p.price >= min))
+            .filter(|p| filter.max_price.is_none_or(|max| p.price <= max))
+            .filter(|p| filter.available.is_none_or(|available| p.available == available))
+            .filter(|p| filter.category_ids.as_ref().is_none_or(|ids| p.category_id.is_some_and(|id| ids.contains(&id))))
+            .filter(|p| filter.tag.as_ref().is_none_or(|tag| self.tagged(p.id, tag)))
+            .filter(|p| filter.updated_since.is_none_or(|since| p.updated_at >= since))
+            .collect();
+
+        products.sort_by(|a, b| {
+            let ordering = match filter.sort_by {
+                ProductSortField::Id => a.id.cmp(&b.id),
+                ProductSortField::Name => a.name.cmp(&b.name),
+                ProductSortField::Price => a.price.total_cmp(&b.price),
+                ProductSortField::CreatedAt => a.created_at.cmp(&b.created_at),
+                ProductSortField::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+            };
+
+            match filter.direction {
+                SortDirection::Asc => ordering,
+                SortDirection::Desc => ordering.reverse(),
+            }.then(a.id.cmp(&b.id))
+        });
+
+        products
+    }
+}
+
 impl MockService<Product> for TestProductRepo {
     fn new(data: Vec<Product>, reporter: Arc<Mutex<Reporter>>) -> Self {
-        Self { data, reporter }
+        Self { data, wishes: vec![], tags: vec![], product_tags: vec![], changes: vec![], categories: vec![], reporter }
     }
 
     fn data(&self) -> Vec<Product> {
@@ -30,24 +110,24 @@ impl contracts::ProductRepo for TestProductRepo {
         }
     }
 
-    fn list(&self) -> Result<Vec<ListedProduct>, Error> {
+    fn list(&self, filter: &ProductFilter) -> Result<Vec<ListedProduct>, Error> {
         self.reporter.lock()
             .expect("Error locking reporter")
             .register_fn_call("product_repo.list");
         
-        Ok(self.data().iter()
-            .map(|p| ListedProduct::from(p.clone()))
+        Ok(self.catalog(filter).iter()
+            .map(|p| self.listed(p))
             .collect()
         )
     }
 
-    fn list_paginated(&self, page: i64, per_page: i64) -> Result<(i64, Vec<ListedProduct>), Error> {
-        let products = self.data();
+    fn list_paginated(&self, filter: &ProductFilter, page: i64, per_page: i64) -> Result<(i64, Vec<ListedProduct>), Error> {
+        let products = self.catalog(filter);
         let entries = products.len() as i64;
 
         let grouped: Vec<ListedProduct> = get_paginated_page(&products, page, per_page)
             .iter()
-            .map(|p| ListedProduct::from(p.clone()))
+            .map(|p| self.listed(p))
             .collect();
 
         self.reporter.lock()
@@ -59,13 +139,17 @@ impl contracts::ProductRepo for TestProductRepo {
 
     fn insert(&self, new_product: NewProduct) -> Result<Product, Error> {
         let products = self.data();
-        let NewProduct { name, description, url, price, available } = new_product;
+        let NewProduct { name, description, url, price, available, review_status, submitted_by, category_id } = new_product;
         let id = match products.last() {
             None => 1,
             Some(p) => p.id + 1,
         };
 
-        let product = Product { id, name, description, url, price, available };
+        let product = Product { id, name, description, url, price, available, review_status, submitted_by, category_id, created_at: timestamp(), updated_at: timestamp() };
+
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("product_repo.insert");
 
         Ok(product)
     }
@@ -73,16 +157,56 @@ impl contracts::ProductRepo for TestProductRepo {
     fn delete(&self, id: i32) -> Result<usize, Error> {
         let products = self.data();
 
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("product_repo.delete");
+
         match products.iter().find(|p| p.id == id) {
             None => Err(Error::NotFound),
             Some(_) => Ok(1),
         }
     }
 
+    fn restore(&self, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("product_repo.restore");
+
+        Ok(self.data.iter().filter(|item| item.id == id).count())
+    }
+
+    fn purge(&self, _retention_days: i32) -> Result<(usize, Vec<Image>), Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("product_repo.purge");
+
+        Ok((0, vec![]))
+    }
+
     fn update(&self, product: &Product) -> Result<Product, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("product_repo.update");
+
+        let previous = self.find_one(product.id)?;
+
+        if NewProductChange::between(&previous, product).is_some() {
+            self.reporter.lock()
+                .expect("Locking Reporter failed")
+                .register_fn_call("product_repo.record_change");
+        }
+
         Ok(product.clone())
     }
 
+    fn list_changes(&self, id: i32) -> Result<Vec<ProductChange>, Error> {
+        Ok(self.changes.iter()
+            .filter(|c| c.product_id == id)
+            .cloned()
+            .collect()
+        )
+    }
+
     fn insert_many(&self, products: Vec<NewProduct>) -> Result<usize, Error> {
         self.reporter.lock()
             .expect("Locking Reporter failed")
@@ -90,5 +214,27 @@ impl contracts::ProductRepo for TestProductRepo {
 
         Ok(products.len())
     }
-}
 
+    fn list_by_review_status(&self, review_status: ReviewStatus) -> Result<Vec<Product>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("product_repo.list_by_review_status");
+
+        Ok(self.data().into_iter()
+            .filter(|p| p.review_status == review_status)
+            .collect()
+        )
+    }
+
+    fn merge(&self, _survivor_id: i32, duplicate_ids: &[i32]) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("product_repo.merge");
+
+        Ok(duplicate_ids.len())
+    }
+
+    fn count_wishes(&self, id: i32) -> Result<i64, Error> {
+        Ok(self.wishes.iter().filter(|w| w.product_id == id).count() as i64)
+    }
+}
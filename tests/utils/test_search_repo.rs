@@ -0,0 +1,38 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::search::models::{SearchHit, SearchLanguage};
+use fishbowl::services::database::contracts;
+use super::{MockService, get_paginated_page, Reporter};
+
+pub struct TestSearchRepo {
+    pub data: Vec<SearchHit>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl MockService<SearchHit> for TestSearchRepo {
+    fn new(data: Vec<SearchHit>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<SearchHit> {
+        self.data.clone()
+    }
+}
+
+impl contracts::SearchRepo for TestSearchRepo {
+    fn search(&self, _language: SearchLanguage, q: &str, page: i64, per_page: i64) -> Result<(i64, Vec<SearchHit>), Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("search_repo.search");
+
+        let q = q.to_lowercase();
+
+        let hits: Vec<SearchHit> = self.data().into_iter()
+            .filter(|hit| hit.title.to_lowercase().contains(&q))
+            .collect();
+
+        let page_hits = get_paginated_page(&hits, page, per_page).to_vec();
+
+        Ok((hits.len() as i64, page_hits))
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::{Arc, Mutex};
+use chrono::{NaiveDateTime, Utc};
+use diesel::result::Error;
+use fishbowl::api::resources::shares::models::{NewShareLink, ShareLink};
+use fishbowl::services::database::contracts;
+use super::{MockService, Reporter};
+
+pub struct TestShareLinkRepo {
+    pub data: Vec<ShareLink>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+/// Mirrors the conditions of the diesel `find_active` query
+fn is_active(share_link: &ShareLink, now: NaiveDateTime) -> bool {
+    !share_link.revoked && share_link.expires_at.is_none_or(|expires_at| expires_at > now)
+}
+
+impl MockService<ShareLink> for TestShareLinkRepo {
+    fn new(data: Vec<ShareLink>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<ShareLink> {
+        self.data.clone()
+    }
+}
+
+impl contracts::ShareLinkRepo for TestShareLinkRepo {
+    fn list_by_wishlist(&self, wishlist_id: i32) -> Result<Vec<ShareLink>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("share_link_repo.list_by_wishlist");
+
+        Ok(self.data().into_iter().filter(|s| s.wishlist_id == wishlist_id).collect())
+    }
+
+    fn find_active(&self, token: &str) -> Result<ShareLink, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("share_link_repo.find_active");
+
+        let now = Utc::now().naive_utc();
+
+        self.data().into_iter()
+            .find(|s| s.token == token && is_active(s, now))
+            .ok_or(Error::NotFound)
+    }
+
+    fn insert(&self, new_share_link: NewShareLink) -> Result<ShareLink, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("share_link_repo.insert");
+
+        let NewShareLink { wishlist_id, token, expires_at } = new_share_link;
+
+        Ok(ShareLink { id: 1, wishlist_id, token, expires_at, revoked: false })
+    }
+
+    fn revoke(&self, wishlist_id: i32, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("share_link_repo.revoke");
+
+        Ok(self.data().iter().filter(|s| s.wishlist_id == wishlist_id && s.id == id).count())
+    }
+}
@@ -2,17 +2,29 @@ use std::sync::{Arc, Mutex};
 
 use fishbowl::{api::resources::sponsors::models::{NewSponsor, Sponsor}, db::contracts};
 
-use super::{MockService, Reporter};
+use fishbowl::api::resources::products::models::Product;
+use fishbowl::api::resources::wishes::models::Wish;
+use fishbowl::api::utils::sorting::SyncFilter;
+
+use super::{sync_filtered, timestamp, MockService, Reporter};
 
 
 pub struct TestSponsorRepo {
     pub data: Vec<Sponsor>,
+    pub wishes: Vec<Wish>,
+    pub products: Vec<Product>,
     pub reporter: Arc<Mutex<Reporter>>,
 }
 
+impl TestSponsorRepo {
+    pub fn with_wishes(self, wishes: Vec<Wish>, products: Vec<Product>) -> Self {
+        Self { wishes, products, ..self }
+    }
+}
+
 impl MockService<Sponsor> for TestSponsorRepo {
     fn new(data: Vec<Sponsor>, reporter: Arc<Mutex<Reporter>>) -> Self {
-        Self { data, reporter }
+        Self { data, wishes: vec![], products: vec![], reporter }
     }
 
     fn data(&self) -> Vec<Sponsor> {
@@ -21,17 +33,30 @@ impl MockService<Sponsor> for TestSponsorRepo {
 }
 
 impl contracts::SponsorRepo for TestSponsorRepo {
-    fn insert(&self, new_sponsor: NewSponsor) -> Result<Sponsor, diesel::result::Error> {
+    fn insert(&self, new_sponsor: NewSponsor) -> Result<(Sponsor, bool), diesel::result::Error> {
         let NewSponsor { leader, amount, user_id, wish_id } = new_sponsor;
 
         self.reporter.lock()
             .expect("Error locking reporter")
             .register_fn_call("sponsor_repo.insert");
 
-        Ok(Sponsor { id: 1, leader, amount, user_id, wish_id })
+        let price = self.wishes.iter()
+            .find(|w| w.id == wish_id)
+            .and_then(|wish| self.products.iter().find(|p| p.id == wish.product_id))
+            .map(|product| product.price)
+            .ok_or(diesel::result::Error::NotFound)?;
+
+        let previous: f32 = self.data.iter()
+            .filter(|s| s.wish_id == wish_id)
+            .map(|s| s.amount)
+            .sum();
+
+        let sponsor = Sponsor { id: 1, leader, amount, user_id, wish_id, created_at: timestamp(), updated_at: timestamp() };
+
+        Ok((sponsor, previous < price && previous + amount >= price))
     }
     
-    fn list_by_wish(&self, wish_id: i32) -> Result<Vec<Sponsor>, diesel::result::Error> {
+    fn list_by_wish(&self, wish_id: i32, filter: &SyncFilter) -> Result<Vec<Sponsor>, diesel::result::Error> {
         self.reporter.lock()
             .expect("Error locking reporter")
             .register_fn_call("sponsor_repo.list_by_wish");
@@ -40,7 +65,7 @@ impl contracts::SponsorRepo for TestSponsorRepo {
             .filter(|s| s.wish_id == wish_id)
             .collect();
 
-        Ok(sponsors)
+        Ok(sync_filtered(sponsors, filter, |sponsor| (sponsor.id, sponsor.created_at, sponsor.updated_at)))
     }
 }
 
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+use fishbowl::services::storage::contracts::Storage;
+use super::Reporter;
+
+/// Keeps the files in memory, shared by all the requests to the same service
+pub struct TestStorage {
+    pub files: Mutex<HashMap<String, Vec<u8>>>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl TestStorage {
+    pub fn new(reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { files: Mutex::new(HashMap::new()), reporter }
+    }
+}
+
+impl Storage for TestStorage {
+    fn put(&self, key: &str, content: &[u8]) -> io::Result<()> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("storage.put");
+
+        self.files.lock().unwrap().insert(key.to_string(), content.to_vec());
+
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> io::Result<Vec<u8>> {
+        self.files.lock().unwrap()
+            .get(key)
+            .cloned()
+            .ok_or(io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn delete(&self, key: &str) -> io::Result<()> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("storage.delete");
+
+        self.files.lock().unwrap().remove(key);
+
+        Ok(())
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("/api/v1/uploads/{key}")
+    }
+}
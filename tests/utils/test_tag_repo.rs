@@ -0,0 +1,75 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::tags::models::{ProductTag, Tag};
+use fishbowl::services::database::contracts;
+use super::{MockService, Reporter};
+
+pub struct TestTagRepo {
+    pub data: Vec<Tag>,
+    pub product_tags: Vec<ProductTag>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl TestTagRepo {
+    pub fn with_product_tags(self, product_tags: Vec<ProductTag>) -> Self {
+        Self { product_tags, ..self }
+    }
+}
+
+impl MockService<Tag> for TestTagRepo {
+    fn new(data: Vec<Tag>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, product_tags: vec![], reporter }
+    }
+
+    fn data(&self) -> Vec<Tag> {
+        self.data.clone()
+    }
+}
+
+impl contracts::TagRepo for TestTagRepo {
+    fn list(&self) -> Result<Vec<Tag>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("tag_repo.list");
+
+        Ok(self.data())
+    }
+
+    fn list_by_product(&self, product_id: i32) -> Result<Vec<Tag>, Error> {
+        Ok(self.data().into_iter()
+            .filter(|t| self.product_tags.contains(&ProductTag { product_id, tag_id: t.id }))
+            .collect()
+        )
+    }
+
+    fn set_product_tags(&self, _product_id: i32, names: &[String]) -> Result<Vec<Tag>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("tag_repo.set_product_tags");
+
+        let mut tags = self.data();
+        let mut next_id = tags.iter().map(|t| t.id).max().unwrap_or(0);
+
+        Ok(names.iter()
+            .map(|name| match tags.iter().find(|t| &t.name == name) {
+                Some(tag) => tag.clone(),
+                None => {
+                    next_id += 1;
+                    let tag = Tag { id: next_id, name: name.clone() };
+                    tags.push(tag.clone());
+
+                    tag
+                },
+            })
+            .collect()
+        )
+    }
+
+    fn delete(&self, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("tag_repo.delete");
+
+        Ok(self.data().iter().filter(|t| t.id == id).count())
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::{Arc, Mutex};
+use diesel::result::Error;
+use fishbowl::api::resources::templates::models::{DetailedTemplate, NewTemplate, Template};
+use fishbowl::services::database::contracts;
+use super::{MockService, Reporter};
+
+pub struct TestTemplateRepo {
+    pub data: Vec<DetailedTemplate>,
+    pub reporter: Arc<Mutex<Reporter>>,
+}
+
+impl MockService<DetailedTemplate> for TestTemplateRepo {
+    fn new(data: Vec<DetailedTemplate>, reporter: Arc<Mutex<Reporter>>) -> Self {
+        Self { data, reporter }
+    }
+
+    fn data(&self) -> Vec<DetailedTemplate> {
+        self.data.clone()
+    }
+}
+
+impl contracts::TemplateRepo for TestTemplateRepo {
+    fn list(&self) -> Result<Vec<Template>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("template_repo.list");
+
+        Ok(self.data().into_iter().map(Template::from).collect())
+    }
+
+    fn find_one(&self, id: i32) -> Result<DetailedTemplate, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("template_repo.find_one");
+
+        self.data().into_iter()
+            .find(|t| t.id == id)
+            .ok_or(Error::NotFound)
+    }
+
+    fn insert(&self, new_template: NewTemplate, _product_ids: Vec<i32>) -> Result<Template, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("template_repo.insert");
+
+        let NewTemplate { title, description } = new_template;
+
+        Ok(Template { id: 1, title, description })
+    }
+
+    fn update(&self, template: &Template, _product_ids: Option<Vec<i32>>) -> Result<Template, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("template_repo.update");
+
+        Ok(template.clone())
+    }
+
+    fn delete(&self, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("template_repo.delete");
+
+        Ok(self.data().iter().filter(|t| t.id == id).count())
+    }
+}
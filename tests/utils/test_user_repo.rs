@@ -1,8 +1,12 @@
 use std::sync::{Arc, Mutex};
-use diesel::result::Error;
-use fishbowl::api::resources::users::models::{User, NewUser};
-use fishbowl::services::database::contracts;
-use super::{MockService, Reporter};
+use diesel::result::{DatabaseErrorKind, Error};
+use fishbowl::api::resources::users::models::{EmailOwner, User, NewUser};
+use fishbowl::api::resources::outbox::models::NewOutboxEntry;
+use fishbowl::services::database::contracts::{self, OutboxRepo};
+use fishbowl::services::notifications::messages::Message;
+use super::test_outbox_repo::TestOutboxRepo;
+use fishbowl::api::utils::sorting::SyncFilter;
+use super::{sync_filtered, timestamp, MockService, Reporter};
 
 pub struct TestUserRepo {
     pub data: Vec<User>,
@@ -20,16 +24,29 @@ impl MockService<User> for TestUserRepo {
 }
 
 impl contracts::UserRepo for TestUserRepo {
-    fn list(&self) -> Result<Vec<User>, Error> {
+    fn list(&self, filter: &SyncFilter) -> Result<Vec<User>, Error> {
         self.reporter.lock()
             .expect("Locking Reporter failed")
             .register_fn_call("user_repo.list");
 
-        Ok(self.data())
+        Ok(sync_filtered(self.data(), filter, |user| (user.id, user.created_at, user.updated_at)))
     }
 
-    fn find_user(&self, _id: i32) -> Result<User, Error> {
-        todo!()
+    fn list_email_owners(&self) -> Result<Vec<EmailOwner>, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("user_repo.list_email_owners");
+
+        Ok(self.data.iter()
+            .map(|user| EmailOwner { id: user.id, name: user.name.clone(), email: user.email.clone(), active: user.active })
+            .collect())
+    }
+
+    fn find_user(&self, id: i32) -> Result<User, Error> {
+        self.data.iter()
+            .find(|user| user.id == id)
+            .cloned()
+            .ok_or(Error::NotFound)
     }
 
     fn insert_many(&self, users: Vec<NewUser>) ->Result<usize, Error> {
@@ -45,8 +62,21 @@ impl contracts::UserRepo for TestUserRepo {
             .expect("Locking Reporter failed")
             .register_fn_call("user_repo.insert");
 
-        let NewUser { name, email, active, .. } = new_user;
+        let NewUser { name, email, active, locale, .. } = new_user;
+
+        if self.data.iter().any(|user| user.email.to_lowercase() == email.to_lowercase()) {
+            return Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, Box::new("users_email_lower_key".to_string())));
+        }
+
+        Ok(User { id: 1, name, email, active, role: "USER".to_string(), created_at: timestamp(), updated_at: timestamp(), locale })
+    }
+
+    /// Queues the message in the outbox double, which registers it
+    fn insert_notified(&self, new_user: NewUser, message: &dyn Fn(&User) -> Message) -> Result<User, Error> {
+        let user = self.insert(new_user)?;
+
+        TestOutboxRepo::new(vec![], self.reporter.clone()).insert(NewOutboxEntry::new(user.id, &message(&user)))?;
 
-        Ok(User { id: 1, name, email, active, role: "USER".to_string() })
+        Ok(user)
     }
 }
@@ -1,33 +1,48 @@
 use std::sync::{Arc, Mutex};
 use diesel::result::Error;
-use fishbowl::api::resources::products::models::Product;
+use fishbowl::api::resources::products::models::{Product, ReviewStatus};
 use fishbowl::api::resources::wishes::models::{Wish, NewWish, WishProduct};
 use fishbowl::models::Composable;
 use fishbowl::services::database::contracts;
-use super::{MockService, Reporter};
+use super::{timestamp, MockService, Reporter};
 
 pub struct TestWishRepo {
-    pub data: Vec<Wish>,
+    /// Behind a lock so `reorder` can move the wishes
+    pub data: Mutex<Vec<Wish>>,
     pub reporter: Arc<Mutex<Reporter>>,
 }
 
 impl MockService<Wish> for TestWishRepo {
     fn new(data: Vec<Wish>, reporter: Arc<Mutex<Reporter>>) -> Self {
-        Self { data, reporter }
+        Self { data: Mutex::new(data), reporter }
     }
 
     fn data(&self) -> Vec<Wish> {
-        self.data.clone()
+        self.data.lock().expect("Locking wishes failed").clone()
     }
 }
 
 impl contracts::WishRepo for TestWishRepo {
-    fn list_by_wishlist(&self, _id: i32) -> Result<Vec<WishProduct>, Error> {
+    fn list_by_wishlist(&self, id: i32) -> Result<Vec<WishProduct>, Error> {
         self.reporter.lock()
             .expect("Locking Reporter failed")
             .register_fn_call("wish_repo.list_by_wishlist");
 
-        Ok(vec![])
+        let mut wishes: Vec<Wish> = self.data().into_iter()
+            .filter(|w| w.wishlist_id == id)
+            .collect();
+
+        wishes.sort_by_key(|w| (w.position, w.id));
+
+        let wish_products = wishes.into_iter()
+            .map(|wish| {
+                let product = Product { id: wish.product_id, name: "".to_string(), description: None, url: None, price: 2000.0, available: true, review_status: ReviewStatus::Approved, submitted_by: None, category_id: None, created_at: timestamp(), updated_at: timestamp() };
+
+                WishProduct::compose(wish, product)
+            })
+            .collect();
+
+        Ok(wish_products)
     }
     
     fn insert(&self, new_wish: NewWish) -> Result<Wish, Error> {
@@ -37,7 +52,13 @@ impl contracts::WishRepo for TestWishRepo {
 
         let NewWish { wishlist_id, product_id } = new_wish;
 
-        Ok(Wish { id: 3, wishlist_id, product_id, pending: true })
+        let position = self.data().iter()
+            .filter(|w| w.wishlist_id == wishlist_id)
+            .map(|w| w.position + 1)
+            .max()
+            .unwrap_or_default();
+
+        Ok(Wish { id: 3, wishlist_id, product_id, pending: true, position, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() })
     }
 
     fn find_one(&self, id: i32) -> Result<Wish, Error> {
@@ -45,9 +66,7 @@ impl contracts::WishRepo for TestWishRepo {
             .expect("Locking Reporter failed")
             .register_fn_call("wish_repo.find_one");
 
-        let wish = self.data.iter().find(|w| w.id == id).ok_or(Error::NotFound)?;
-
-        Ok(wish.clone())
+        self.data().into_iter().find(|w| w.id == id).ok_or(Error::NotFound)
     }
     
     fn find_one_expanded(&self, id: i32) -> Result<WishProduct, Error> {
@@ -55,10 +74,10 @@ impl contracts::WishRepo for TestWishRepo {
             .expect("")
             .register_fn_call("wish_repo.find_one_expanded");
 
-        let wish = self.data.iter().find(|w| w.id == id).ok_or(Error::NotFound)?;
+        let wish = self.data().into_iter().find(|w| w.id == id).ok_or(Error::NotFound)?;
 
-        let product = Product { id: 1, name: "".to_string(), description: None, url: None, price: 2000.0, available: true };
-        let wish_product = WishProduct::compose(wish.clone(), product);
+        let product = Product { id: 1, name: "".to_string(), description: None, url: None, price: 2000.0, available: true, review_status: ReviewStatus::Approved, submitted_by: None, category_id: None, created_at: timestamp(), updated_at: timestamp() };
+        let wish_product = WishProduct::compose(wish, product);
         Ok(wish_product)
     }
 
@@ -69,4 +88,36 @@ impl contracts::WishRepo for TestWishRepo {
 
         Ok(1)
     }
+
+    fn restore(&self, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("wish_repo.restore");
+
+        Ok(self.data().iter().filter(|item| item.id == id).count())
+    }
+
+    fn purge(&self, _retention_days: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("wish_repo.purge");
+
+        Ok(0)
+    }
+
+    fn reorder(&self, wishlist_id: i32, wish_ids: &[i32]) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("wish_repo.reorder");
+
+        let mut data = self.data.lock().expect("Locking wishes failed");
+
+        for wish in data.iter_mut().filter(|w| w.wishlist_id == wishlist_id) {
+            if let Some(position) = wish_ids.iter().position(|id| *id == wish.id) {
+                wish.position = position as i32;
+            }
+        }
+
+        Ok(wish_ids.len())
+    }
 }
@@ -1,8 +1,9 @@
 use std::sync::{Arc, Mutex};
 use diesel::result::Error;
 use fishbowl::api::resources::wishlists::models::{Wishlist, NewWishlist};
+use fishbowl::api::resources::images::models::Image;
 use fishbowl::services::database::contracts;
-use super::{MockService, Reporter};
+use super::{timestamp, MockService, Reporter};
 
 pub struct TestWishlistRepo {
     pub data: Vec<Wishlist>,
@@ -28,7 +29,7 @@ impl contracts::WishlistRepo for TestWishlistRepo {
 
         self.data().iter()
             .find(|w| w.id == id)
-            .map(|w| w.clone())
+            .cloned()
             .ok_or(Error::NotFound)
     }
 
@@ -38,9 +39,9 @@ impl contracts::WishlistRepo for TestWishlistRepo {
             .expect("Locking Reporter failed")
             .register_fn_call("wishlist_repo.insert");
 
-        let NewWishlist { title, description, date, user_id, published } = new_wishlist;
+        let NewWishlist { title, description, date, user_id, visibility } = new_wishlist;
 
-        let wishlist = Wishlist { id: 1, title, description, user_id, date, published };
+        let wishlist = Wishlist { id: 1, title, description, user_id, date, visibility, created_at: timestamp(), updated_at: timestamp() };
 
         Ok(wishlist)
     }
@@ -53,8 +54,22 @@ impl contracts::WishlistRepo for TestWishlistRepo {
         Ok(wishlists.len())
     }
         
-    fn update(&self, _wishlist: &Wishlist) -> Result<Wishlist, Error> {
-        todo!()
+    fn insert_with_products(&self, new_wishlist: NewWishlist, _product_ids: Vec<i32>) -> Result<Wishlist, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("wishlist_repo.insert_with_products");
+
+        let NewWishlist { title, description, date, user_id, visibility } = new_wishlist;
+
+        Ok(Wishlist { id: 2, title, description, user_id, date, visibility, created_at: timestamp(), updated_at: timestamp() })
+    }
+
+    fn update(&self, wishlist: &Wishlist) -> Result<Wishlist, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("wishlist_repo.update");
+
+        Ok(wishlist.clone())
     }
     
     fn delete(&self, _id: i32) -> Result<usize, Error> {
@@ -64,4 +79,20 @@ impl contracts::WishlistRepo for TestWishlistRepo {
 
         Ok(1)
     }
+
+    fn restore(&self, id: i32) -> Result<usize, Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("wishlist_repo.restore");
+
+        Ok(self.data.iter().filter(|item| item.id == id).count())
+    }
+
+    fn purge(&self, _retention_days: i32) -> Result<(usize, Vec<Image>), Error> {
+        self.reporter.lock()
+            .expect("Locking Reporter failed")
+            .register_fn_call("wishlist_repo.purge");
+
+        Ok((0, vec![]))
+    }
 }
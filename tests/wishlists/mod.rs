@@ -1,12 +1,13 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use fishbowl::api::resources::products::models::Product;
-use fishbowl::api::resources::wishlists::models::Wishlist;
+use fishbowl::api::resources::followers::models::Follower;
+use fishbowl::api::resources::products::models::{Product, ReviewStatus};
+use fishbowl::api::resources::wishlists::models::{Visibility, Wishlist};
 use salvo::test::{ResponseExt, TestClient};
 use fishbowl::api::resources::wishes::models::{Wish, WishProduct};
 use fishbowl::api::responses::ResourceResponse;
-use crate::utils::{get_admin_and_token, get_user_and_token};
+use crate::utils::{timestamp, get_admin_and_token, get_user_and_token};
 
 use super::utils::{prepare_api_service, ServiceData, BASE_URL, Reporter};
 
@@ -14,30 +15,44 @@ fn test_products() -> HashMap<String, Product> {
     let mut map = HashMap::new();
     map.insert("product1".to_string(), Product {
         id: 1,
-        name: format!("product 1"),
-        description: Some(format!("desc")),
-        url: Some(format!("any url")),
+        name: "product 1".to_string(),
+        description: Some("desc".to_string()),
+        url: Some("any url".to_string()),
         price: 34000.6,
-        available: true
-
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map.insert("product2".to_string(), Product {
         id: 2,
-        name: format!("product 2"),
-        description: Some(format!("desc for 2")),
+        name: "product 2".to_string(),
+        description: Some("desc for 2".to_string()),
         url: None,
         price: 120000.6,
-        available: true
+        available: true,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map.insert("product3".to_string(), Product {
         id: 3,
-        name: format!("product 3"),
+        name: "product 3".to_string(),
         description: None,
-        url: Some(format!("any url")),
+        url: Some("any url".to_string()),
         price: 34000.0,
-        available: false
+        available: false,
+        review_status: ReviewStatus::Approved,
+        submitted_by: None,
+        category_id: None,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map
@@ -50,7 +65,10 @@ fn test_wishes() -> HashMap<String, Wish> {
         wishlist_id: 1,
         product_id: 1,
         pending: true,
-
+        position: 0,
+        original_price: 2000.0,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map.insert("wish2".to_string(), Wish {
@@ -58,6 +76,10 @@ fn test_wishes() -> HashMap<String, Wish> {
         wishlist_id: 1,
         product_id: 2,
         pending: true,
+        position: 1,
+        original_price: 2000.0,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map.insert("wish3".to_string(), Wish {
@@ -65,6 +87,10 @@ fn test_wishes() -> HashMap<String, Wish> {
         wishlist_id: 1,
         product_id: 3,
         pending: false,
+        position: 2,
+        original_price: 2000.0,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     });
 
     map
@@ -104,7 +130,9 @@ async fn show_wishlist() {
         description: Some("A meaningful description".to_string()),
         date: None,
         user_id: 1,
-        published: true,
+        visibility: Visibility::Public,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     };
 
     let service_data = ServiceData::default()
@@ -134,12 +162,11 @@ async fn show_wishlist() {
         .await
         .unwrap();
 
-    let locked_reporter = reporter.lock().unwrap();
+    let (wishlist_calls, wish_calls) = {
+        let locked_reporter = reporter.lock().unwrap();
 
-    let wishlist_calls = locked_reporter.get_fn_calls("wishlist_repo.find_one");
-    let wish_calls = locked_reporter.get_fn_calls("wish_repo.list_by_wishlist");
-
-    drop(locked_reporter);
+        (locked_reporter.get_fn_calls("wishlist_repo.find_one"), locked_reporter.get_fn_calls("wish_repo.list_by_wishlist"))
+    };
 
     // -- assert 1
 
@@ -241,7 +268,9 @@ async fn add_wish() {
         description: Some("A meaningful description".to_string()),
         date: None,
         user_id: 1,
-        published: true,
+        visibility: Visibility::Public,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     };
 
     let service_data = ServiceData::default()
@@ -274,16 +303,28 @@ async fn add_wish() {
 
     // -- assert
 
-    let locked_reporter = reporter.lock().unwrap();
+    let (find_wishlist_calls, insert_wish_calls) = {
+        let locked_reporter = reporter.lock().unwrap();
 
-    let find_wishlist_calls = locked_reporter.get_fn_calls("wishlist_repo.find_one");
-    let insert_wish_calls = locked_reporter.get_fn_calls("wish_repo.insert");
-
-    drop(locked_reporter);
+        (locked_reporter.get_fn_calls("wishlist_repo.find_one"), locked_reporter.get_fn_calls("wish_repo.insert"))
+    };
 
     assert_eq!(status_code, 202,  "status code should be 202");
     assert_eq!(find_wishlist_calls, 1, "wishlist_repo.find_one() should be called once");
     assert_eq!(insert_wish_calls, 1, "wish_repo.insert() should be called once");
+
+    // -- run 2
+
+    let response = TestClient::post(format!("{BASE_URL}/wishlists/1/wishes"))
+        .add_header("authorization", &bearer, true)
+        .form(&[("wishlist_id", "1"), ("product_id", "2")])
+        .send(&target)
+        .await;
+
+    // -- assert 2
+
+    assert_eq!(response.status_code.unwrap(), 404, "deleted products can't be wished for");
+    assert_eq!(reporter.lock().unwrap().get_fn_calls("wish_repo.insert"), 1, "wish_repo.insert() shouldn't be called again");
 }
 
 #[tokio::test]
@@ -302,7 +343,9 @@ async fn show_wish() {
         description: Some("A meaningful description".to_string()),
         date: None,
         user_id: 2,
-        published: true,
+        visibility: Visibility::Public,
+        created_at: timestamp(),
+        updated_at: timestamp(),
     };
 
     let service_data = ServiceData::default()
@@ -337,15 +380,15 @@ async fn show_wish() {
 
     // ---
 
-    let mut locked_reporter = reporter.lock().unwrap();
-
-    let fn_called = locked_reporter.get_fn_calls("wish_repo.find_one_expanded");
+    {
+        let mut locked_reporter = reporter.lock().unwrap();
 
-    assert_eq!(fn_called, 1, "wish_repo.find_one_expanded() should be called once");
+        let fn_called = locked_reporter.get_fn_calls("wish_repo.find_one_expanded");
 
-    locked_reporter.clear();
+        assert_eq!(fn_called, 1, "wish_repo.find_one_expanded() should be called once");
 
-    drop(locked_reporter);
+        locked_reporter.clear();
+    }
 
     // run 2
 
@@ -362,15 +405,15 @@ async fn show_wish() {
 
     // ---
 
-    let mut locked_reporter = reporter.lock().unwrap();
+    {
+        let mut locked_reporter = reporter.lock().unwrap();
 
-    let fn_called = locked_reporter.get_fn_calls("wish_repo.find_one_expanded");
+        let fn_called = locked_reporter.get_fn_calls("wish_repo.find_one_expanded");
 
-    assert_eq!(fn_called, 0, "wish_repo.find_one_expanded() shouldn't be called");
+        assert_eq!(fn_called, 0, "wish_repo.find_one_expanded() shouldn't be called");
 
-    locked_reporter.clear();
-
-    drop(locked_reporter);
+        locked_reporter.clear();
+    }
 
     // run 3
 
@@ -391,13 +434,305 @@ async fn show_wish() {
 
     // ---
 
-    let mut locked_reporter = reporter.lock().unwrap();
+    {
+        let mut locked_reporter = reporter.lock().unwrap();
+
+        let fn_called = locked_reporter.get_fn_calls("wish_repo.find_one_expanded");
 
-    let fn_called = locked_reporter.get_fn_calls("wish_repo.find_one_expanded");
+        assert_eq!(fn_called, 1, "wish_repo.find_one_expanded() should be called once");
 
-    assert_eq!(fn_called, 1, "wish_repo.find_one_expanded() should be called once");
+        locked_reporter.clear();
+    }
+}
+
+#[tokio::test]
+async fn reorder_wishes() {
+    // -- setup
+
+    let wishes = vec![
+        Wish { id: 1, wishlist_id: 1, product_id: 1, pending: true, position: 0, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+        Wish { id: 2, wishlist_id: 1, product_id: 2, pending: true, position: 1, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+        Wish { id: 3, wishlist_id: 1, product_id: 3, pending: true, position: 2, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+    ];
+
+    let wishlist = Wishlist {
+        id: 1,
+        title: "Wishlist title".to_string(),
+        description: None,
+        date: None,
+        user_id: 2,
+        visibility: Visibility::Private,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let service_data = ServiceData::default()
+        .wishlists(vec![wishlist])
+        .wishes(wishes);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_user, auth_token) = get_user_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
+    // -- run 1
+
+    let response = &mut TestClient::put(format!("{BASE_URL}/wishlists/1/wishes/order"))
+        .add_header("authorization", &bearer, true)
+        .form(&[("wish_ids", "3,1,2")])
+        .send(&target)
+        .await;
 
-    locked_reporter.clear();
+    let status_code = response.status_code.unwrap();
+
+    let parsed_response = response.take_json::<ResourceResponse<Vec<WishProduct>>>()
+        .await
+        .unwrap();
+
+    let reorder_calls = reporter.lock().unwrap().get_fn_calls("wish_repo.reorder");
+
+    // -- assert 1
+
+    assert_eq!(status_code, 202, "status code should be 202");
+    assert_eq!(reorder_calls, 1, "wish_repo.reorder() should be called once");
+    assert_eq!(parsed_response.data.iter().map(|w| w.id).collect::<Vec<i32>>(), vec![3, 1, 2], "the wishes should be returned in the new order");
+
+    reporter.lock().unwrap().clear();
+
+    // -- run 2
+
+    let response = TestClient::put(format!("{BASE_URL}/wishlists/1/wishes/order"))
+        .add_header("authorization", &bearer, true)
+        .form(&[("wish_ids", "3,1,4")])
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let reorder_calls = reporter.lock().unwrap().get_fn_calls("wish_repo.reorder");
+
+    // -- assert 2
+
+    assert_eq!(status_code, 400, "status code should be 400 for a foreign wish");
+    assert_eq!(reorder_calls, 0, "wish_repo.reorder() shouldn't be called");
+
+    // -- run 3
+
+    let (_, auth_admin_token) = get_admin_and_token();
+
+    let response = TestClient::put(format!("{BASE_URL}/wishlists/1/wishes/order"))
+        .add_header("authorization", format!("Bearer {auth_admin_token}"), true)
+        .form(&[("wish_ids", "3,1,2")])
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    // -- assert 3
+
+    assert_eq!(status_code, 403, "status code should be 403 for a non owner");
+}
+
+#[tokio::test]
+async fn clone_wishlist() {
+    // -- setup
+
+    let private_wishlist = Wishlist {
+        id: 1,
+        title: "Private wishlist".to_string(),
+        description: None,
+        date: None,
+        user_id: 1,
+        visibility: Visibility::Private,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let published_wishlist = Wishlist {
+        id: 2,
+        title: "Published wishlist".to_string(),
+        description: Some("Birthday".to_string()),
+        date: None,
+        user_id: 1,
+        visibility: Visibility::Public,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let wishes = vec![
+        Wish { id: 1, wishlist_id: 2, product_id: 1, pending: false, position: 0, original_price: 2000.0, created_at: timestamp(), updated_at: timestamp() },
+    ];
 
-    drop(locked_reporter);
-}
\ No newline at end of file
+    let service_data = ServiceData::default()
+        .wishlists(vec![private_wishlist, published_wishlist])
+        .wishes(wishes);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (user, auth_token) = get_user_and_token();
+
+    let bearer = format!("Bearer {auth_token}");
+
+    // -- run 1
+
+    let response = TestClient::post(format!("{BASE_URL}/wishlists/1/clone"))
+        .add_header("authorization", &bearer, true)
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let insert_calls = reporter.lock().unwrap().get_fn_calls("wishlist_repo.insert_with_products");
+
+    // -- assert 1
+
+    assert_eq!(status_code, 403, "cloning an unpublished foreign wishlist is forbidden");
+    assert_eq!(insert_calls, 0, "wishlist_repo.insert_with_products() shouldn't be called");
+
+    // -- run 2
+
+    let response = &mut TestClient::post(format!("{BASE_URL}/wishlists/2/clone"))
+        .add_header("authorization", &bearer, true)
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let cloned = response.take_json::<ResourceResponse<Wishlist>>()
+        .await
+        .unwrap()
+        .data;
+
+    let insert_calls = reporter.lock().unwrap().get_fn_calls("wishlist_repo.insert_with_products");
+
+    // -- assert 2
+
+    assert_eq!(status_code, 202, "status code should be 202");
+    assert_eq!(insert_calls, 1, "wishlist_repo.insert_with_products() should be called once");
+    assert_eq!(cloned.user_id, user.id, "clone should belong to the caller");
+    assert_eq!(cloned.visibility, Visibility::Private, "clone should be private");
+    assert_eq!(cloned.date, None, "clone should reset the date");
+
+    // -- run 3
+
+    let (_, auth_admin_token) = get_admin_and_token();
+
+    let response = TestClient::post(format!("{BASE_URL}/wishlists/1/clone"))
+        .add_header("authorization", format!("Bearer {auth_admin_token}"), true)
+        .send(&target)
+        .await;
+
+    // -- assert 3
+
+    assert_eq!(response.status_code.unwrap(), 202, "the owner can clone an unpublished wishlist");
+}
+
+#[tokio::test]
+async fn wishlist_visibility() {
+    // -- setup
+
+    let wishlist = |id: i32, visibility: Visibility| Wishlist {
+        id,
+        title: format!("Wishlist {id}"),
+        description: None,
+        date: None,
+        user_id: 1,
+        visibility,
+        created_at: timestamp(),
+        updated_at: timestamp(),
+    };
+
+    let wishlists = vec![
+        wishlist(1, Visibility::Private),
+        wishlist(2, Visibility::FollowersOnly),
+        wishlist(3, Visibility::FollowersOnly),
+        wishlist(4, Visibility::LinkOnly),
+    ];
+
+    let followers = vec![
+        Follower { id: 1, wishlist_id: 2, user_id: 2, active: true, created_at: timestamp(), updated_at: timestamp() },
+        Follower { id: 2, wishlist_id: 3, user_id: 2, active: false, created_at: timestamp(), updated_at: timestamp() },
+    ];
+
+    let service_data = ServiceData::default()
+        .wishlists(wishlists)
+        .followers(followers);
+
+    let reporter = Arc::new(Mutex::new(Reporter::new()));
+
+    let target = prepare_api_service(service_data, reporter.clone());
+
+    let (_, user_token) = get_user_and_token();
+
+    let (_, owner_token) = get_admin_and_token();
+
+    let user_bearer = format!("Bearer {user_token}");
+
+    let status_for = |id: i32, bearer: String| {
+        let target = &target;
+
+        async move {
+            TestClient::get(format!("{BASE_URL}/wishlists/{id}"))
+                .add_header("authorization", bearer, true)
+                .send(target)
+                .await
+                .status_code
+                .unwrap()
+        }
+    };
+
+    // -- run 1
+
+    let private_status = status_for(1, user_bearer.clone()).await;
+    let followed_status = status_for(2, user_bearer.clone()).await;
+    let inactive_follower_status = status_for(3, user_bearer.clone()).await;
+    let link_only_status = status_for(4, user_bearer.clone()).await;
+    let owner_status = status_for(1, format!("Bearer {owner_token}")).await;
+
+    // -- assert 1
+
+    assert_eq!(private_status, 403, "a private wishlist is hidden from strangers");
+    assert_eq!(followed_status, 200, "an active follower sees a followers-only wishlist");
+    assert_eq!(inactive_follower_status, 403, "an inactive follower doesn't see a followers-only wishlist");
+    assert_eq!(link_only_status, 403, "a link-only wishlist is hidden without the link");
+    assert_eq!(owner_status, 200, "the owner sees a private wishlist");
+
+    // -- run 2
+
+    let response = &mut TestClient::put(format!("{BASE_URL}/wishlists/1"))
+        .add_header("authorization", format!("Bearer {owner_token}"), true)
+        .form(&[("visibility", "followers_only")])
+        .send(&target)
+        .await;
+
+    let status_code = response.status_code.unwrap();
+
+    let updated = response.take_json::<ResourceResponse<Wishlist>>()
+        .await
+        .unwrap()
+        .data;
+
+    // -- assert 2
+
+    assert_eq!(status_code, 202, "status code should be 202");
+    assert_eq!(updated.visibility, Visibility::FollowersOnly, "visibility should be updated");
+
+    // -- run 3
+
+    let status_code = TestClient::put(format!("{BASE_URL}/wishlists/1"))
+        .add_header("authorization", format!("Bearer {owner_token}"), true)
+        .form(&[("visibility", "everyone")])
+        .send(&target)
+        .await
+        .status_code
+        .unwrap();
+
+    // -- assert 3
+
+    assert_eq!(status_code, 422, "unknown visibilities are rejected");
+}